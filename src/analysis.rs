@@ -0,0 +1,421 @@
+//! The `analysis` module provides statistics for inspecting ciphertext
+
+use crate::color::{self, Stream, Style};
+use crate::output::OutputError;
+use std::fmt;
+
+/// Relative frequencies (in percent) of the letters `a` to `z` in English text.
+const ENGLISH_FREQUENCIES: [f64; 26] = [
+    8.167, 1.492, 2.782, 4.253, 12.702, 2.228, 2.015, 6.094, 6.966, 0.153, 0.772, 4.025, 2.406,
+    6.749, 7.507, 1.929, 0.095, 5.987, 6.327, 9.056, 2.758, 0.978, 2.360, 0.150, 1.974, 0.074,
+];
+
+/// The most common English bigrams and their frequencies in percent.
+const ENGLISH_BIGRAMS: [(&str, f64); 42] = [
+    ("th", 3.56),
+    ("he", 3.07),
+    ("in", 2.43),
+    ("er", 2.05),
+    ("an", 1.99),
+    ("re", 1.85),
+    ("on", 1.76),
+    ("at", 1.49),
+    ("en", 1.45),
+    ("nd", 1.35),
+    ("ti", 1.34),
+    ("es", 1.34),
+    ("or", 1.28),
+    ("te", 1.20),
+    ("of", 1.17),
+    ("ed", 1.17),
+    ("is", 1.13),
+    ("it", 1.12),
+    ("al", 1.09),
+    ("ar", 1.07),
+    ("st", 1.05),
+    ("to", 1.04),
+    ("nt", 1.04),
+    ("ng", 0.95),
+    ("se", 0.93),
+    ("ha", 0.93),
+    ("as", 0.87),
+    ("ou", 0.87),
+    ("io", 0.83),
+    ("le", 0.83),
+    ("ve", 0.83),
+    ("co", 0.79),
+    ("me", 0.79),
+    ("de", 0.76),
+    ("hi", 0.76),
+    ("ri", 0.73),
+    ("ro", 0.73),
+    ("ic", 0.70),
+    ("ne", 0.69),
+    ("ea", 0.69),
+    ("ra", 0.69),
+    ("ce", 0.65),
+];
+
+/// Number of most frequent letters highlighted in the report.
+const HIGHLIGHTED_LETTERS: usize = 3;
+
+/// Number of most frequent bigrams listed in the report.
+const REPORTED_BIGRAMS: usize = 5;
+
+/// Frequency (in percent) assumed for bigrams missing from `ENGLISH_BIGRAMS`.
+const BIGRAM_FLOOR: f64 = 0.01;
+
+/// Index of coincidence of English text, as used by the Friedman test and `classify`.
+pub const ENGLISH_IOC: f64 = 0.0650;
+
+/// Index of coincidence of uniformly random letters, as used by the Friedman test
+/// and `classify`.
+pub const RANDOM_IOC: f64 = 0.0385;
+
+/// Statistics of a text, gathered in a single pass by `analyze` and shared
+/// by the analyze and classify reports and the scoring of search candidates.
+///
+/// Letters are ASCII letters, counted without regard to case. Bigrams are
+/// pairs of consecutive letters once everything else is removed.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TextStats {
+    /// Number of characters in the text.
+    pub total: usize,
+    /// Number of letters in the text.
+    pub letters: usize,
+    /// How often each letter `a` to `z` appears.
+    pub letter_counts: [usize; 26],
+    /// How often each bigram appears, indexed by its first then its second letter.
+    pub bigram_counts: [[usize; 26]; 26],
+    /// Probability that two letters drawn at random are the same: around
+    /// 0.066 for English and monoalphabetic ciphers, and closer to 0.038
+    /// for uniformly random letters or strong polyalphabetic ciphers; 0.0
+    /// with fewer than two letters.
+    pub index_of_coincidence: f64,
+    /// Shannon entropy of the letters in bits per letter: around 4.1 for
+    /// English, approaching log2(26) ≈ 4.7 for random letters; 0.0 when
+    /// there are no letters.
+    pub entropy: f64,
+    /// Polyalphabetic key length estimated by the Friedman test from the
+    /// index of coincidence; about 1 for English and monoalphabetic
+    /// ciphers, and `None` when the letters are too few or too evenly
+    /// spread for an estimate.
+    pub friedman_estimate: Option<f64>,
+}
+
+/// Gathers the statistics of the text in one pass.
+///
+/// # Arguments
+/// * `text` - A string slice to be analyzed.
+///
+/// # Returns
+/// The `TextStats` of the text.
+///
+/// # Examples
+/// ```
+/// let stats = analyze("abba!");
+/// assert_eq!((stats.total, stats.letters), (5, 4));
+/// ```
+pub fn analyze(text: &str) -> TextStats {
+    let mut total = 0;
+    let mut letter_counts = [0; 26];
+    let mut bigram_counts = [[0; 26]; 26];
+    let mut previous: Option<usize> = None;
+    for c in text.chars() {
+        total += 1;
+        if !c.is_ascii_alphabetic() {
+            continue;
+        }
+        let index = (c.to_ascii_lowercase() as u8 - b'a') as usize;
+        letter_counts[index] += 1;
+        if let Some(previous) = previous {
+            bigram_counts[previous][index] += 1;
+        }
+        previous = Some(index);
+    }
+
+    let letters: usize = letter_counts.iter().sum();
+    let index_of_coincidence = match letters {
+        0 | 1 => 0.0,
+        _ => {
+            let matches: usize = letter_counts
+                .iter()
+                .map(|&count| count * count.saturating_sub(1))
+                .sum();
+            matches as f64 / (letters * (letters - 1)) as f64
+        }
+    };
+    let entropy = letter_counts
+        .iter()
+        .filter(|&&count| count > 0)
+        .map(|&count| {
+            let p = count as f64 / letters as f64;
+            -p * p.log2()
+        })
+        .sum();
+    let n = letters as f64;
+    let denominator = (n - 1.0) * index_of_coincidence - RANDOM_IOC * n + ENGLISH_IOC;
+    let friedman_estimate = match letters >= 2 && denominator > 0.0 {
+        true => Some((ENGLISH_IOC - RANDOM_IOC) * n / denominator),
+        false => None,
+    };
+
+    TextStats {
+        total,
+        letters,
+        letter_counts,
+        bigram_counts,
+        index_of_coincidence,
+        entropy,
+        friedman_estimate,
+    }
+}
+
+/// The lowercase letter at `index` in the alphabet.
+fn letter(index: usize) -> char {
+    (b'a' + index as u8) as char
+}
+
+impl TextStats {
+    /// The letters that appear and their counts, sorted by descending
+    /// count and then alphabetically so ties always come out in the same order.
+    ///
+    /// # Examples
+    /// ```
+    /// let frequencies = analyze("abba").letter_frequencies();
+    /// assert_eq!(frequencies, vec![('a', 2), ('b', 2)]);
+    /// ```
+    pub fn letter_frequencies(&self) -> Vec<(char, usize)> {
+        let mut frequencies: Vec<(char, usize)> = (0..26)
+            .filter(|&i| self.letter_counts[i] > 0)
+            .map(|i| (letter(i), self.letter_counts[i]))
+            .collect();
+        frequencies.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+        frequencies
+    }
+
+    /// The bigrams that appear and their counts, sorted like `letter_frequencies`.
+    pub fn bigram_frequencies(&self) -> Vec<(String, usize)> {
+        let mut frequencies: Vec<(String, usize)> = (0..26 * 26)
+            .map(|i| (i / 26, i % 26))
+            .filter(|&(a, b)| self.bigram_counts[a][b] > 0)
+            .map(|(a, b)| {
+                (
+                    [letter(a), letter(b)].iter().collect(),
+                    self.bigram_counts[a][b],
+                )
+            })
+            .collect();
+        frequencies.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+        frequencies
+    }
+
+    /// Measures how far the letter counts are from English, unshifted.
+    ///
+    /// This is the chi-squared statistic against the expected English counts,
+    /// divided by the number of letters so texts of different lengths compare.
+    /// English text and its transpositions score well under 1, while any
+    /// substitution moves the common letters and scores far higher.
+    ///
+    /// # Returns
+    /// The chi-squared statistic per letter, or 0.0 when there are no letters.
+    pub fn english_chi_squared(&self) -> f64 {
+        if self.letters == 0 {
+            return 0.0;
+        }
+
+        let chi_squared: f64 = self
+            .letter_counts
+            .iter()
+            .zip(ENGLISH_FREQUENCIES)
+            .map(|(&observed, frequency)| {
+                let expected = frequency / 100.0 * self.letters as f64;
+                (observed as f64 - expected).powi(2) / expected
+            })
+            .sum();
+        chi_squared / self.letters as f64
+    }
+
+    /// Scores how much the text resembles English.
+    ///
+    /// The score is the average log10 frequency of its letters plus the
+    /// average log10 frequency of its bigrams. Higher (closer to zero) is
+    /// more English-like.
+    ///
+    /// # Returns
+    /// The score, or negative infinity when the text has no letters.
+    pub fn english_score(&self) -> f64 {
+        if self.letters == 0 {
+            return f64::NEG_INFINITY;
+        }
+
+        let monogram_score: f64 = self
+            .letter_counts
+            .iter()
+            .zip(ENGLISH_FREQUENCIES)
+            .map(|(&count, frequency)| count as f64 * frequency.log10())
+            .sum::<f64>()
+            / self.letters as f64;
+
+        if self.letters < 2 {
+            return monogram_score;
+        }
+
+        // Bigrams missing from the table count at the floor frequency
+        let mut unlisted = self.letters - 1;
+        let mut bigram_score = 0.0;
+        for (bigram, frequency) in ENGLISH_BIGRAMS {
+            let bigram = bigram.as_bytes();
+            let count =
+                self.bigram_counts[(bigram[0] - b'a') as usize][(bigram[1] - b'a') as usize];
+            unlisted -= count;
+            bigram_score += count as f64 * frequency.log10();
+        }
+        let bigram_score =
+            (bigram_score + unlisted as f64 * BIGRAM_FLOOR.log10()) / (self.letters - 1) as f64;
+
+        monogram_score + bigram_score
+    }
+}
+
+/// Lays out the statistics as the analyze report, highlighting the most
+/// frequent letters when stdout is colored.
+impl fmt::Display for TextStats {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "Characters: {} ({} letters)", self.total, self.letters)?;
+        writeln!(f, "Index of coincidence: {:.4}", self.index_of_coincidence)?;
+        writeln!(f, "Entropy: {:.4} bits/char", self.entropy)?;
+        match self.friedman_estimate {
+            Some(estimate) => writeln!(f, "Friedman key length estimate: {:.2}", estimate)?,
+            None => writeln!(f, "Friedman key length estimate: n/a")?,
+        }
+        write!(f, "Letter frequencies:")?;
+        for (rank, (letter, count)) in self.letter_frequencies().into_iter().enumerate() {
+            let line = format!(
+                "{}: {} ({:.2}%)",
+                letter,
+                count,
+                count as f64 / self.letters as f64 * 100.0
+            );
+            match rank < HIGHLIGHTED_LETTERS {
+                true => write!(f, "\n{}", color::paint(&line, Style::Green, Stream::Stdout))?,
+                false => write!(f, "\n{}", line)?,
+            }
+        }
+        write!(f, "\nTop bigrams:")?;
+        for (bigram, count) in self.bigram_frequencies().into_iter().take(REPORTED_BIGRAMS) {
+            write!(f, "\n{}: {}", bigram, count)?;
+        }
+        Ok(())
+    }
+}
+
+/// Scores how much the text resembles English; see `TextStats::english_score`.
+pub fn english_score(text: &str) -> f64 {
+    analyze(text).english_score()
+}
+
+/// Prints the analysis report for the given text.
+pub fn print_report(text: &str) -> Result<(), OutputError> {
+    outln!("{}", analyze(text))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_letter_frequencies_tie_break() {
+        let frequencies = analyze("zzyyX aab-c!").letter_frequencies();
+
+        assert_eq!(
+            frequencies,
+            vec![('a', 2), ('y', 2), ('z', 2), ('b', 1), ('c', 1), ('x', 1)]
+        );
+    }
+
+    #[test]
+    fn test_index_of_coincidence() {
+        let uniform = analyze("abcdefghijklmnopqrstuvwxyz").index_of_coincidence;
+        let repeated = analyze("aaaa").index_of_coincidence;
+
+        assert_eq!(uniform, 0.0);
+        assert_eq!(repeated, 1.0);
+    }
+
+    #[test]
+    fn test_shannon_entropy() {
+        let uniform = analyze("qwertyuiopasdfghjklzxcvbnmmnbvcxzlkjhgfdsapoiuytrewq").entropy;
+        let english = analyze(
+            "the quick brown fox jumps over the lazy dog and then sleeps all afternoon in the sun",
+        )
+        .entropy;
+        let single = analyze("aaaa").entropy;
+
+        assert!((uniform - 26f64.log2()).abs() < 1e-9);
+        assert!(english < uniform);
+        assert_eq!(single, 0.0);
+    }
+
+    #[test]
+    fn test_english_chi_squared() {
+        let english =
+            analyze("the enemy is moving their troops to the river at night").english_chi_squared();
+        let shifted =
+            analyze("wkh hqhpb lv prylqj wkhlu wursv wr wkh ulyhu dw qljkw").english_chi_squared();
+
+        assert!(english < 1.0);
+        assert!(shifted > english * 5.0);
+        assert_eq!(analyze("123").english_chi_squared(), 0.0);
+    }
+
+    #[test]
+    fn test_english_score() {
+        let english = english_score("the enemy is moving their troops to the river at night");
+        let gibberish = english_score("xqzvj kwpfq zzxjq vkqwz jxqvz pqkzw xjvqz kqxzv");
+
+        assert!(english > gibberish);
+        assert_eq!(english_score("123"), f64::NEG_INFINITY);
+    }
+
+    #[test]
+    fn test_analyze_known_corpus() {
+        let stats = analyze("The quick brown fox jumps over the lazy dog");
+
+        assert_eq!(stats.total, 43);
+        assert_eq!(stats.letters, 35);
+        assert_eq!(
+            stats.letter_frequencies()[..3],
+            [('o', 4), ('e', 3), ('h', 2)]
+        );
+        assert_eq!(
+            stats.bigram_frequencies()[..3],
+            [
+                ("he".to_string(), 2),
+                ("th".to_string(), 2),
+                ("az".to_string(), 1)
+            ]
+        );
+        // "the" ends one word and "quick" starts the next
+        assert_eq!(stats.bigram_counts[4][16], 1);
+        assert!((stats.index_of_coincidence - 26.0 / 1190.0).abs() < 1e-12);
+        assert!((stats.entropy - 4.5363).abs() < 1e-4);
+        // A pangram is flatter than random letters, so there is no estimate
+        assert_eq!(stats.friedman_estimate, None);
+
+        let english = analyze("the enemy is moving their troops to the river at night");
+        assert!((english.friedman_estimate.unwrap() - 0.7295).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_report_layout() {
+        let report = analyze("abba").to_string();
+
+        assert!(report.starts_with("Characters: 4 (4 letters)\nIndex of coincidence: 0.3333\n"));
+        assert!(report.contains("Friedman key length estimate: 0.12\n"));
+        assert!(
+            report.contains("\na: 2 (50.00%)\nb: 2 (50.00%)\nTop bigrams:\nab: 1\nba: 1\nbb: 1")
+        );
+    }
+}
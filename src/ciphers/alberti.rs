@@ -0,0 +1,168 @@
+//! The `alberti` module provides an implementation of the Alberti cipher disk
+
+use super::alphabet::Alphabet;
+use super::Cipher;
+
+/// An `AlbertiCipher` represents the Alberti cipher disk.
+/// A movable inner disk holding a mixed alphabet turns against a fixed
+/// outer ring holding A-Z. Plaintext letters are read on the outer ring and
+/// replaced by the lowercase letter under them on the inner disk. Every
+/// `period` letters the disk turns one step and the capital letter of the
+/// outer ring now above the index letter is written into the ciphertext,
+/// so the recipient can turn their disk to match
+pub struct AlbertiCipher {
+    pub disk: Alphabet,
+    pub index: usize,
+    pub period: usize,
+}
+
+impl AlbertiCipher {
+    /// Creates an Alberti cipher.
+    ///
+    /// # Arguments
+    /// * `keyword` - Keyword that mixes the alphabet of the inner disk.
+    /// * `index_letter` - The single letter on the inner disk whose position
+    ///   the capital letters in the ciphertext announce.
+    /// * `period` - Number of letters enciphered before the disk turns.
+    ///
+    /// # Returns
+    /// An `AlbertiCipher`, or an error if the index isn't a single letter
+    /// or the period is zero.
+    pub fn new(keyword: &str, index_letter: &str, period: usize) -> Result<AlbertiCipher, String> {
+        let disk = Alphabet::keyed(keyword);
+        let index = match index_letter.chars().collect::<Vec<char>>().as_slice() {
+            [c] if c.is_ascii_alphabetic() => disk.index_of(c.to_ascii_uppercase()).unwrap(),
+            _ => return Err("Alberti index must be a single letter.".to_string()),
+        };
+        if period == 0 {
+            return Err("Alberti rotation period must be positive.".to_string());
+        }
+
+        Ok(AlbertiCipher {
+            disk,
+            index,
+            period,
+        })
+    }
+
+    /// The outer ring position the index letter starts under: its own letter.
+    fn initial_alignment(&self) -> usize {
+        (self.disk.char_at(self.index) as u8 - b'A') as usize
+    }
+}
+
+impl Cipher for AlbertiCipher {
+    fn name(&self) -> &'static str {
+        "Alberti cipher"
+    }
+
+    /// Encrypts the given plaintext string slice through the turning disk.
+    ///
+    /// Letters become lowercase and a capital is written before every
+    /// group of `period` letters to announce the disk's new position.
+    /// Other characters are left in place.
+    ///
+    /// # Arguments
+    /// * `plaintext` - A string slice that holds the text to be encrypted.
+    ///
+    /// # Returns
+    /// A `String` containing the encrypted text.
+    fn encrypt(&self, plaintext: &str) -> String {
+        let mut alignment = self.initial_alignment();
+        let mut letter_index = 0;
+        let mut ciphertext =
+            String::with_capacity(plaintext.len() + plaintext.len() / self.period + 1);
+
+        for c in plaintext.chars() {
+            if !c.is_ascii_alphabetic() {
+                ciphertext.push(c);
+                continue;
+            }
+            if letter_index % self.period == 0 {
+                if letter_index > 0 {
+                    alignment = (alignment + 1) % 26;
+                }
+                ciphertext.push((b'A' + alignment as u8) as char);
+            }
+            letter_index += 1;
+
+            let plain = (c.to_ascii_uppercase() as u8 - b'A') as usize;
+            let inner = (plain + 26 - alignment + self.index) % 26;
+            ciphertext.push(self.disk.char_at(inner).to_ascii_lowercase());
+        }
+
+        ciphertext
+    }
+
+    /// Decrypts the given ciphertext string slice, turning the disk to
+    /// every capital letter met along the way.
+    ///
+    /// # Arguments
+    /// * `ciphertext` - A string slice that holds the text to be decrypted.
+    ///
+    /// # Returns
+    /// A `String` containing the decrypted text in capitals, as read off the outer ring.
+    fn decrypt(&self, ciphertext: &str) -> String {
+        let mut alignment = self.initial_alignment();
+
+        ciphertext
+            .chars()
+            .filter_map(|c| {
+                if c.is_ascii_uppercase() {
+                    alignment = (c as u8 - b'A') as usize;
+                    return None;
+                }
+                let inner = match c.is_ascii_lowercase() {
+                    true => self.disk.index_of(c.to_ascii_uppercase()).unwrap(),
+                    false => return Some(c),
+                };
+                let plain = (inner + 26 - self.index + alignment) % 26;
+                Some((b'A' + plain as u8) as char)
+            })
+            .collect()
+    }
+
+    /// A capital is inserted every `period` letters.
+    fn preserves_length(&self) -> bool {
+        false
+    }
+
+    /// Only ASCII letters are enciphered.
+    fn transforms(&self, c: char) -> bool {
+        c.is_ascii_alphabetic()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_alberti_straight_disk() {
+        let cipher = AlbertiCipher::new("", "a", 4).unwrap();
+
+        assert_eq!(cipher.encrypt("abcdefgh"), "AabcdBdefg");
+        assert_eq!(cipher.decrypt("AabcdBdefg"), "ABCDEFGH");
+    }
+
+    #[test]
+    fn test_alberti_round_trip() {
+        let cipher = AlbertiCipher::new("cipher", "k", 3).unwrap();
+        let ciphertext = cipher.encrypt("Attack at dawn!");
+        let markers = ciphertext
+            .chars()
+            .filter(|c| c.is_ascii_uppercase())
+            .count();
+
+        assert_eq!(markers, 4);
+        assert!(ciphertext.starts_with('K'));
+        assert_eq!(cipher.decrypt(&ciphertext), "ATTACK AT DAWN!");
+    }
+
+    #[test]
+    fn test_alberti_rejects_bad_index() {
+        assert!(AlbertiCipher::new("cipher", "kk", 3).is_err());
+        assert!(AlbertiCipher::new("cipher", "7", 3).is_err());
+        assert!(AlbertiCipher::new("cipher", "k", 0).is_err());
+    }
+}
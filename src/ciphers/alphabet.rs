@@ -0,0 +1,282 @@
+//! The `alphabet` module provides user-defined character sets for the ciphers
+
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::str::FromStr;
+
+/// An `AlphabetIndex` maps characters to their position in an alphabet in O(1).
+///
+/// ASCII characters are looked up in a flat table, anything else in a `HashMap`,
+/// so custom-alphabet ciphers avoid a linear search per character.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AlphabetIndex {
+    ascii: Box<[Option<usize>; 128]>,
+    other: HashMap<char, usize>,
+}
+
+impl AlphabetIndex {
+    /// Precomputes the position of every character.
+    pub fn new(chars: &[char]) -> AlphabetIndex {
+        let mut index = AlphabetIndex {
+            ascii: Box::new([None; 128]),
+            other: HashMap::new(),
+        };
+        for (position, &c) in chars.iter().enumerate() {
+            match c.is_ascii() {
+                true => index.ascii[c as usize] = Some(position),
+                false => {
+                    index.other.insert(c, position);
+                }
+            }
+        }
+        index
+    }
+
+    /// Position of `c`, if it is indexed.
+    pub fn get(&self, c: char) -> Option<usize> {
+        match c.is_ascii() {
+            true => self.ascii[c as usize],
+            false => self.other.get(&c).copied(),
+        }
+    }
+}
+
+/// The 24 capital letters of the Greek alphabet, in order.
+pub const GREEK: &str = "ΑΒΓΔΕΖΗΘΙΚΛΜΝΞΟΠΡΣΤΥΦΧΨΩ";
+
+/// The 33 capital letters of the Russian alphabet, in order.
+///
+/// Ё is a letter of its own between Е and Ж, as in the official alphabet,
+/// rather than a variant of Е, so text written with Ё round-trips; text
+/// that writes Е for it is unaffected.
+pub const CYRILLIC: &str = "АБВГДЕЁЖЗИЙКЛМНОПРСТУФХЦЧШЩЪЫЬЭЮЯ";
+
+/// An `Alphabet` is an ordered set of distinct characters that a cipher
+/// rotates within. Characters outside the alphabet are left unchanged.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Alphabet {
+    chars: Vec<char>,
+    index: AlphabetIndex,
+    // lowercase letters are looked up by their capital and keep their case
+    fold_case: bool,
+    // the name a built-in alphabet is given by on the command line
+    name: Option<&'static str>,
+}
+
+impl Alphabet {
+    /// Number of characters in the alphabet.
+    pub fn len(&self) -> usize {
+        self.chars.len()
+    }
+
+    /// Position of `c` in the alphabet, if it belongs to it.
+    pub fn index_of(&self, c: char) -> Option<usize> {
+        self.index.get(c)
+    }
+
+    /// Whether lowercase letters belong to the alphabet through their capitals.
+    pub fn folds_case(&self) -> bool {
+        self.fold_case
+    }
+
+    /// Character at `index`, wrapping around the end of the alphabet.
+    pub fn char_at(&self, index: usize) -> char {
+        self.chars[index % self.chars.len()]
+    }
+
+    /// Builds an uppercase A-Z alphabet that starts with the keyword's letters,
+    /// without repeats, followed by the remaining letters in order.
+    ///
+    /// # Examples
+    /// ```
+    /// let alphabet = Alphabet::keyed("key");
+    /// assert_eq!(alphabet.char_at(3), 'A');
+    /// ```
+    pub fn keyed(keyword: &str) -> Alphabet {
+        let mut seen = HashSet::new();
+        let chars: Vec<char> = keyword
+            .chars()
+            .filter(|c| c.is_ascii_alphabetic())
+            .map(|c| c.to_ascii_uppercase())
+            .chain('A'..='Z')
+            .filter(|&c| seen.insert(c))
+            .collect();
+
+        let index = AlphabetIndex::new(&chars);
+        Alphabet {
+            chars,
+            index,
+            fold_case: false,
+            name: None,
+        }
+    }
+
+    /// Builds the 24-letter Greek alphabet, whose lowercase letters rotate
+    /// with their capitals and keep their case.
+    pub fn greek() -> Alphabet {
+        Alphabet::built_in("greek", GREEK)
+    }
+
+    /// Builds the 33-letter Russian alphabet, whose lowercase letters rotate
+    /// with their capitals and keep their case.
+    pub fn cyrillic() -> Alphabet {
+        Alphabet::built_in("cyrillic", CYRILLIC)
+    }
+
+    /// Builds a named alphabet of capitals that folds case.
+    fn built_in(name: &'static str, capitals: &str) -> Alphabet {
+        let chars: Vec<char> = capitals.chars().collect();
+        let index = AlphabetIndex::new(&chars);
+        Alphabet {
+            chars,
+            index,
+            fold_case: true,
+            name: Some(name),
+        }
+    }
+}
+
+impl fmt::Display for Alphabet {
+    /// Writes the alphabet the way `from_str` parses it back.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.name {
+            Some(name) => write!(f, "{}", name),
+            None => write!(f, "{}", self.chars.iter().collect::<String>()),
+        }
+    }
+}
+
+impl FromStr for Alphabet {
+    type Err = String;
+
+    /// Parses an alphabet from its characters in order, or `greek` or
+    /// `cyrillic` for the built-in Greek and Russian alphabets.
+    ///
+    /// Fails if the alphabet is empty or repeats a character.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "greek" => return Ok(Alphabet::greek()),
+            "cyrillic" => return Ok(Alphabet::cyrillic()),
+            _ => {}
+        }
+
+        let chars: Vec<char> = s.chars().collect();
+        if chars.is_empty() {
+            return Err("alphabet must not be empty".to_string());
+        }
+
+        let mut seen = HashSet::new();
+        if let Some(duplicate) = chars.iter().find(|&&c| !seen.insert(c)) {
+            return Err(format!("alphabet contains '{}' more than once", duplicate));
+        }
+
+        let index = AlphabetIndex::new(&chars);
+        Ok(Alphabet {
+            chars,
+            index,
+            fold_case: false,
+            name: None,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Instant;
+
+    #[test]
+    fn test_keyed_alphabet() {
+        let alphabet = Alphabet::keyed("Spring fever");
+        let expected: Alphabet = "SPRINGFEVABCDHJKLMOQTUWXYZ".parse().unwrap();
+
+        assert_eq!(alphabet, expected);
+    }
+
+    #[test]
+    fn test_alphabet_from_str() {
+        let alphabet: Alphabet = "0123456789".parse().unwrap();
+
+        assert_eq!(alphabet.len(), 10);
+        assert_eq!(alphabet.index_of('7'), Some(7));
+        assert_eq!(alphabet.index_of('a'), None);
+        assert_eq!(alphabet.char_at(12), '2');
+    }
+
+    #[test]
+    fn test_alphabet_rejects_duplicates() {
+        let duplicate = "abca".parse::<Alphabet>();
+        let empty = "".parse::<Alphabet>();
+
+        assert_eq!(
+            duplicate,
+            Err("alphabet contains 'a' more than once".to_string())
+        );
+        assert!(empty.is_err());
+    }
+
+    #[test]
+    fn test_greek_alphabet() {
+        let alphabet: Alphabet = "greek".parse().unwrap();
+
+        assert_eq!(alphabet.len(), 24);
+        assert_eq!(alphabet.index_of('Σ'), Some(17));
+        assert_eq!(alphabet.index_of('σ'), None);
+        assert!(alphabet.folds_case());
+        assert_eq!(alphabet.to_string(), "greek");
+        assert_eq!(
+            Alphabet::keyed("key").to_string(),
+            "KEYABCDFGHIJLMNOPQRSTUVWXZ"
+        );
+        assert!(!"abc".parse::<Alphabet>().unwrap().folds_case());
+    }
+
+    #[test]
+    fn test_cyrillic_alphabet() {
+        let alphabet: Alphabet = "cyrillic".parse().unwrap();
+
+        assert_eq!(alphabet.len(), 33);
+        assert_eq!(alphabet.index_of('Ё'), Some(6));
+        assert_eq!(alphabet.index_of('Я'), Some(32));
+        assert!(alphabet.folds_case());
+        assert_eq!(alphabet.to_string(), "cyrillic");
+    }
+
+    #[test]
+    fn test_alphabet_index_non_ascii() {
+        let alphabet: Alphabet = "aéß€".parse().unwrap();
+
+        assert_eq!(alphabet.index_of('a'), Some(0));
+        assert_eq!(alphabet.index_of('€'), Some(3));
+        assert_eq!(alphabet.index_of('b'), None);
+    }
+
+    /// Compares a linear `position` search with the `AlphabetIndex` lookup.
+    /// Run with `cargo test --release -- --ignored --nocapture`.
+    #[test]
+    #[ignore]
+    fn bench_alphabet_index() {
+        let chars: Vec<char> = ('a'..='z').chain('0'..='9').collect();
+        let index = AlphabetIndex::new(&chars);
+        let input: Vec<char> = "the 36 character alphabet benchmark 0123456789 "
+            .chars()
+            .cycle()
+            .take(5_000_000)
+            .collect();
+
+        let start = Instant::now();
+        let linear: usize = input
+            .iter()
+            .filter_map(|&c| chars.iter().position(|&a| a == c))
+            .sum();
+        let linear_time = start.elapsed();
+
+        let start = Instant::now();
+        let indexed: usize = input.iter().filter_map(|&c| index.get(c)).sum();
+        let indexed_time = start.elapsed();
+
+        println!("linear search: {:?}", linear_time);
+        println!("indexed lookup: {:?}", indexed_time);
+        assert_eq!(linear, indexed);
+    }
+}
@@ -0,0 +1,257 @@
+//! The `bazeries` module provides an implementation of the Bazeries cipher
+
+use super::Cipher;
+use std::collections::HashSet;
+
+/// The 25 letters of the squares, with `J` merged into `I`.
+const SQUARE_LETTERS: &str = "ABCDEFGHIKLMNOPQRSTUVWXYZ";
+
+/// A `BazeriesCipher` represents the Bazeries cipher.
+/// The letters are split into groups sized by the successive digits of the
+/// numeric key and each group is reversed. Every letter is then found in a
+/// plain square holding the alphabet down its columns and replaced by the
+/// letter in the same cell of a square keyed by the number spelled out in
+/// words. Other characters are dropped and `J` is read as `I`
+pub struct BazeriesCipher {
+    pub key: u32,
+    pub square: Vec<char>,
+}
+
+/// Spells a number out in English words, without "and".
+///
+/// # Examples
+/// ```
+/// assert_eq!(spell_number(3752), "three thousand seven hundred fifty two");
+/// ```
+pub fn spell_number(number: u32) -> String {
+    const ONES: [&str; 20] = [
+        "zero",
+        "one",
+        "two",
+        "three",
+        "four",
+        "five",
+        "six",
+        "seven",
+        "eight",
+        "nine",
+        "ten",
+        "eleven",
+        "twelve",
+        "thirteen",
+        "fourteen",
+        "fifteen",
+        "sixteen",
+        "seventeen",
+        "eighteen",
+        "nineteen",
+    ];
+    const TENS: [&str; 10] = [
+        "", "", "twenty", "thirty", "forty", "fifty", "sixty", "seventy", "eighty", "ninety",
+    ];
+    const SCALES: [(u32, &str); 3] = [
+        (1_000_000_000, "billion"),
+        (1_000_000, "million"),
+        (1_000, "thousand"),
+    ];
+
+    fn below_thousand(number: u32, words: &mut Vec<&'static str>) {
+        if number >= 100 {
+            words.push(ONES[(number / 100) as usize]);
+            words.push("hundred");
+        }
+        match number % 100 {
+            0 => {}
+            n if n < 20 => words.push(ONES[n as usize]),
+            n => {
+                words.push(TENS[(n / 10) as usize]);
+                if n % 10 != 0 {
+                    words.push(ONES[(n % 10) as usize]);
+                }
+            }
+        }
+    }
+
+    if number == 0 {
+        return ONES[0].to_string();
+    }
+    let mut words = Vec::new();
+    let mut rest = number;
+    for (scale, name) in SCALES {
+        if rest >= scale {
+            below_thousand(rest / scale, &mut words);
+            words.push(name);
+            rest %= scale;
+        }
+    }
+    below_thousand(rest, &mut words);
+    words.join(" ")
+}
+
+impl BazeriesCipher {
+    /// Creates a Bazeries cipher.
+    ///
+    /// # Arguments
+    /// * `key` - A positive number whose digits size the reversed groups
+    ///   and whose spelling keys the cipher square.
+    ///
+    /// # Returns
+    /// A `BazeriesCipher`, or an error if the key has no nonzero digit.
+    pub fn new(key: u32) -> Result<BazeriesCipher, String> {
+        if key == 0 {
+            return Err("Bazeries key must be a positive number.".to_string());
+        }
+
+        let mut seen = HashSet::new();
+        let square = spell_number(key)
+            .chars()
+            .filter(|c| c.is_ascii_alphabetic())
+            .map(|c| match c.to_ascii_uppercase() {
+                'J' => 'I',
+                c => c,
+            })
+            .chain(SQUARE_LETTERS.chars())
+            .filter(|&c| seen.insert(c))
+            .collect();
+
+        Ok(BazeriesCipher { key, square })
+    }
+
+    /// Sizes of the groups the letters are split into, cycling through
+    /// the key's nonzero digits.
+    fn group_sizes(&self) -> impl Iterator<Item = usize> {
+        let digits: Vec<usize> = self
+            .key
+            .to_string()
+            .chars()
+            .filter_map(|c| c.to_digit(10))
+            .filter(|&d| d > 0)
+            .map(|d| d as usize)
+            .collect();
+        digits.into_iter().cycle()
+    }
+
+    /// Reverses every group of the letters in place.
+    fn reverse_groups(&self, letters: &mut [char]) {
+        let mut start = 0;
+        for size in self.group_sizes() {
+            if start >= letters.len() {
+                break;
+            }
+            let end = (start + size).min(letters.len());
+            letters[start..end].reverse();
+            start = end;
+        }
+    }
+
+    /// Uppercases the letters of the text, reading `J` as `I` and dropping the rest.
+    fn letters(text: &str) -> Vec<char> {
+        text.chars()
+            .filter(|c| c.is_ascii_alphabetic())
+            .map(|c| match c.to_ascii_uppercase() {
+                'J' => 'I',
+                c => c,
+            })
+            .collect()
+    }
+
+    /// Cell of the plain square holding `c`, as a row-major index.
+    fn plain_cell(c: char) -> usize {
+        let index = SQUARE_LETTERS.find(c).unwrap();
+        (index % 5) * 5 + index / 5
+    }
+
+    /// Letter in the plain square's row-major `cell`.
+    fn plain_letter(cell: usize) -> char {
+        SQUARE_LETTERS.as_bytes()[(cell % 5) * 5 + cell / 5] as char
+    }
+}
+
+impl Cipher for BazeriesCipher {
+    fn name(&self) -> &'static str {
+        "Bazeries cipher"
+    }
+
+    /// Encrypts the given plaintext string slice by reversing the letter
+    /// groups and then substituting through the squares.
+    ///
+    /// # Arguments
+    /// * `plaintext` - A string slice that holds the text to be encrypted.
+    ///
+    /// # Returns
+    /// A `String` of uppercase letters containing the encrypted text.
+    fn encrypt(&self, plaintext: &str) -> String {
+        let mut letters = Self::letters(plaintext);
+        self.reverse_groups(&mut letters);
+        letters
+            .into_iter()
+            .map(|c| self.square[Self::plain_cell(c)])
+            .collect()
+    }
+
+    /// Decrypts the given ciphertext string slice by substituting back
+    /// through the squares and reversing the letter groups again.
+    ///
+    /// # Arguments
+    /// * `ciphertext` - A string slice that holds the text to be decrypted.
+    ///
+    /// # Returns
+    /// A `String` of uppercase letters containing the decrypted text.
+    fn decrypt(&self, ciphertext: &str) -> String {
+        let mut letters: Vec<char> = Self::letters(ciphertext)
+            .into_iter()
+            .map(|c| {
+                let cell = self.square.iter().position(|&s| s == c).unwrap();
+                Self::plain_letter(cell)
+            })
+            .collect();
+        self.reverse_groups(&mut letters);
+        letters.into_iter().collect()
+    }
+
+    /// Anything that isn't a letter is dropped.
+    fn preserves_length(&self) -> bool {
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_spell_number() {
+        assert_eq!(spell_number(0), "zero");
+        assert_eq!(spell_number(3752), "three thousand seven hundred fifty two");
+        assert_eq!(spell_number(1_000_013), "one million thirteen");
+        assert_eq!(spell_number(81_400), "eighty one thousand four hundred");
+    }
+
+    #[test]
+    fn test_bazeries_square() {
+        let cipher = BazeriesCipher::new(3752).unwrap();
+        let square: String = cipher.square.iter().collect();
+
+        assert_eq!(square, "THREOUSANDVFIYWBCGKLMPQXZ");
+    }
+
+    #[test]
+    fn test_bazeries_encrypt() {
+        let cipher = BazeriesCipher::new(3752).unwrap();
+
+        assert_eq!(cipher.encrypt("hello"), "RMFGR");
+        assert_eq!(cipher.decrypt("RMFGR"), "HELLO");
+    }
+
+    #[test]
+    fn test_bazeries_round_trip() {
+        let cipher = BazeriesCipher::new(3752).unwrap();
+        let plaintext = "SIMPLESUBSTITUTIONPLUSTRANSPOSITION";
+        let ciphertext = cipher.encrypt(plaintext);
+
+        assert_ne!(ciphertext, plaintext);
+        assert_eq!(cipher.decrypt(&ciphertext), plaintext);
+        assert_eq!(cipher.decrypt(&cipher.encrypt("Jump, now!")), "IUMPNOW");
+        assert!(BazeriesCipher::new(0).is_err());
+    }
+}
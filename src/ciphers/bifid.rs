@@ -0,0 +1,155 @@
+//! The `bifid` module provides an implementation of Delastelle's Bifid cipher
+
+use super::Cipher;
+use std::collections::HashSet;
+
+/// A `BifidCipher` represents Delastelle's Bifid cipher.
+/// Each letter sits in a keyed 5x5 Polybius square (J shares I's cell) and
+/// is split into its row and column. Within every block of `period` letters
+/// the rows are written out, then the columns, and read back in pairs
+pub struct BifidCipher {
+    square: Vec<char>,
+    pub period: usize,
+}
+
+impl BifidCipher {
+    /// Creates a Bifid cipher whose square starts with the keyword.
+    ///
+    /// # Arguments
+    /// * `key` - Keyword filling the square before the rest of the alphabet.
+    /// * `period` - Number of letters fractionated together; zero means
+    ///   the whole message is one block.
+    ///
+    /// # Returns
+    /// A `BifidCipher` instance with its square generated.
+    pub fn new(key: &str, period: usize) -> BifidCipher {
+        let mut seen = HashSet::new();
+        let square = key
+            .chars()
+            .filter(|c| c.is_ascii_alphabetic())
+            .map(|c| c.to_ascii_uppercase())
+            .chain('A'..='Z')
+            .map(|c| if c == 'J' { 'I' } else { c })
+            .filter(|&c| seen.insert(c))
+            .collect();
+
+        BifidCipher { square, period }
+    }
+
+    /// Position of the uppercase letter in the square, reading row by row.
+    fn index_of(&self, c: char) -> usize {
+        let c = if c == 'J' { 'I' } else { c };
+        self.square.iter().position(|&s| s == c).unwrap()
+    }
+
+    /// Fractionates the square positions one block at a time.
+    fn transform(&self, indices: &[usize], encrypt: bool) -> Vec<usize> {
+        let period = match self.period {
+            0 => indices.len().max(1),
+            period => period,
+        };
+
+        let mut output = Vec::with_capacity(indices.len());
+        for block in indices.chunks(period) {
+            let n = block.len();
+            match encrypt {
+                true => {
+                    let stream: Vec<usize> = block
+                        .iter()
+                        .map(|i| i / 5)
+                        .chain(block.iter().map(|i| i % 5))
+                        .collect();
+                    output.extend(stream.chunks(2).map(|pair| pair[0] * 5 + pair[1]));
+                }
+                false => {
+                    let stream: Vec<usize> = block.iter().flat_map(|i| [i / 5, i % 5]).collect();
+                    output.extend((0..n).map(|i| stream[i] * 5 + stream[n + i]));
+                }
+            }
+        }
+
+        output
+    }
+
+    /// Applies the cipher to the letters, leaving every other character
+    /// in place and restoring the case of lowercase letters.
+    fn apply(&self, text: &str, encrypt: bool) -> String {
+        let chars: Vec<char> = text.chars().collect();
+        let positions: Vec<usize> = (0..chars.len())
+            .filter(|&i| chars[i].is_ascii_alphabetic())
+            .collect();
+        let indices: Vec<usize> = positions
+            .iter()
+            .map(|&i| self.index_of(chars[i].to_ascii_uppercase()))
+            .collect();
+
+        let mut output = chars.clone();
+        for (&position, index) in positions.iter().zip(self.transform(&indices, encrypt)) {
+            output[position] = match chars[position].is_ascii_lowercase() {
+                true => self.square[index].to_ascii_lowercase(),
+                false => self.square[index],
+            };
+        }
+
+        output.into_iter().collect()
+    }
+}
+
+impl Cipher for BifidCipher {
+    fn name(&self) -> &'static str {
+        "Bifid cipher"
+    }
+
+    /// Encrypts the given plaintext with the Polybius square.
+    ///
+    /// # Arguments
+    /// * `plaintext` - A string slice that holds the text to be encrypted.
+    ///
+    /// # Returns
+    /// A `String` containing the encrypted text.
+    fn encrypt(&self, plaintext: &str) -> String {
+        self.apply(plaintext, true)
+    }
+
+    /// Decrypts the given ciphertext with the Polybius square.
+    ///
+    /// # Arguments
+    /// * `ciphertext` - A string slice that holds the text to be decrypted.
+    ///
+    /// # Returns
+    /// A `String` containing the decrypted text.
+    fn decrypt(&self, ciphertext: &str) -> String {
+        self.apply(ciphertext, false)
+    }
+
+    /// Only ASCII letters are enciphered.
+    fn transforms(&self, c: char) -> bool {
+        c.is_ascii_alphabetic()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bifid_known_vector() {
+        let cipher = BifidCipher::new("BGWKZQPNDSIOAXEFCLUMTHYVR", 0);
+        let ciphertext = cipher.encrypt("FLEEATONCE");
+        let plaintext = cipher.decrypt(&ciphertext);
+
+        assert_eq!(ciphertext, "UAEOLWRINS");
+        assert_eq!(plaintext, "FLEEATONCE");
+    }
+
+    #[test]
+    fn test_bifid_period_round_trip() {
+        let five = BifidCipher::new("keyword", 5);
+        let seven = BifidCipher::new("keyword", 7);
+        let plaintext = "Meet me by the old oak tree";
+
+        assert_ne!(five.encrypt(plaintext), seven.encrypt(plaintext));
+        assert_eq!(five.decrypt(&five.encrypt(plaintext)), plaintext);
+        assert_eq!(seven.decrypt(&seven.encrypt(plaintext)), plaintext);
+    }
+}
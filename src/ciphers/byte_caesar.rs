@@ -0,0 +1,126 @@
+//! The `byte_caesar` module provides a Caesar cipher over byte values,
+//! adding the key modulo 256 to every byte
+
+use super::{ByteCipher, Cipher};
+use crate::encoding;
+use std::io::{self, Write};
+
+/// Bytes shifted per write when streaming; a multiple of 3 so a base64 sink
+/// receives whole groups.
+const STREAM_CHUNK: usize = 3 * 1024;
+
+/// A `ByteCaesarCipher` adds the key to every byte of the input, wrapping
+/// around at 256. As text, the ciphertext is written as lowercase hex
+pub struct ByteCaesarCipher {
+    pub key: u8,
+}
+
+impl ByteCaesarCipher {
+    /// Creates a byte Caesar cipher, reducing the key modulo 256.
+    ///
+    /// # Examples
+    /// ```
+    /// assert_eq!(ByteCaesarCipher::new(-1).key, 255);
+    /// ```
+    pub fn new(key: i32) -> ByteCaesarCipher {
+        ByteCaesarCipher {
+            key: key.rem_euclid(256) as u8,
+        }
+    }
+
+    /// Adds `shift` to every byte, wrapping around at 256.
+    ///
+    /// # Arguments
+    /// * `bytes` - The raw bytes to transform.
+    /// * `shift` - The amount added to each byte.
+    ///
+    /// # Returns
+    /// The transformed bytes.
+    fn shift(bytes: &[u8], shift: u8) -> Vec<u8> {
+        bytes.iter().map(|b| b.wrapping_add(shift)).collect()
+    }
+}
+
+impl ByteCipher for ByteCaesarCipher {
+    fn encrypt_to(&self, bytes: &[u8], sink: &mut dyn Write) -> io::Result<()> {
+        for chunk in bytes.chunks(STREAM_CHUNK) {
+            sink.write_all(&Self::shift(chunk, self.key))?;
+        }
+        Ok(())
+    }
+
+    fn decrypt_bytes(&self, bytes: &[u8]) -> Vec<u8> {
+        Self::shift(bytes, self.key.wrapping_neg())
+    }
+}
+
+impl Cipher for ByteCaesarCipher {
+    fn name(&self) -> &'static str {
+        "Byte Caesar cipher"
+    }
+
+    /// Encrypts the UTF-8 bytes of the plaintext.
+    ///
+    /// # Arguments
+    /// * `plaintext` - A string slice that holds the text to be encrypted.
+    ///
+    /// # Returns
+    /// A `String` containing the encrypted bytes as hex.
+    fn encrypt(&self, plaintext: &str) -> String {
+        encoding::encode_hex(&Self::shift(plaintext.as_bytes(), self.key))
+    }
+
+    /// Decrypts hex ciphertext back into text.
+    ///
+    /// Invalid hex decrypts to an empty string and bytes that aren't
+    /// valid UTF-8 are replaced with U+FFFD.
+    ///
+    /// # Arguments
+    /// * `ciphertext` - A string slice that holds the hex to be decrypted.
+    ///
+    /// # Returns
+    /// A `String` containing the decrypted text.
+    fn decrypt(&self, ciphertext: &str) -> String {
+        let bytes = encoding::decode_hex(ciphertext).unwrap_or_default();
+        String::from_utf8_lossy(&self.decrypt_bytes(&bytes)).into_owned()
+    }
+
+    fn key_space(&self) -> Option<u64> {
+        Some(256)
+    }
+
+    /// Every byte becomes two hex digits.
+    fn preserves_length(&self) -> bool {
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_byte_caesar_wraps_around() {
+        let cipher = ByteCaesarCipher::new(300);
+        let mut ciphertext = Vec::new();
+        cipher
+            .encrypt_to(&[0x00, 0x7f, 0xd4, 0xff], &mut ciphertext)
+            .unwrap();
+
+        assert_eq!(cipher.key, 44);
+        assert_eq!(ciphertext, vec![0x2c, 0xab, 0x00, 0x2b]);
+        assert_eq!(
+            cipher.decrypt_bytes(&ciphertext),
+            vec![0x00, 0x7f, 0xd4, 0xff]
+        );
+    }
+
+    #[test]
+    fn test_byte_caesar_round_trip_through_hex() {
+        let cipher = ByteCaesarCipher::new(-3);
+        let ciphertext = cipher.encrypt("héllo");
+
+        assert_eq!(ciphertext, "65c0a669696c");
+        assert_eq!(cipher.decrypt(&ciphertext), "héllo");
+    }
+}
@@ -1,5 +1,6 @@
 //! The `caesar` module provides an implementation of the Caesar cipher
 
+use super::alphabet::Alphabet;
 use super::Cipher;
 
 /// A `CaesarCipher` represents the Caesar cipher encryption algorithm.
@@ -10,6 +11,10 @@ pub struct CaesarCipher {
 }
 
 impl Cipher for CaesarCipher {
+    fn name(&self) -> &'static str {
+        "Caesar cipher"
+    }
+
     /// Encrypts the given plaintext string by shifting the letters by the given key.
     ///
     /// # Arguments
@@ -61,9 +66,188 @@ impl Cipher for CaesarCipher {
     /// assert_eq!(cipher.decrypt("def"), "abc");
     /// ```
     fn decrypt(&self, ciphertext: &str) -> String {
-        let cipher = CaesarCipher { key: -self.key };
+        let cipher = CaesarCipher {
+            key: -self.key.rem_euclid(26),
+        };
+        cipher.encrypt(ciphertext)
+    }
+
+    /// Every shift from 1 to 25; a shift of 0 or 26 leaves the text unchanged.
+    fn key_space(&self) -> Option<u64> {
+        Some(25)
+    }
+
+    /// Only ASCII letters are enciphered.
+    fn transforms(&self, c: char) -> bool {
+        c.is_ascii_alphabetic()
+    }
+}
+
+/// A `ReversedCaesarCipher` is the Caesar cipher along a reversed alphabet.
+/// Each letter is flipped as in Atbash (A becomes Z) and then shifted
+/// forward by the key, so a key of 0 is Atbash. Flipping and shifting
+/// twice gives back the original letter, so decryption is the same operation
+pub struct ReversedCaesarCipher {
+    pub key: i32,
+}
+
+impl ReversedCaesarCipher {
+    /// Flips every letter within its case and shifts it by the key.
+    fn apply(&self, text: &str) -> String {
+        let shift = self.key.rem_euclid(26) as u8;
+        text.chars()
+            .map(|c| match c.is_ascii_alphabetic() {
+                true => {
+                    let base = if c.is_ascii_lowercase() { b'a' } else { b'A' };
+                    let offset = c as u8 - base;
+                    ((25 - offset + shift) % 26 + base) as char
+                }
+                false => c,
+            })
+            .collect()
+    }
+}
+
+impl Cipher for ReversedCaesarCipher {
+    fn name(&self) -> &'static str {
+        "Reversed Caesar cipher"
+    }
+
+    /// Encrypts the given plaintext by flipping each letter and shifting it by the key.
+    ///
+    /// # Arguments
+    /// * `plaintext` - A string slice that holds the text to be encrypted.
+    ///
+    /// # Returns
+    /// A `String` containing the encrypted text.
+    ///
+    /// # Examples
+    /// ```
+    /// let cipher = ReversedCaesarCipher { key: 1 };
+    /// assert_eq!(cipher.encrypt("abc"), "azy");
+    /// ```
+    fn encrypt(&self, plaintext: &str) -> String {
+        self.apply(plaintext)
+    }
+
+    /// Decrypts the given ciphertext, which applies the same flip and shift again.
+    ///
+    /// # Arguments
+    /// * `ciphertext` - A string slice that holds the text to be decrypted.
+    ///
+    /// # Returns
+    /// A `String` containing the decrypted text.
+    fn decrypt(&self, ciphertext: &str) -> String {
+        self.apply(ciphertext)
+    }
+
+    /// Every shift from 1 to 26; unlike the plain Caesar cipher, no key
+    /// leaves the text unchanged, and 26 is the same as 0, plain Atbash.
+    fn key_space(&self) -> Option<u64> {
+        Some(26)
+    }
+
+    /// Only ASCII letters are enciphered.
+    fn transforms(&self, c: char) -> bool {
+        c.is_ascii_alphabetic()
+    }
+}
+
+/// An `AlphabetCaesarCipher` is the Caesar cipher over a custom alphabet.
+/// It rotates characters within the alphabet, so the modulus is the
+/// alphabet's length, and passes any other character through unchanged
+pub struct AlphabetCaesarCipher {
+    pub key: i32,
+    pub alphabet: Alphabet,
+}
+
+impl AlphabetCaesarCipher {
+    /// Rotates a lowercase letter through its capital, for alphabets that fold case.
+    ///
+    /// Final sigma: `ς` is read as `σ`, and a rotated lowercase sigma is
+    /// written `ς` when it ends a word, so text spelled with final sigmas
+    /// round-trips. A word-final `σ` in the input comes back as `ς`.
+    fn rotate_lowercase(&self, c: char, shift: usize, at_word_end: bool) -> char {
+        let capital = match c {
+            'ς' => 'Σ',
+            _ if c.is_lowercase() => c.to_uppercase().next().unwrap_or(c),
+            _ => return c,
+        };
+        let rotated = match self.alphabet.index_of(capital) {
+            Some(index) => self.alphabet.char_at(index + shift),
+            None => return c,
+        };
+
+        match (rotated, at_word_end) {
+            ('Σ', true) => 'ς',
+            _ => rotated.to_lowercase().next().unwrap_or(rotated),
+        }
+    }
+}
+
+impl Cipher for AlphabetCaesarCipher {
+    fn name(&self) -> &'static str {
+        "Caesar cipher"
+    }
+
+    /// Encrypts the given plaintext by rotating each alphabet character forward by the key.
+    ///
+    /// # Arguments
+    /// * `plaintext` - A string slice that holds the text to be encrypted.
+    ///
+    /// # Returns
+    /// A `String` containing the encrypted text.
+    fn encrypt(&self, plaintext: &str) -> String {
+        let shift = self.key.rem_euclid(self.alphabet.len() as i32) as usize;
+        let chars: Vec<char> = plaintext.chars().collect();
+        chars
+            .iter()
+            .enumerate()
+            .map(|(i, &c)| match self.alphabet.index_of(c) {
+                Some(index) => self.alphabet.char_at(index + shift),
+                None if self.alphabet.folds_case() => {
+                    let at_word_end = !chars.get(i + 1).is_some_and(|next| next.is_alphabetic());
+                    self.rotate_lowercase(c, shift, at_word_end)
+                }
+                None => c,
+            })
+            .collect()
+    }
+
+    /// Decrypts the given ciphertext by rotating each alphabet character back by the key.
+    ///
+    /// # Arguments
+    /// * `ciphertext` - A string slice that holds the text to be decrypted.
+    ///
+    /// # Returns
+    /// A `String` containing the decrypted text.
+    fn decrypt(&self, ciphertext: &str) -> String {
+        let cipher = AlphabetCaesarCipher {
+            key: -self.key.rem_euclid(self.alphabet.len() as i32),
+            alphabet: self.alphabet.clone(),
+        };
         cipher.encrypt(ciphertext)
     }
+
+    /// Every shift except the identity shift of 0.
+    fn key_space(&self) -> Option<u64> {
+        Some(self.alphabet.len() as u64 - 1)
+    }
+
+    /// Alphabet characters are rotated, and for case-folding alphabets
+    /// so are the lowercase forms of its letters.
+    fn transforms(&self, c: char) -> bool {
+        match self.alphabet.index_of(c) {
+            Some(_) => true,
+            None if self.alphabet.folds_case() => match c {
+                'ς' => true,
+                _ => c.to_uppercase().next().is_some_and(|capital| {
+                    capital != c && self.alphabet.index_of(capital).is_some()
+                }),
+            },
+            None => false,
+        }
+    }
 }
 
 #[cfg(test)]
@@ -85,4 +269,86 @@ mod tests {
 
         assert_eq!(plaintext, "test");
     }
+
+    #[test]
+    fn test_caesar_cipher_key_space() {
+        let cipher = CaesarCipher { key: 13 };
+
+        assert_eq!(cipher.key_space(), Some(25));
+    }
+
+    #[test]
+    fn test_reversed_caesar_cipher() {
+        let cipher = ReversedCaesarCipher { key: 3 };
+        let ciphertext = cipher.encrypt("Attack at dawn");
+
+        assert_eq!(ciphertext, "Cjjcas cj zcgp");
+        assert_eq!(cipher.decrypt(&ciphertext), "Attack at dawn");
+    }
+
+    #[test]
+    fn test_reversed_caesar_key_zero_is_atbash() {
+        let cipher = ReversedCaesarCipher { key: 0 };
+
+        assert_eq!(cipher.encrypt("abc xyz ABC"), "zyx cba ZYX");
+        assert_eq!(
+            ReversedCaesarCipher { key: 26 }.encrypt("hello"),
+            cipher.encrypt("hello")
+        );
+    }
+
+    #[test]
+    fn test_alphabet_caesar_cipher() {
+        let cipher = AlphabetCaesarCipher {
+            key: 3,
+            alphabet: "0123456789".parse().unwrap(),
+        };
+        let ciphertext = cipher.encrypt("call 555-0199");
+        let plaintext = cipher.decrypt(&ciphertext);
+
+        assert_eq!(ciphertext, "call 888-3422");
+        assert_eq!(plaintext, "call 555-0199");
+        assert_eq!(cipher.key_space(), Some(9));
+    }
+
+    #[test]
+    fn test_greek_caesar_cipher() {
+        let cipher = AlphabetCaesarCipher {
+            key: 3,
+            alphabet: Alphabet::greek(),
+        };
+        let ciphertext = cipher.encrypt("Λόγος και Ωμέγα");
+        let plaintext = cipher.decrypt(&ciphertext);
+
+        assert_eq!(ciphertext, "Ξόζσφ νδμ Γοέζδ");
+        assert_eq!(plaintext, "Λόγος και Ωμέγα");
+        assert_eq!(cipher.encrypt("το"), "χς");
+        assert_eq!(cipher.decrypt("χς"), "το");
+        assert_eq!(cipher.key_space(), Some(23));
+    }
+
+    #[test]
+    fn test_alphabet_caesar_min_key() {
+        let cipher = AlphabetCaesarCipher {
+            key: i32::MIN,
+            alphabet: Alphabet::greek(),
+        };
+
+        assert_eq!(cipher.decrypt(&cipher.encrypt("Ωμέγα")), "Ωμέγα");
+    }
+
+    #[test]
+    fn test_cyrillic_caesar_cipher() {
+        let cipher = AlphabetCaesarCipher {
+            key: 3,
+            alphabet: Alphabet::cyrillic(),
+        };
+        let ciphertext = cipher.encrypt("Привет, мир! Ёж");
+        let plaintext = cipher.decrypt(&ciphertext);
+
+        assert_eq!(ciphertext, "Тулезх, плу! Ий");
+        assert_eq!(plaintext, "Привет, мир! Ёж");
+        assert_eq!(cipher.encrypt("яЯ"), "вВ");
+        assert_eq!(cipher.key_space(), Some(32));
+    }
 }
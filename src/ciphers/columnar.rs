@@ -0,0 +1,236 @@
+//! The `columnar` module provides an implementation of the columnar transposition cipher
+//! and its Myszkowski variant
+
+use super::Cipher;
+
+/// How columns sharing the same key letter are read off.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ColumnarVariant {
+    /// Every column gets its own rank, ties broken left-to-right.
+    Standard,
+    /// Columns with the same key letter share a rank and are read off
+    /// together, row by row.
+    Myszkowski,
+}
+
+/// A `ColumnarCipher` represents the columnar transposition cipher.
+/// It writes the plaintext into rows under the key and reads it
+/// back out column by column in the alphabetical order of the key
+pub struct ColumnarCipher {
+    pub key: String,
+    pub variant: ColumnarVariant,
+}
+
+impl ColumnarCipher {
+    /// New function to ensure that key is always uppercase.
+    ///
+    /// # Arguments
+    /// * `key` - A String whose letters order the columns.
+    /// * `variant` - Standard columnar or Myszkowski read-out.
+    ///
+    /// # Returns
+    /// A `ColumnarCipher` instance with an all uppercase key.
+    pub fn new(key: String, variant: ColumnarVariant) -> ColumnarCipher {
+        ColumnarCipher {
+            key: key.to_ascii_uppercase(),
+            variant,
+        }
+    }
+
+    /// Ranks each column of the key, starting at zero. Columns are read
+    /// off in ascending rank.
+    ///
+    /// Standard columnar gives every column a distinct rank, while
+    /// Myszkowski gives repeated letters the same rank.
+    pub fn column_ranks(&self) -> Vec<usize> {
+        let key: Vec<char> = self.key.chars().collect();
+
+        match self.variant {
+            ColumnarVariant::Standard => {
+                let mut order: Vec<usize> = (0..key.len()).collect();
+                order.sort_by_key(|&i| key[i]);
+
+                let mut ranks = vec![0; key.len()];
+                for (rank, &column) in order.iter().enumerate() {
+                    ranks[column] = rank;
+                }
+                ranks
+            }
+            ColumnarVariant::Myszkowski => {
+                let mut sorted = key.clone();
+                sorted.sort_unstable();
+                sorted.dedup();
+                key.iter()
+                    .map(|c| sorted.binary_search(c).unwrap())
+                    .collect()
+            }
+        }
+    }
+
+    /// Writes the plaintext into rows under the key, with the read-off
+    /// rank of each column, counted from 1, below the key letters.
+    ///
+    /// # Returns
+    /// The key line, the rank line and one line per row of the grid,
+    /// cells separated by spaces; the last row may be short.
+    pub fn grid(&self, plaintext: &str) -> Vec<String> {
+        let ranks = self.column_ranks();
+        // wide enough for two-digit ranks to stay lined up under the key
+        let cell = ranks.len().to_string().len();
+        let spaced = |cells: Vec<String>| {
+            cells
+                .iter()
+                .map(|text| format!("{:<cell$}", text))
+                .collect::<Vec<String>>()
+                .join(" ")
+                .trim_end()
+                .to_string()
+        };
+        let width = ranks.len().max(1);
+        let chars: Vec<char> = plaintext.chars().collect();
+
+        let mut lines = vec![
+            spaced(self.key.chars().map(String::from).collect()),
+            spaced(ranks.iter().map(|rank| (rank + 1).to_string()).collect()),
+        ];
+        lines.extend(
+            chars
+                .chunks(width)
+                .map(|row| spaced(row.iter().map(|c| c.to_string()).collect())),
+        );
+        lines
+    }
+
+    /// Computes the plaintext index of every ciphertext character.
+    ///
+    /// Ranks are read in ascending order; within a rank, cells are read
+    /// row by row and left-to-right across the columns sharing that rank.
+    fn read_order(&self, length: usize) -> Vec<usize> {
+        let ranks = self.column_ranks();
+        let width = ranks.len();
+        let rows = length.div_ceil(width);
+        let max_rank = ranks.iter().copied().max().unwrap_or(0);
+
+        let mut order = Vec::with_capacity(length);
+        for rank in 0..=max_rank {
+            for row in 0..rows {
+                for (column, _) in ranks.iter().enumerate().filter(|(_, &r)| r == rank) {
+                    let index = row * width + column;
+                    if index < length {
+                        order.push(index);
+                    }
+                }
+            }
+        }
+
+        order
+    }
+}
+
+impl Cipher for ColumnarCipher {
+    fn name(&self) -> &'static str {
+        match self.variant {
+            ColumnarVariant::Standard => "Columnar transposition",
+            ColumnarVariant::Myszkowski => "Myszkowski transposition",
+        }
+    }
+
+    /// Encrypts the given plaintext by reading its grid out in key order.
+    ///
+    /// The last row may be incomplete; no padding is added.
+    ///
+    /// # Arguments
+    /// * `plaintext` - A string slice that holds the text to be encrypted.
+    ///
+    /// # Returns
+    /// A `String` containing the encrypted text.
+    fn encrypt(&self, plaintext: &str) -> String {
+        let chars: Vec<char> = plaintext.chars().collect();
+        self.read_order(chars.len())
+            .into_iter()
+            .map(|index| chars[index])
+            .collect()
+    }
+
+    /// Decrypts the given ciphertext by writing it back into the grid in key order.
+    ///
+    /// # Arguments
+    /// * `ciphertext` - A string slice that holds the text to be decrypted.
+    ///
+    /// # Returns
+    /// A `String` containing the decrypted text.
+    fn decrypt(&self, ciphertext: &str) -> String {
+        let chars: Vec<char> = ciphertext.chars().collect();
+        let mut plaintext = vec![' '; chars.len()];
+        for (c, index) in chars.iter().zip(self.read_order(chars.len())) {
+            plaintext[index] = *c;
+        }
+
+        plaintext.into_iter().collect()
+    }
+
+    /// A single column reads the text back unchanged.
+    fn key_length_bounds(&self) -> (usize, Option<usize>) {
+        (2, None)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_column_ranks() {
+        let standard = ColumnarCipher::new("tomato".to_string(), ColumnarVariant::Standard);
+        let myszkowski = ColumnarCipher::new("tomato".to_string(), ColumnarVariant::Myszkowski);
+
+        assert_eq!(standard.column_ranks(), vec![4, 2, 1, 0, 5, 3]);
+        assert_eq!(myszkowski.column_ranks(), vec![3, 2, 1, 0, 3, 2]);
+    }
+
+    #[test]
+    fn test_column_ranks_zebra() {
+        let cipher = ColumnarCipher::new("zebra".to_string(), ColumnarVariant::Standard);
+
+        assert_eq!(cipher.column_ranks(), vec![4, 2, 1, 3, 0]);
+    }
+
+    #[test]
+    fn test_grid() {
+        let cipher = ColumnarCipher::new("zebra".to_string(), ColumnarVariant::Standard);
+
+        assert_eq!(
+            cipher.grid("attackatdawn"),
+            ["Z E B R A", "5 3 2 4 1", "a t t a c", "k a t d a", "w n"]
+        );
+    }
+
+    #[test]
+    fn test_columnar_cipher_encrypt() {
+        let cipher = ColumnarCipher::new("tomato".to_string(), ColumnarVariant::Standard);
+        let ciphertext = cipher.encrypt("WEAREDISCOVEREDFLEEATONCE");
+
+        assert_eq!(ciphertext, "ROFOACDTESEADEECWIREEEVLN");
+    }
+
+    #[test]
+    fn test_myszkowski_cipher_encrypt() {
+        let cipher = ColumnarCipher::new("tomato".to_string(), ColumnarVariant::Myszkowski);
+        let ciphertext = cipher.encrypt("WEAREDISCOVEREDFLEEATONCE");
+
+        assert_eq!(ciphertext, "ROFOACDTEDSEEEACWEIVRLENE");
+    }
+
+    #[test]
+    fn test_columnar_cipher_decrypt() {
+        let standard = ColumnarCipher::new("tomato".to_string(), ColumnarVariant::Standard);
+        let myszkowski = ColumnarCipher::new("tomato".to_string(), ColumnarVariant::Myszkowski);
+        let plaintext = "WEAREDISCOVEREDFLEEATONCE";
+
+        assert_eq!(standard.decrypt(&standard.encrypt(plaintext)), plaintext);
+        assert_eq!(
+            myszkowski.decrypt(&myszkowski.encrypt(plaintext)),
+            plaintext
+        );
+    }
+}
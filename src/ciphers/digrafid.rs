@@ -0,0 +1,195 @@
+//! The `digrafid` module provides an implementation of the Digrafid cipher
+
+use super::Cipher;
+use std::collections::HashSet;
+
+/// The 27 symbols of the grids: the alphabet and `#`.
+const SYMBOLS: &str = "ABCDEFGHIJKLMNOPQRSTUVWXYZ#";
+
+/// Letter appended to pair up the last letter of odd-length text.
+const FILLER: char = 'X';
+
+/// A `DigrafidCipher` represents the Digrafid cipher.
+/// The first letter of each pair sits in a keyed 3x9 grid filled row by
+/// row, the second in a keyed 9x3 grid filled column by column. A pair
+/// becomes three digits: the first letter's column, a digit combining the
+/// first letter's row with the second letter's column, and the second
+/// letter's row. Within every block of `period` pairs the top, middle and
+/// bottom digits are written out in turn and read back in threes as new
+/// pairs. Other characters are dropped and odd-length text is padded with `X`
+pub struct DigrafidCipher {
+    horizontal: Vec<char>,
+    vertical: Vec<char>,
+    pub period: usize,
+}
+
+/// Keyed ordering of the 27 symbols, starting with the keyword.
+fn keyed_symbols(key: &str) -> Vec<char> {
+    let mut seen = HashSet::new();
+    key.chars()
+        .map(|c| c.to_ascii_uppercase())
+        .filter(|&c| SYMBOLS.contains(c))
+        .chain(SYMBOLS.chars())
+        .filter(|&c| seen.insert(c))
+        .collect()
+}
+
+impl DigrafidCipher {
+    /// Creates a Digrafid cipher.
+    ///
+    /// # Arguments
+    /// * `key` - Keyword of the horizontal 3x9 grid holding the first letters.
+    /// * `key2` - Keyword of the vertical 9x3 grid holding the second letters.
+    /// * `period` - Number of pairs fractionated together; zero means
+    ///   the whole message is one block.
+    ///
+    /// # Returns
+    /// A `DigrafidCipher` instance with both grids generated.
+    pub fn new(key: &str, key2: &str, period: usize) -> DigrafidCipher {
+        DigrafidCipher {
+            horizontal: keyed_symbols(key),
+            vertical: keyed_symbols(key2),
+            period,
+        }
+    }
+
+    /// The three digits, each from 0 to 8, of a pair of grid symbols.
+    fn digits(&self, first: char, second: char) -> [usize; 3] {
+        let h = self.horizontal.iter().position(|&c| c == first).unwrap();
+        let v = self.vertical.iter().position(|&c| c == second).unwrap();
+        let (row, column) = (h / 9, h % 9);
+        let (row2, column2) = (v % 9, v / 9);
+        [column, row * 3 + column2, row2]
+    }
+
+    /// The pair of grid symbols encoded by three digits.
+    fn pair(&self, digits: &[usize]) -> [char; 2] {
+        let (column, middle, row2) = (digits[0], digits[1], digits[2]);
+        [
+            self.horizontal[(middle / 3) * 9 + column],
+            self.vertical[(middle % 3) * 9 + row2],
+        ]
+    }
+
+    /// Uppercases the text, keeps the grid symbols and pads it to whole pairs.
+    fn symbols(text: &str) -> Vec<char> {
+        let mut symbols: Vec<char> = text
+            .chars()
+            .map(|c| c.to_ascii_uppercase())
+            .filter(|&c| SYMBOLS.contains(c))
+            .collect();
+        if symbols.len() % 2 == 1 {
+            symbols.push(FILLER);
+        }
+        symbols
+    }
+
+    /// Fractionates the pairs of the text one block at a time.
+    fn apply(&self, text: &str, encrypt: bool) -> String {
+        let symbols = Self::symbols(text);
+        let digits: Vec<[usize; 3]> = symbols
+            .chunks(2)
+            .map(|pair| self.digits(pair[0], pair[1]))
+            .collect();
+        let period = match self.period {
+            0 => digits.len().max(1),
+            period => period,
+        };
+
+        let mut output = String::with_capacity(symbols.len());
+        for block in digits.chunks(period) {
+            let n = block.len();
+            match encrypt {
+                true => {
+                    let stream: Vec<usize> = (0..3)
+                        .flat_map(|row| block.iter().map(move |digits| digits[row]))
+                        .collect();
+                    output.extend(stream.chunks(3).flat_map(|digits| self.pair(digits)));
+                }
+                false => {
+                    let stream: Vec<usize> = block.iter().flatten().copied().collect();
+                    output.extend(
+                        (0..n).flat_map(|i| {
+                            self.pair(&[stream[i], stream[n + i], stream[2 * n + i]])
+                        }),
+                    );
+                }
+            }
+        }
+
+        output
+    }
+}
+
+impl Cipher for DigrafidCipher {
+    fn name(&self) -> &'static str {
+        "Digrafid cipher"
+    }
+
+    /// Encrypts the given plaintext with the two grids.
+    ///
+    /// # Arguments
+    /// * `plaintext` - A string slice that holds the text to be encrypted.
+    ///
+    /// # Returns
+    /// A `String` of uppercase symbols containing the encrypted text.
+    fn encrypt(&self, plaintext: &str) -> String {
+        self.apply(plaintext, true)
+    }
+
+    /// Decrypts the given ciphertext with the two grids.
+    ///
+    /// # Arguments
+    /// * `ciphertext` - A string slice that holds the text to be decrypted.
+    ///
+    /// # Returns
+    /// A `String` of uppercase symbols containing the decrypted text.
+    fn decrypt(&self, ciphertext: &str) -> String {
+        self.apply(ciphertext, false)
+    }
+
+    /// Characters outside the grids are dropped and odd-length text is padded.
+    fn preserves_length(&self) -> bool {
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_digrafid_unkeyed_vector() {
+        // With unkeyed grids AB gives the digits 0 0 1 and CD gives 2 0 3;
+        // read across the rows they become 0 2 0 and 0 1 3, i.e. AS and AM
+        let cipher = DigrafidCipher::new("", "", 2);
+
+        assert_eq!(cipher.encrypt("ABCD"), "ASAM");
+        assert_eq!(cipher.decrypt("ASAM"), "ABCD");
+    }
+
+    #[test]
+    fn test_digrafid_period_one_is_identity() {
+        let cipher = DigrafidCipher::new("keyword", "vertical", 1);
+
+        assert_eq!(cipher.encrypt("ATTACKATDAWN"), "ATTACKATDAWN");
+    }
+
+    #[test]
+    fn test_digrafid_round_trip() {
+        let plaintext = "THISISTHEFORESTPRIMEVALTHEMURMURINGPINES";
+        for period in [0, 3, 4, 7] {
+            let cipher = DigrafidCipher::new("keyword", "vertical", period);
+            let ciphertext = cipher.encrypt(plaintext);
+
+            assert_ne!(ciphertext, plaintext);
+            assert_eq!(cipher.decrypt(&ciphertext), plaintext);
+        }
+
+        let cipher = DigrafidCipher::new("keyword", "vertical", 3);
+        assert_eq!(
+            cipher.decrypt(&cipher.encrypt("odd, isn't it")),
+            "ODDISNTITX"
+        );
+    }
+}
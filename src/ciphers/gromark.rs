@@ -0,0 +1,191 @@
+//! The `gromark` module provides an implementation of the Gromark cipher
+
+use super::alphabet::Alphabet;
+use super::columnar::{ColumnarCipher, ColumnarVariant};
+use super::vic::chain_addition;
+use super::Cipher;
+use std::collections::HashSet;
+
+/// A `GromarkCipher` represents the Gromark cipher, a Gronsfeld cipher with
+/// a running key. The numeric primer is extended by chain addition into one
+/// digit per letter; each plaintext letter is shifted forward by its digit
+/// in the straight alphabet and replaced by the letter at that position in
+/// a mixed alphabet
+pub struct GromarkCipher {
+    pub primer: Vec<u8>,
+    pub cipher_alphabet: Alphabet,
+}
+
+/// Generates the Gromark running key: the primer followed by chain-added digits.
+///
+/// # Arguments
+/// * `primer` - The starting digits; at least two.
+/// * `length` - Number of key digits needed.
+///
+/// # Returns
+/// The first `length` digits of the running key.
+///
+/// # Examples
+/// ```
+/// assert_eq!(keystream(&[2, 3, 4, 5, 2], 8), vec![2, 3, 4, 5, 2, 5, 7, 9]);
+/// ```
+pub fn keystream(primer: &[u8], length: usize) -> Vec<u8> {
+    let mut digits: Vec<u8> = primer.iter().copied().take(length).collect();
+    digits.extend(chain_addition(primer, length.saturating_sub(primer.len())));
+    digits
+}
+
+/// Builds the Gromark cipher alphabet: the keyed alphabet is written in
+/// rows under the keyword's distinct letters and read off column by column
+/// in alphabetical order of those letters.
+///
+/// # Examples
+/// ```
+/// let alphabet = mixed_alphabet("enigma");
+/// assert_eq!(alphabet, "AJRXEBKSYGFPVIDOUMHQWNCLTZ".parse().unwrap());
+/// ```
+pub fn mixed_alphabet(keyword: &str) -> Alphabet {
+    let keyed = Alphabet::keyed(keyword);
+    let letters: String = (0..keyed.len()).map(|i| keyed.char_at(i)).collect();
+    let width = keyword
+        .chars()
+        .filter(|c| c.is_ascii_alphabetic())
+        .map(|c| c.to_ascii_uppercase())
+        .collect::<HashSet<char>>()
+        .len()
+        .max(1);
+    let transposition =
+        ColumnarCipher::new(letters[..width].to_string(), ColumnarVariant::Standard);
+
+    transposition.encrypt(&letters).parse().unwrap()
+}
+
+impl GromarkCipher {
+    /// Creates a Gromark cipher.
+    ///
+    /// # Arguments
+    /// * `primer` - The starting digits of the running key, traditionally five.
+    /// * `keyword` - Keyword that mixes the cipher alphabet.
+    ///
+    /// # Returns
+    /// A `GromarkCipher`, or an error if the primer isn't at least two digits.
+    pub fn new(primer: &str, keyword: &str) -> Result<GromarkCipher, String> {
+        let primer: Vec<u8> = primer
+            .chars()
+            .map(|c| c.to_digit(10).map(|d| d as u8))
+            .collect::<Option<_>>()
+            .filter(|digits: &Vec<u8>| digits.len() >= 2)
+            .ok_or_else(|| "Gromark primer must be at least two digits.".to_string())?;
+
+        Ok(GromarkCipher {
+            primer,
+            cipher_alphabet: mixed_alphabet(keyword),
+        })
+    }
+
+    /// Substitutes every uppercased letter of the text with `substitute`,
+    /// given the letter's position in A-Z and its running key digit. Other
+    /// characters are left in place and don't use up a key digit; case is preserved.
+    fn apply(&self, text: &str, substitute: impl Fn(usize, usize) -> char) -> String {
+        let letters = text.chars().filter(|c| c.is_ascii_alphabetic()).count();
+        let mut key = keystream(&self.primer, letters).into_iter();
+
+        text.chars()
+            .map(|c| match c.is_ascii_alphabetic() {
+                true => {
+                    let digit = key.next().unwrap() as usize;
+                    let position = (c.to_ascii_uppercase() as u8 - b'A') as usize;
+                    let substituted = substitute(position, digit);
+                    match c.is_ascii_lowercase() {
+                        true => substituted.to_ascii_lowercase(),
+                        false => substituted,
+                    }
+                }
+                false => c,
+            })
+            .collect()
+    }
+}
+
+impl Cipher for GromarkCipher {
+    fn name(&self) -> &'static str {
+        "Gromark cipher"
+    }
+
+    /// Encrypts the given plaintext with the running key and mixed alphabet.
+    ///
+    /// # Arguments
+    /// * `plaintext` - A string slice that holds the text to be encrypted.
+    ///
+    /// # Returns
+    /// A `String` containing the encrypted text.
+    fn encrypt(&self, plaintext: &str) -> String {
+        self.apply(plaintext, |position, digit| {
+            self.cipher_alphabet.char_at(position + digit)
+        })
+    }
+
+    /// Decrypts the given ciphertext with the running key and mixed alphabet.
+    ///
+    /// # Arguments
+    /// * `ciphertext` - A string slice that holds the text to be decrypted.
+    ///
+    /// # Returns
+    /// A `String` containing the decrypted text.
+    fn decrypt(&self, ciphertext: &str) -> String {
+        self.apply(ciphertext, |position, digit| {
+            let letter = (b'A' + position as u8) as char;
+            let index = self.cipher_alphabet.index_of(letter).unwrap();
+            (b'A' + ((index + 26 - digit) % 26) as u8) as char
+        })
+    }
+
+    /// Only ASCII letters are enciphered.
+    fn transforms(&self, c: char) -> bool {
+        c.is_ascii_alphabetic()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_keystream() {
+        let short = keystream(&[2, 3, 4, 5, 2], 3);
+        let long = keystream(&[2, 3, 4, 5, 2], 12);
+
+        assert_eq!(short, vec![2, 3, 4]);
+        assert_eq!(long, vec![2, 3, 4, 5, 2, 5, 7, 9, 7, 7, 2, 6]);
+    }
+
+    #[test]
+    fn test_mixed_alphabet() {
+        let alphabet = mixed_alphabet("enigma");
+        let letters: String = (0..26).map(|i| alphabet.char_at(i)).collect();
+
+        assert_eq!(letters, "AJRXEBKSYGFPVIDOUMHQWNCLTZ");
+    }
+
+    #[test]
+    fn test_gromark_known_vector() {
+        let cipher = GromarkCipher::new("23452", "enigma").unwrap();
+        let plaintext = "THEREAREUPTOTENSUBSTITUTESPERLETTER";
+        let ciphertext = cipher.encrypt(plaintext);
+
+        assert_eq!(ciphertext, "NFYCKBTIJCNWZYCACJNAYNLQPWWSTWPJQFL");
+        assert_eq!(cipher.decrypt(&ciphertext), plaintext);
+    }
+
+    #[test]
+    fn test_gromark_round_trip() {
+        let cipher = GromarkCipher::new("77651", "spring fever").unwrap();
+        let plaintext = "Meet me by the old oak tree, at noon!";
+        let ciphertext = cipher.encrypt(plaintext);
+
+        assert_ne!(ciphertext, plaintext);
+        assert_eq!(cipher.decrypt(&ciphertext), plaintext);
+        assert!(GromarkCipher::new("7", "key").is_err());
+        assert!(GromarkCipher::new("12a45", "key").is_err());
+    }
+}
@@ -1,12 +1,220 @@
+pub mod alberti;
+pub mod alphabet;
+pub mod bazeries;
+pub mod bifid;
+pub mod byte_caesar;
 pub mod caesar;
+pub mod columnar;
+pub mod digrafid;
+pub mod gromark;
 pub mod playfair;
+pub mod quagmire;
+pub mod rail_fence;
+pub mod rot;
+pub mod shuffle;
+pub mod trifid;
+pub mod vic;
 pub mod vigenere;
+pub mod xor;
+
+use std::fmt;
+use std::io::{self, Write};
 
 pub trait Cipher {
+    /// Human-friendly name of the cipher, e.g. "Vigenère cipher".
+    fn name(&self) -> &'static str;
     fn encrypt(&self, plaintext: &str) -> String;
     fn decrypt(&self, ciphertext: &str) -> String;
+
+    /// Number of distinct non-trivial keys, when it is finite and small
+    /// enough to enumerate. `None` means brute force isn't feasible.
+    fn key_space(&self) -> Option<u64> {
+        None
+    }
+
+    /// Whether the output always has as many characters as the input.
+    ///
+    /// Keeping the length doesn't promise an exact round trip: Bifid reads
+    /// J as I, so decrypting gives I back wherever the plaintext had J.
+    fn preserves_length(&self) -> bool {
+        true
+    }
+
+    /// Whether the cipher changes `c` rather than passing it through as is.
+    /// Ciphers that transpose, drop or re-encode every character keep the default.
+    fn transforms(&self, _c: char) -> bool {
+        true
+    }
+
+    /// Shortest and longest key lengths the cipher makes full use of, with
+    /// `None` for no upper limit. Shorter keys degenerate into a simpler
+    /// cipher and longer ones have characters that can't be used.
+    fn key_length_bounds(&self) -> (usize, Option<usize>) {
+        (0, None)
+    }
+}
+
+/// A cipher that works on raw bytes, for input decoded from hex, base64
+/// or base32 rather than read as text.
+pub trait ByteCipher {
+    /// Encrypts the bytes and writes the result to `sink` chunk by chunk
+    /// instead of building the whole output first.
+    fn encrypt_to(&self, bytes: &[u8], sink: &mut dyn Write) -> io::Result<()>;
+    fn decrypt_bytes(&self, bytes: &[u8]) -> Vec<u8>;
+}
+
+impl fmt::Display for dyn Cipher + '_ {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// Why a cipher couldn't be built from the key it was given.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CipherError(pub String);
+
+impl fmt::Display for CipherError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
 }
 
-pub use caesar::CaesarCipher;
-pub use playfair::PlayfairCipher;
+impl std::error::Error for CipherError {}
+
+pub use alberti::AlbertiCipher;
+pub use alphabet::Alphabet;
+pub use bazeries::BazeriesCipher;
+pub use bifid::BifidCipher;
+pub use byte_caesar::ByteCaesarCipher;
+pub use caesar::{AlphabetCaesarCipher, CaesarCipher, ReversedCaesarCipher};
+pub use columnar::{ColumnarCipher, ColumnarVariant};
+pub use digrafid::DigrafidCipher;
+pub use gromark::GromarkCipher;
+pub use playfair::{DigraphStep, LetterMerge, PlayfairCipher, SquareFill};
+pub use quagmire::QuagmireCipher;
+pub use rail_fence::RailFenceCipher;
+pub use rot::{Rot18Cipher, Rot47Cipher, UnicodeRotCipher};
+pub use shuffle::ShuffleCipher;
+pub use trifid::TrifidCipher;
+pub use vic::VicCipher;
 pub use vigenere::VigenereCipher;
+pub use xor::XorCipher;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cipher_display_names() {
+        let ciphers: Vec<Box<dyn Cipher>> = vec![
+            Box::new(CaesarCipher { key: 3 }),
+            Box::new(AlphabetCaesarCipher {
+                key: 3,
+                alphabet: "0123456789".parse().unwrap(),
+            }),
+            Box::new(VigenereCipher::new("key".to_string())),
+            Box::new(PlayfairCipher::new("key".to_string())),
+            Box::new(ColumnarCipher::new(
+                "key".to_string(),
+                ColumnarVariant::Standard,
+            )),
+            Box::new(ColumnarCipher::new(
+                "key".to_string(),
+                ColumnarVariant::Myszkowski,
+            )),
+            Box::new(RailFenceCipher {
+                rails: 3,
+                offset: 0,
+            }),
+            Box::new(Rot18Cipher),
+            Box::new(Rot47Cipher),
+            Box::new(UnicodeRotCipher { shift: 3 }),
+            Box::new(BifidCipher::new("key", 5)),
+            Box::new(TrifidCipher::new("abcdefghijklmnopqrstuvwxyz.", 5).unwrap()),
+            Box::new(QuagmireCipher::new("key", "b").unwrap()),
+            Box::new(VicCipher::new("77651", "phrase").unwrap()),
+            Box::new(XorCipher { key: vec![1] }),
+            Box::new(GromarkCipher::new("23452", "enigma").unwrap()),
+            Box::new(AlbertiCipher::new("key", "k", 5).unwrap()),
+            Box::new(ShuffleCipher::new("key")),
+            Box::new(BazeriesCipher::new(3752).unwrap()),
+            Box::new(ByteCaesarCipher::new(3)),
+            Box::new(DigrafidCipher::new("key", "word", 3)),
+        ];
+        let names: Vec<String> = ciphers.iter().map(|cipher| cipher.to_string()).collect();
+
+        assert_eq!(
+            names,
+            vec![
+                "Caesar cipher",
+                "Caesar cipher",
+                "Vigenère cipher",
+                "Playfair cipher",
+                "Columnar transposition",
+                "Myszkowski transposition",
+                "Rail fence cipher",
+                "ROT18",
+                "ROT47",
+                "Unicode ROT",
+                "Bifid cipher",
+                "Trifid cipher",
+                "Quagmire I cipher",
+                "VIC cipher",
+                "XOR cipher",
+                "Gromark cipher",
+                "Alberti cipher",
+                "Shuffle transposition",
+                "Bazeries cipher",
+                "Byte Caesar cipher",
+                "Digrafid cipher",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_preserves_length() {
+        let caesar = CaesarCipher { key: 3 };
+        let columnar = ColumnarCipher::new("key".to_string(), ColumnarVariant::Standard);
+        let playfair = PlayfairCipher::new("key".to_string());
+        let bifid = BifidCipher::new("key", 5);
+
+        assert!(caesar.preserves_length());
+        assert!(columnar.preserves_length());
+        assert!(bifid.preserves_length());
+        assert_eq!(bifid.decrypt(&bifid.encrypt("jest")), "iest");
+        assert!(!playfair.preserves_length());
+        assert_ne!(playfair.encrypt("hello").len(), "hello".len());
+    }
+
+    #[test]
+    fn test_key_length_bounds() {
+        let vigenere = VigenereCipher::new("key".to_string());
+        let playfair = PlayfairCipher::new("key".to_string());
+        let six_by_six = PlayfairCipher::with_square_size("key".to_string(), 6);
+
+        assert_eq!(vigenere.key_length_bounds(), (2, None));
+        assert_eq!(playfair.key_length_bounds(), (0, Some(25)));
+        assert_eq!(six_by_six.key_length_bounds(), (0, Some(36)));
+        assert_eq!(CaesarCipher { key: 3 }.key_length_bounds(), (0, None));
+    }
+
+    #[test]
+    fn test_transforms() {
+        let caesar = CaesarCipher { key: 3 };
+        let greek = AlphabetCaesarCipher {
+            key: 3,
+            alphabet: Alphabet::greek(),
+        };
+        let columnar = ColumnarCipher::new("key".to_string(), ColumnarVariant::Standard);
+
+        assert!(caesar.transforms('a'));
+        assert!(!caesar.transforms(','));
+        assert!(!caesar.transforms(' '));
+        assert!(greek.transforms('λ'));
+        assert!(greek.transforms('ς'));
+        assert!(!greek.transforms('a'));
+        assert!(Rot18Cipher.transforms('7'));
+        assert!(!Rot47Cipher.transforms(' '));
+        assert!(columnar.transforms(' '));
+    }
+}
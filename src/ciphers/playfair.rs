@@ -1,25 +1,128 @@
 //! The `playfair` module provides an implementation of the Playfair cipher
 
+use super::alphabet::AlphabetIndex;
 use super::Cipher;
 use std::collections::HashSet;
+use std::fmt;
+use std::str::FromStr;
 
+#[derive(Clone, Copy)]
 enum EncryptionDirection {
     Encrypt,
     Decrypt,
 }
 
+/// Which Playfair rule transformed a digraph.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DigraphRule {
+    SameRow,
+    SameColumn,
+    Rectangle,
+}
+
+impl fmt::Display for DigraphRule {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DigraphRule::SameRow => write!(f, "same row"),
+            DigraphRule::SameColumn => write!(f, "same column"),
+            DigraphRule::Rectangle => write!(f, "rectangle"),
+        }
+    }
+}
+
+/// A single digraph substitution, as shown by the verbose breakdown.
+#[derive(Debug, PartialEq, Eq)]
+pub struct DigraphStep {
+    pub input: (char, char),
+    pub rule: DigraphRule,
+    pub output: (char, char),
+}
+
+/// How the keyword and then the rest of the alphabet fill the square.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum SquareFill {
+    /// Row by row, the rest of the alphabet in order.
+    Rows,
+    /// Column by column, the rest of the alphabet in order.
+    Columns,
+    /// Row by row, the rest of the alphabet in reverse order.
+    Reverse,
+}
+
+/// The letter the 5x5 square leaves out and the letter that stands in for it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LetterMerge {
+    pub from: char,
+    pub to: char,
+}
+
+impl Default for LetterMerge {
+    /// The classic merge, reading J as I.
+    fn default() -> Self {
+        LetterMerge { from: 'J', to: 'I' }
+    }
+}
+
+impl fmt::Display for LetterMerge {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}={}", self.from, self.to)
+    }
+}
+
+impl FromStr for LetterMerge {
+    type Err = String;
+
+    /// Parses a `FROM=TO` pair of letters in either case.
+    ///
+    /// Fails unless both sides are single, distinct letters A–Z.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let letter = |side: &str| {
+            let mut chars = side.chars();
+            match (chars.next(), chars.next()) {
+                (Some(c), None) if c.is_ascii_alphabetic() => Ok(c.to_ascii_uppercase()),
+                _ => Err(format!("'{}' is not a single letter A-Z", side)),
+            }
+        };
+
+        let (from, to) = s
+            .split_once('=')
+            .ok_or_else(|| format!("expected FROM=TO, got '{}'", s))?;
+        let merge = LetterMerge {
+            from: letter(from)?,
+            to: letter(to)?,
+        };
+        if merge.from == merge.to {
+            return Err(format!("cannot merge {} into itself", merge.from));
+        }
+        Ok(merge)
+    }
+}
+
 /// A `PlayfairCipher` represents the Playfair cipher encryption algorithm
-/// It shifts bigrams of the plaintext according to a 5x5 matrix
+/// It shifts bigrams of the plaintext according to a 5x5 matrix,
+/// or a 6x6 matrix that also holds the digits
 pub struct PlayfairCipher {
     pub key: String,
-    matrix: [[char; 5]; 5],
+    /// Put the characters that aren't in the square back at their
+    /// original positions instead of dropping them.
+    pub keep_punctuation: bool,
+    size: usize,
+    fill: SquareFill,
+    merge: LetterMerge,
+    matrix: Vec<Vec<char>>,
+    // position of each character in the square, read row by row
+    index: AlphabetIndex,
 }
 
 impl PlayfairCipher {
     fn clean_key_on_new(&mut self, key: &str) {
         let mut seen = HashSet::new();
         for c in key.to_ascii_uppercase().chars() {
-            if c == 'J' {
+            let c = self.merged(c);
+            if self.size == 5 && !c.is_ascii_alphabetic() {
+                continue;
+            }
+            if self.size == 6 && !c.is_ascii_alphanumeric() {
                 continue;
             }
             if seen.insert(c) {
@@ -28,49 +131,251 @@ impl PlayfairCipher {
         }
     }
 
-    fn generate_matrix(&mut self) {
-        let mut matrix: [[char; 5]; 5] = [[' '; 5]; 5];
-        let mut seen: HashSet<char> = HashSet::new();
+    /// Characters filling the square after the key, in order.
+    fn fill_alphabet(&self) -> Vec<char> {
+        match self.size {
+            5 => ('A'..='Z').filter(|&c| c != self.merge.from).collect(),
+            _ => ('A'..='Z').chain('0'..='9').collect(),
+        }
+    }
 
-        let mut index: usize = 0;
-        for c in self.key.chars() {
-            matrix[index / 5][index % 5] = c;
-            seen.insert(c);
-            index += 1;
+    fn generate_matrix(&mut self) {
+        let size = self.size;
+        let mut matrix: Vec<Vec<char>> = vec![vec![' '; size]; size];
+        let seen: HashSet<char> = self.key.chars().collect();
+
+        let mut rest: Vec<char> = self
+            .fill_alphabet()
+            .into_iter()
+            .filter(|c| !seen.contains(c))
+            .collect();
+        if self.fill == SquareFill::Reverse {
+            rest.reverse();
         }
 
-        for c in ('A'..='Z').filter(|&c| c != 'J' && !seen.contains(&c)) {
-            matrix[index / 5][index % 5] = c;
-            index += 1;
+        for (index, c) in self.key.chars().chain(rest).enumerate() {
+            match self.fill {
+                SquareFill::Columns => matrix[index % size][index / size] = c,
+                SquareFill::Rows | SquareFill::Reverse => matrix[index / size][index % size] = c,
+            }
         }
 
+        self.index = AlphabetIndex::new(&matrix.concat());
         self.matrix = matrix;
     }
 
-    fn get_char_indexes(&self, mut target: char) -> (usize, usize) {
-        if target == 'J' {
-            target = 'X';
+    /// The uppercase character as it appears in the square: the merged-away
+    /// letter of a 5x5 square becomes its stand-in.
+    fn merged(&self, c: char) -> char {
+        match self.size == 5 && c == self.merge.from {
+            true => self.merge.to,
+            false => c,
         }
+    }
 
-        for (row_index, row) in self.matrix.iter().enumerate() {
-            if let Some(col_index) = row.iter().position(|&c| c == target) {
-                return (row_index, col_index);
-            }
-        }
+    /// Row and column of the character in the square, looked up in O(1).
+    fn get_char_indexes(&self, target: char) -> (usize, usize) {
+        let position = self
+            .index
+            .get(self.merged(target))
+            .expect("Character not found in matrix, which should never happen");
 
-        panic!("Character not found in matrix, which should never happen");
+        (position / self.size, position % self.size)
+    }
+
+    /// The rows of the square the key produced.
+    pub fn square(&self) -> &[Vec<char>] {
+        &self.matrix
     }
 
     pub fn new(key: String) -> Self {
+        PlayfairCipher::with_square_size(key, 5)
+    }
+
+    /// Builds a Playfair cipher over a square of the given size.
+    ///
+    /// # Arguments
+    /// * `key` - A String that acts as the keyword for the square.
+    /// * `size` - 5 for the classic A–Z square with J merged into I,
+    ///   or 6 for A–Z plus the digits 0–9 with no merged letters.
+    ///
+    /// # Returns
+    /// A `PlayfairCipher` instance with its square generated.
+    pub fn with_square_size(key: String, size: usize) -> Self {
+        PlayfairCipher::with_fill(key, size, SquareFill::Rows)
+    }
+
+    /// Builds a Playfair cipher over a square of the given size, filled in
+    /// the given order.
+    ///
+    /// # Arguments
+    /// * `key` - A String that acts as the keyword for the square.
+    /// * `size` - 5 or 6, as for `with_square_size`.
+    /// * `fill` - How the keyword and the rest of the alphabet are laid out.
+    ///
+    /// # Returns
+    /// A `PlayfairCipher` instance with its square generated.
+    pub fn with_fill(key: String, size: usize, fill: SquareFill) -> Self {
+        PlayfairCipher::with_merge(key, size, fill, LetterMerge::default())
+    }
+
+    /// Builds a Playfair cipher that leaves a chosen letter out of the 5x5
+    /// square, reading it as another wherever it appears in the key or text.
+    ///
+    /// # Arguments
+    /// * `key` - A String that acts as the keyword for the square.
+    /// * `size` - 5 or 6, as for `with_square_size`; a 6x6 square holds the
+    ///   whole alphabet and ignores the merge.
+    /// * `fill` - How the keyword and the rest of the alphabet are laid out.
+    /// * `merge` - The letter to leave out and the letter that replaces it.
+    ///
+    /// # Returns
+    /// A `PlayfairCipher` instance with its square generated.
+    pub fn with_merge(key: String, size: usize, fill: SquareFill, merge: LetterMerge) -> Self {
+        assert!(
+            size == 5 || size == 6,
+            "Playfair square size must be 5 or 6"
+        );
+
         let mut cipher = PlayfairCipher {
             key: String::new(),
-            matrix: [[' '; 5]; 5],
+            keep_punctuation: false,
+            size,
+            fill,
+            merge,
+            matrix: Vec::new(),
+            index: AlphabetIndex::new(&[]),
         };
         cipher.clean_key_on_new(&key);
         cipher.generate_matrix();
         cipher
     }
 
+    /// Determines which rule applies to a digraph of uppercase letters.
+    ///
+    /// Doubled letters are compared as though the second were the 'X' filler.
+    fn digraph_rule(&self, primary_char: char, mut secondary_char: char) -> DigraphRule {
+        if primary_char == secondary_char {
+            secondary_char = 'X';
+        }
+
+        let (primary_row_index, primary_col_index) = self.get_char_indexes(primary_char);
+        let (secondary_row_index, secondary_col_index) = self.get_char_indexes(secondary_char);
+
+        match (
+            primary_row_index == secondary_row_index,
+            primary_col_index == secondary_col_index,
+        ) {
+            (true, _) => DigraphRule::SameRow,
+            (_, true) => DigraphRule::SameColumn,
+            (false, false) => DigraphRule::Rectangle,
+        }
+    }
+
+    /// Breaks the plaintext into digraphs and reports how each one is encrypted.
+    ///
+    /// # Arguments
+    /// * `plaintext` - A string slice that holds the text to be explained.
+    ///
+    /// # Returns
+    /// A `Vec` of `DigraphStep`s in uppercase, one per digraph.
+    pub fn explain_encrypt(&self, plaintext: &str) -> Vec<DigraphStep> {
+        self.explain(plaintext, EncryptionDirection::Encrypt)
+    }
+
+    /// Breaks the ciphertext into digraphs and reports how each one is decrypted.
+    ///
+    /// # Arguments
+    /// * `ciphertext` - A string slice that holds the text to be explained.
+    ///
+    /// # Returns
+    /// A `Vec` of `DigraphStep`s in uppercase, one per digraph.
+    pub fn explain_decrypt(&self, ciphertext: &str) -> Vec<DigraphStep> {
+        self.explain(ciphertext, EncryptionDirection::Decrypt)
+    }
+
+    fn explain(&self, text: &str, direction: EncryptionDirection) -> Vec<DigraphStep> {
+        let mut chars: Vec<char> = self.square_chars(&text.to_ascii_uppercase());
+        if !chars.len().is_multiple_of(2) {
+            chars.push('X');
+        }
+
+        chars
+            .chunks(2)
+            .map(|pair| DigraphStep {
+                input: (pair[0], pair[1]),
+                rule: self.digraph_rule(pair[0], pair[1]),
+                output: self.swap_chars(pair[0], pair[1], direction),
+            })
+            .collect()
+    }
+
+    /// Whether the character (in either case) has a place in the square.
+    fn in_square(&self, c: char) -> bool {
+        match self.size {
+            5 => c.is_ascii_alphabetic(),
+            _ => c.is_ascii_alphanumeric(),
+        }
+    }
+
+    /// Number of letters of the ciphertext that the square decrypts.
+    ///
+    /// Encryption always produces whole digraphs, so valid Playfair
+    /// ciphertext has an even count; an odd one means it was cut short or
+    /// mistyped, and decryption pads it with 'x' to pair up the last letter.
+    pub fn ciphertext_letters(&self, ciphertext: &str) -> usize {
+        self.square_chars(ciphertext).len()
+    }
+
+    /// The characters of the text that have a place in the square, in order.
+    fn square_chars(&self, text: &str) -> Vec<char> {
+        text.chars().filter(|&c| self.in_square(c)).collect()
+    }
+
+    /// Substitutes the digraphs of the text, ignoring characters outside the square.
+    ///
+    /// Odd-length text is padded with 'x', which for ciphertext only happens
+    /// when it isn't valid Playfair output. Lowercase input letters give
+    /// lowercase output letters. With `keep_punctuation`, every ignored
+    /// character is put back at its original position and the padding goes last.
+    fn transform(&self, text: &str, direction: EncryptionDirection) -> String {
+        let mut chars = self.square_chars(text);
+        if !chars.len().is_multiple_of(2) {
+            chars.push('x');
+        }
+
+        let mut output: Vec<char> = Vec::with_capacity(chars.len());
+        for pair in chars.chunks(2) {
+            let (primary_char, secondary_char) = self.swap_chars(
+                pair[0].to_ascii_uppercase(),
+                pair[1].to_ascii_uppercase(),
+                direction,
+            );
+
+            for (input_char, output_char) in [(pair[0], primary_char), (pair[1], secondary_char)] {
+                output.push(match input_char.is_ascii_lowercase() {
+                    true => output_char.to_ascii_lowercase(),
+                    false => output_char,
+                });
+            }
+        }
+
+        if !self.keep_punctuation {
+            return output.into_iter().collect();
+        }
+
+        let mut substituted = output.into_iter();
+        let mut restored: String = text
+            .chars()
+            .map(|c| match self.in_square(c) {
+                true => substituted.next().unwrap(),
+                false => c,
+            })
+            .collect();
+        restored.extend(substituted);
+        restored
+    }
+
     fn swap_chars(
         &self,
         primary_char: char,
@@ -84,6 +389,7 @@ impl PlayfairCipher {
         let (primary_row_index, primary_col_index) = self.get_char_indexes(primary_char);
         let (secondary_row_index, secondary_col_index) = self.get_char_indexes(secondary_char);
 
+        let size = self.size as i8;
         let translation: i8 = match direction {
             EncryptionDirection::Encrypt => 1,
             EncryptionDirection::Decrypt => -1,
@@ -95,14 +401,16 @@ impl PlayfairCipher {
         ) {
             (true, _) => (
                 self.matrix[primary_row_index]
-                    [(((primary_col_index as i8 + translation) % 5 + 5) % 5) as usize],
+                    [(((primary_col_index as i8 + translation) % size + size) % size) as usize],
                 self.matrix[secondary_row_index]
-                    [(((secondary_col_index as i8 + translation) % 5 + 5) % 5) as usize],
+                    [(((secondary_col_index as i8 + translation) % size + size) % size) as usize],
             ),
             (_, true) => (
-                self.matrix[(((primary_row_index as i8 + translation) % 5 + 5) % 5) as usize]
+                self.matrix
+                    [(((primary_row_index as i8 + translation) % size + size) % size) as usize]
                     [primary_col_index],
-                self.matrix[(((secondary_row_index as i8 + translation) % 5 + 5) % 5) as usize]
+                self.matrix
+                    [(((secondary_row_index as i8 + translation) % size + size) % size) as usize]
                     [secondary_col_index],
             ),
             (false, false) => (
@@ -114,73 +422,241 @@ impl PlayfairCipher {
 }
 
 impl Cipher for PlayfairCipher {
+    fn name(&self) -> &'static str {
+        "Playfair cipher"
+    }
+
     fn encrypt(&self, plaintext: &str) -> String {
-        let mut plaintext_string = plaintext.to_string();
-        if plaintext_string.len() % 2 != 0 {
-            plaintext_string.push('x');
-        }
+        self.transform(plaintext, EncryptionDirection::Encrypt)
+    }
 
-        let mut ciphertext: String = String::with_capacity(plaintext_string.len());
-        for i in (0..plaintext.len()).step_by(2) {
-            let primary_plaintext_char = plaintext_string.chars().nth(i).unwrap();
-            let secondary_plaintext_char = plaintext_string.chars().nth(i + 1).unwrap();
+    fn decrypt(&self, ciphertext: &str) -> String {
+        self.transform(ciphertext, EncryptionDirection::Decrypt)
+    }
 
-            let (mut primary_ciphertext_char, mut secondary_ciphertext_char) = self.swap_chars(
-                primary_plaintext_char.to_ascii_uppercase(),
-                secondary_plaintext_char.to_ascii_uppercase(),
-                EncryptionDirection::Encrypt,
-            );
+    /// Letters are paired up, padded with filler and stripped of anything
+    /// outside the square, so the length changes.
+    fn preserves_length(&self) -> bool {
+        false
+    }
 
-            primary_ciphertext_char = match primary_plaintext_char.is_ascii_uppercase() {
-                true => primary_ciphertext_char,
-                false => primary_ciphertext_char.to_ascii_lowercase(),
-            };
-            secondary_ciphertext_char = match secondary_plaintext_char.is_ascii_uppercase() {
-                true => secondary_ciphertext_char,
-                false => secondary_ciphertext_char.to_ascii_lowercase(),
-            };
+    /// The key can place at most one distinct character per cell of the
+    /// square; an empty key just leaves the square unkeyed.
+    fn key_length_bounds(&self) -> (usize, Option<usize>) {
+        (0, Some(self.size * self.size))
+    }
+}
 
-            ciphertext.push(primary_ciphertext_char);
-            ciphertext.push(secondary_ciphertext_char);
-        }
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Instant;
 
-        ciphertext
+    /// Finds the character by scanning the square row by row, as the
+    /// cipher did before it kept an index.
+    fn scan_char_indexes(cipher: &PlayfairCipher, target: char) -> (usize, usize) {
+        let target = cipher.merged(target);
+        cipher
+            .square()
+            .iter()
+            .enumerate()
+            .find_map(|(row, chars)| {
+                chars
+                    .iter()
+                    .position(|&c| c == target)
+                    .map(|col| (row, col))
+            })
+            .unwrap()
     }
 
-    fn decrypt(&self, ciphertext: &str) -> String {
-        let mut ciphertext_string = ciphertext.to_string();
-        if ciphertext_string.len() % 2 != 0 {
-            ciphertext_string.push('x');
+    #[test]
+    fn test_index_matches_scan() {
+        for fill in [SquareFill::Rows, SquareFill::Columns, SquareFill::Reverse] {
+            for size in [5, 6] {
+                let cipher = PlayfairCipher::with_fill("playfair 1854".to_string(), size, fill);
+                for c in ('A'..='Z')
+                    .chain('0'..='9')
+                    .filter(|&c| size == 6 || c.is_alphabetic())
+                {
+                    assert_eq!(
+                        cipher.get_char_indexes(c),
+                        scan_char_indexes(&cipher, c),
+                        "{} in {:?} {}x{}",
+                        c,
+                        fill,
+                        size,
+                        size
+                    );
+                }
+            }
         }
+    }
 
-        let mut plaintext: String = String::with_capacity(ciphertext_string.len());
-        for i in (0..ciphertext_string.len()).step_by(2) {
-            let (primary_char, secondary_char) = self.swap_chars(
-                ciphertext_string.chars().nth(i).unwrap(),
-                ciphertext_string.chars().nth(i + 1).unwrap(),
-                EncryptionDirection::Decrypt,
-            );
-            plaintext.push(primary_char);
-            plaintext.push(secondary_char);
+    /// Compares scanning the square with the index lookup on a large input.
+    /// Run with `cargo test --release -- --ignored --nocapture`.
+    #[test]
+    #[ignore]
+    fn bench_char_indexes() {
+        let cipher = PlayfairCipher::new("playfair example".to_string());
+        let input: Vec<char> = "THEQUICKBROWNFOXJUMPSOVERTHELAZYDOG"
+            .chars()
+            .cycle()
+            .take(5_000_000)
+            .collect();
+
+        let start = Instant::now();
+        let scanned: Vec<(usize, usize)> = input
+            .iter()
+            .map(|&c| scan_char_indexes(&cipher, c))
+            .collect();
+        let scan_time = start.elapsed();
+
+        let start = Instant::now();
+        let indexed: Vec<(usize, usize)> =
+            input.iter().map(|&c| cipher.get_char_indexes(c)).collect();
+        let index_time = start.elapsed();
+
+        println!("scanning the square: {:?}", scan_time);
+        println!("indexed lookup: {:?}", index_time);
+        assert_eq!(scanned, indexed);
+    }
+
+    #[test]
+    fn test_fill_orders() {
+        let square = |fill| {
+            PlayfairCipher::with_fill("keyword".to_string(), 5, fill)
+                .square()
+                .iter()
+                .map(|row| row.iter().collect::<String>())
+                .collect::<Vec<String>>()
+        };
+
+        assert_eq!(
+            square(SquareFill::Rows),
+            ["KEYWO", "RDABC", "FGHIL", "MNPQS", "TUVXZ"]
+        );
+        assert_eq!(
+            square(SquareFill::Columns),
+            ["KRFMT", "EDGNU", "YAHPV", "WBIQX", "OCLSZ"]
+        );
+        assert_eq!(
+            square(SquareFill::Reverse),
+            ["KEYWO", "RDZXV", "UTSQP", "NMLIH", "GFCBA"]
+        );
+    }
+
+    #[test]
+    fn test_merge_w_into_v() {
+        let merge: LetterMerge = "w=v".parse().unwrap();
+        let cipher = PlayfairCipher::with_merge("keyword".to_string(), 5, SquareFill::Rows, merge);
+        let rows: Vec<String> = cipher
+            .square()
+            .iter()
+            .map(|row| row.iter().collect())
+            .collect();
+
+        assert_eq!(rows, ["KEYVO", "RDABC", "FGHIJ", "LMNPQ", "STUXZ"]);
+        assert_eq!(cipher.decrypt(&cipher.encrypt("JUMPATDAWN")), "JUMPATDAVN");
+    }
+
+    #[test]
+    fn test_letter_merge_parsing() {
+        assert_eq!(
+            "V=W".parse::<LetterMerge>(),
+            Ok(LetterMerge { from: 'V', to: 'W' })
+        );
+        assert_eq!(LetterMerge::default().to_string(), "J=I");
+        assert!("V=V".parse::<LetterMerge>().is_err());
+        assert!("v=V".parse::<LetterMerge>().is_err());
+        assert!("VW".parse::<LetterMerge>().is_err());
+        assert!("1=A".parse::<LetterMerge>().is_err());
+        assert!("Q=AB".parse::<LetterMerge>().is_err());
+    }
+
+    #[test]
+    fn test_fill_orders_round_trip() {
+        for fill in [SquareFill::Rows, SquareFill::Columns, SquareFill::Reverse] {
+            for size in [5, 6] {
+                let cipher = PlayfairCipher::with_fill("keyword".to_string(), size, fill);
+                let ciphertext = cipher.encrypt("ATTACKATDAWN");
+
+                assert_eq!(cipher.decrypt(&ciphertext), "ATTACKATDAWN", "{:?}", fill);
+            }
         }
+        assert_ne!(
+            PlayfairCipher::with_fill("keyword".to_string(), 5, SquareFill::Columns)
+                .encrypt("ATTACKATDAWN"),
+            PlayfairCipher::new("keyword".to_string()).encrypt("ATTACKATDAWN")
+        );
+    }
+
+    #[test]
+    fn test_ciphertext_letters() {
+        let cipher = PlayfairCipher::new("keyword".to_string());
 
-        plaintext
+        assert_eq!(cipher.ciphertext_letters(&cipher.encrypt("HELLO")), 6);
+        assert_eq!(cipher.ciphertext_letters("GY IZ S"), 5);
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    #[test]
+    fn test_playfair_ignores_punctuation() {
+        let cipher = PlayfairCipher::new("keyword".to_string());
+        let ciphertext = cipher.encrypt("HELLO, WORLD!");
+        let plaintext = cipher.decrypt(&ciphertext);
+
+        assert_eq!(ciphertext, cipher.encrypt("HELLOWORLD"));
+        assert_eq!(plaintext, "HELXOWORLD");
+    }
+
+    #[test]
+    fn test_playfair_keep_punctuation() {
+        let mut cipher = PlayfairCipher::new("keyword".to_string());
+        cipher.keep_punctuation = true;
+        let ciphertext = cipher.encrypt("HELLO, WORLD!");
+        let plaintext = cipher.decrypt(&ciphertext);
+
+        assert_eq!(
+            ciphertext,
+            format!(
+                "{}, {}!",
+                &cipher.encrypt("HELLOWORLD")[..5],
+                &cipher.encrypt("HELLOWORLD")[5..]
+            )
+        );
+        assert_eq!(plaintext, "HELXO, WORLD!");
+    }
+
+    #[test]
+    fn test_key_ignores_spaces_and_digits() {
+        let cipher = PlayfairCipher::new("key word 123!".to_string());
+        let expected = PlayfairCipher::new("keyword".to_string());
+
+        assert_eq!(cipher.matrix, expected.matrix);
+    }
+
+    #[test]
+    fn test_key_covering_whole_alphabet() {
+        let cipher = PlayfairCipher::new("the quick brown fox jumps over the lazy dog".to_string());
+        let letters: HashSet<char> = cipher.matrix.iter().flatten().copied().collect();
+
+        assert_eq!(letters.len(), 25);
+        assert!(!letters.contains(&'J'));
+        assert_eq!(cipher.matrix[0], vec!['T', 'H', 'E', 'Q', 'U']);
+        assert_eq!(
+            cipher.decrypt(&cipher.encrypt("ATTACKATDAWN")),
+            "ATTACKATDAWN"
+        );
+    }
 
     #[test]
     fn test_generate_matrix() {
-        let cipher: PlayfairCipher = PlayfairCipher::new("keyword");
-        let expected: [[char; 5]; 5] = [
-            ['K', 'E', 'Y', 'W', 'O'],
-            ['R', 'D', 'A', 'B', 'C'],
-            ['F', 'G', 'H', 'I', 'L'],
-            ['M', 'N', 'P', 'Q', 'S'],
-            ['T', 'U', 'V', 'X', 'Z'],
+        let cipher: PlayfairCipher = PlayfairCipher::new("keyword".to_string());
+        let expected: Vec<Vec<char>> = vec![
+            vec!['K', 'E', 'Y', 'W', 'O'],
+            vec!['R', 'D', 'A', 'B', 'C'],
+            vec!['F', 'G', 'H', 'I', 'L'],
+            vec!['M', 'N', 'P', 'Q', 'S'],
+            vec!['T', 'U', 'V', 'X', 'Z'],
         ];
 
         assert_eq!(cipher.matrix, expected);
@@ -188,7 +664,7 @@ mod tests {
 
     #[test]
     fn test_get_char_indexes() {
-        let cipher: PlayfairCipher = PlayfairCipher::new("keyword");
+        let cipher: PlayfairCipher = PlayfairCipher::new("keyword".to_string());
 
         let (char_row, char_col) = cipher.get_char_indexes('C');
         let (expected_row, expected_col) = (1, 4);
@@ -199,7 +675,7 @@ mod tests {
 
     #[test]
     fn test_swap_chars() {
-        let cipher: PlayfairCipher = PlayfairCipher::new("keyword");
+        let cipher: PlayfairCipher = PlayfairCipher::new("keyword".to_string());
 
         let (primary_row_swap_char, secondary_row_swap_char) =
             cipher.swap_chars('D', 'B', EncryptionDirection::Encrypt);
@@ -226,7 +702,7 @@ mod tests {
 
     #[test]
     fn test_playfair_cipher_encrypt() {
-        let cipher: PlayfairCipher = PlayfairCipher::new("keyword");
+        let cipher: PlayfairCipher = PlayfairCipher::new("keyword".to_string());
         let ciphertext1 = cipher.encrypt("SECRET");
         let ciphertext2 = cipher.encrypt("secret");
 
@@ -236,9 +712,68 @@ mod tests {
 
     #[test]
     fn test_playfair_cipher_decrypt() {
-        let cipher: PlayfairCipher = PlayfairCipher::new("keyword");
+        let cipher: PlayfairCipher = PlayfairCipher::new("keyword".to_string());
         let plaintext = cipher.decrypt("NORDKU");
 
         assert_eq!(plaintext, "SECRET");
     }
+
+    #[test]
+    fn test_playfair_cipher_decrypt_lowercase() {
+        let cipher: PlayfairCipher = PlayfairCipher::new("keyword".to_string());
+
+        assert_eq!(cipher.decrypt("nordku"), "secret");
+    }
+
+    #[test]
+    fn test_generate_matrix_six_by_six() {
+        let cipher: PlayfairCipher = PlayfairCipher::with_square_size("key2024".to_string(), 6);
+        let expected: Vec<Vec<char>> = vec![
+            vec!['K', 'E', 'Y', '2', '0', '4'],
+            vec!['A', 'B', 'C', 'D', 'F', 'G'],
+            vec!['H', 'I', 'J', 'L', 'M', 'N'],
+            vec!['O', 'P', 'Q', 'R', 'S', 'T'],
+            vec!['U', 'V', 'W', 'X', 'Z', '1'],
+            vec!['3', '5', '6', '7', '8', '9'],
+        ];
+
+        assert_eq!(cipher.matrix, expected);
+    }
+
+    #[test]
+    fn test_playfair_cipher_six_by_six() {
+        let cipher: PlayfairCipher = PlayfairCipher::with_square_size("key2024".to_string(), 6);
+        let ciphertext = cipher.encrypt("MEETAT1945");
+        let plaintext = cipher.decrypt(&ciphertext);
+
+        assert_eq!(ciphertext, "I04PGO94E9");
+        assert_eq!(plaintext, "MEETAT1945");
+    }
+
+    #[test]
+    fn test_explain_encrypt() {
+        let cipher: PlayfairCipher = PlayfairCipher::new("keyword".to_string());
+        let steps = cipher.explain_encrypt("dbdndq");
+
+        assert_eq!(
+            steps,
+            vec![
+                DigraphStep {
+                    input: ('D', 'B'),
+                    rule: DigraphRule::SameRow,
+                    output: ('A', 'C'),
+                },
+                DigraphStep {
+                    input: ('D', 'N'),
+                    rule: DigraphRule::SameColumn,
+                    output: ('G', 'U'),
+                },
+                DigraphStep {
+                    input: ('D', 'Q'),
+                    rule: DigraphRule::Rectangle,
+                    output: ('B', 'N'),
+                },
+            ]
+        );
+    }
 }
@@ -0,0 +1,138 @@
+//! The `quagmire` module provides an implementation of the Quagmire I cipher
+
+use super::alphabet::Alphabet;
+use super::Cipher;
+
+/// A `QuagmireCipher` represents the Quagmire I cipher.
+/// Plaintext letters are looked up in a keyed alphabet and enciphered
+/// into a straight A-Z alphabet, shifted for each letter so that the
+/// current indicator letter sits under the plaintext `A`. The indicator
+/// advances on every letter, like a Vigenère key over a scrambled alphabet
+pub struct QuagmireCipher {
+    pub plaintext_alphabet: Alphabet,
+    pub indicator: Vec<u8>,
+}
+
+impl QuagmireCipher {
+    /// Creates a Quagmire I cipher.
+    ///
+    /// # Arguments
+    /// * `alphabet_keyword` - Keyword that scrambles the plaintext alphabet.
+    /// * `indicator` - Keyword whose letters pick the shift of each ciphertext alphabet.
+    ///   Non-letters are ignored.
+    ///
+    /// # Returns
+    /// A `QuagmireCipher`, or an error if the indicator has no letters.
+    pub fn new(alphabet_keyword: &str, indicator: &str) -> Result<QuagmireCipher, String> {
+        let indicator: Vec<u8> = indicator
+            .chars()
+            .filter(|c| c.is_ascii_alphabetic())
+            .map(|c| c.to_ascii_uppercase() as u8 - b'A')
+            .collect();
+        if indicator.is_empty() {
+            return Err("Quagmire I indicator must contain letters.".to_string());
+        }
+
+        Ok(QuagmireCipher {
+            plaintext_alphabet: Alphabet::keyed(alphabet_keyword),
+            indicator,
+        })
+    }
+
+    /// Substitutes every uppercased letter of the text with `substitute`,
+    /// given the letter and its indicator shift. Other characters are left
+    /// in place and don't advance the indicator; case is preserved.
+    fn apply(&self, text: &str, substitute: impl Fn(char, usize) -> char) -> String {
+        let mut letter_index = 0;
+        text.chars()
+            .map(|c| match c.is_ascii_alphabetic() {
+                true => {
+                    let key = self.indicator[letter_index % self.indicator.len()] as usize;
+                    letter_index += 1;
+                    let substituted = substitute(c.to_ascii_uppercase(), key);
+                    match c.is_ascii_lowercase() {
+                        true => substituted.to_ascii_lowercase(),
+                        false => substituted,
+                    }
+                }
+                false => c,
+            })
+            .collect()
+    }
+
+    /// Position of the plaintext `A` in the keyed alphabet.
+    fn anchor(&self) -> usize {
+        self.plaintext_alphabet.index_of('A').unwrap()
+    }
+}
+
+impl Cipher for QuagmireCipher {
+    fn name(&self) -> &'static str {
+        "Quagmire I cipher"
+    }
+
+    /// Encrypts the given plaintext through the keyed and straight alphabets.
+    ///
+    /// # Arguments
+    /// * `plaintext` - A string slice that holds the text to be encrypted.
+    ///
+    /// # Returns
+    /// A `String` containing the encrypted text.
+    fn encrypt(&self, plaintext: &str) -> String {
+        let anchor = self.anchor();
+        self.apply(plaintext, |c, key| {
+            let position = self.plaintext_alphabet.index_of(c).unwrap();
+            (b'A' + ((key + 26 - anchor + position) % 26) as u8) as char
+        })
+    }
+
+    /// Decrypts the given ciphertext back through the straight and keyed alphabets.
+    ///
+    /// # Arguments
+    /// * `ciphertext` - A string slice that holds the text to be decrypted.
+    ///
+    /// # Returns
+    /// A `String` containing the decrypted text.
+    fn decrypt(&self, ciphertext: &str) -> String {
+        let anchor = self.anchor();
+        self.apply(ciphertext, |c, key| {
+            let position = (c as usize - 'A' as usize + 26 - key + anchor) % 26;
+            self.plaintext_alphabet.char_at(position)
+        })
+    }
+
+    /// Only ASCII letters are enciphered.
+    fn transforms(&self, c: char) -> bool {
+        c.is_ascii_alphabetic()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_quagmire_encrypt() {
+        let cipher = QuagmireCipher::new("key", "b").unwrap();
+        let ciphertext = cipher.encrypt("AK");
+
+        assert_eq!(ciphertext, "BY");
+    }
+
+    #[test]
+    fn test_quagmire_round_trip() {
+        let cipher = QuagmireCipher::new("spring fever", "flower").unwrap();
+        let plaintext = "Don't let anyone tell you the sky's the limit!";
+        let ciphertext = cipher.encrypt(plaintext);
+
+        assert_ne!(ciphertext, plaintext);
+        assert_eq!(cipher.decrypt(&ciphertext), plaintext);
+    }
+
+    #[test]
+    fn test_quagmire_rejects_empty_indicator() {
+        let cipher = QuagmireCipher::new("key", "123");
+
+        assert!(cipher.is_err());
+    }
+}
@@ -0,0 +1,195 @@
+//! The `rail_fence` module provides an implementation of the rail fence cipher
+
+use super::Cipher;
+
+/// A `RailFenceCipher` represents the rail fence transposition cipher.
+/// It writes the plaintext in a zigzag across `rails` rows and reads
+/// the rows off top to bottom. A nonzero `offset` starts the zigzag
+/// partway through its cycle instead of at the top rail
+pub struct RailFenceCipher {
+    pub rails: usize,
+    pub offset: usize,
+}
+
+impl RailFenceCipher {
+    /// Computes which rail each character position falls on.
+    ///
+    /// # Arguments
+    /// * `length` - Number of characters in the text.
+    ///
+    /// # Returns
+    /// A `Vec` holding the rail index of every position.
+    fn rail_pattern(&self, length: usize) -> Vec<usize> {
+        if self.rails <= 1 {
+            return vec![0; length];
+        }
+
+        let cycle = 2 * (self.rails - 1);
+        let offset = self.offset % cycle;
+        (0..length)
+            .map(|i| {
+                let step = (i + offset) % cycle;
+                match step < self.rails {
+                    true => step,
+                    false => cycle - step,
+                }
+            })
+            .collect()
+    }
+
+    /// Lays the plaintext out on the rails, one line per rail, with `.`
+    /// marking the positions that fall on another rail.
+    ///
+    /// # Examples
+    /// ```
+    /// let cipher = RailFenceCipher { rails: 3, offset: 0 };
+    /// assert_eq!(cipher.grid("HELLO"), ["H...O", ".E.L.", "..L.."]);
+    /// ```
+    pub fn grid(&self, plaintext: &str) -> Vec<String> {
+        let chars: Vec<char> = plaintext.chars().collect();
+        let pattern = self.rail_pattern(chars.len());
+        (0..self.rails.max(1))
+            .map(|rail| {
+                chars
+                    .iter()
+                    .zip(&pattern)
+                    .map(|(&c, &on)| if on == rail { c } else { '.' })
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Orders the character positions rail by rail, left-to-right within a rail.
+    fn read_order(&self, length: usize) -> Vec<usize> {
+        let pattern = self.rail_pattern(length);
+        let mut order: Vec<usize> = (0..length).collect();
+        order.sort_by_key(|&i| pattern[i]);
+        order
+    }
+}
+
+impl Cipher for RailFenceCipher {
+    fn name(&self) -> &'static str {
+        "Rail fence cipher"
+    }
+
+    /// Encrypts the given plaintext by reading the zigzag rail by rail.
+    ///
+    /// # Arguments
+    /// * `plaintext` - A string slice that holds the text to be encrypted.
+    ///
+    /// # Returns
+    /// A `String` containing the encrypted text.
+    fn encrypt(&self, plaintext: &str) -> String {
+        let chars: Vec<char> = plaintext.chars().collect();
+        self.read_order(chars.len())
+            .into_iter()
+            .map(|index| chars[index])
+            .collect()
+    }
+
+    /// Decrypts the given ciphertext by laying it back onto the rails.
+    ///
+    /// The same offset used to encrypt must be supplied.
+    ///
+    /// # Arguments
+    /// * `ciphertext` - A string slice that holds the text to be decrypted.
+    ///
+    /// # Returns
+    /// A `String` containing the decrypted text.
+    fn decrypt(&self, ciphertext: &str) -> String {
+        let chars: Vec<char> = ciphertext.chars().collect();
+        let mut plaintext = vec![' '; chars.len()];
+        for (c, index) in chars.iter().zip(self.read_order(chars.len())) {
+            plaintext[index] = *c;
+        }
+
+        plaintext.into_iter().collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rail_pattern() {
+        let cipher = RailFenceCipher {
+            rails: 3,
+            offset: 0,
+        };
+        let offset_cipher = RailFenceCipher {
+            rails: 3,
+            offset: 2,
+        };
+
+        assert_eq!(cipher.rail_pattern(6), vec![0, 1, 2, 1, 0, 1]);
+        assert_eq!(offset_cipher.rail_pattern(6), vec![2, 1, 0, 1, 2, 1]);
+    }
+
+    #[test]
+    fn test_grid() {
+        let cipher = RailFenceCipher {
+            rails: 3,
+            offset: 0,
+        };
+
+        assert_eq!(
+            cipher.grid("WEAREDISCOVERED"),
+            ["W...E...C...R..", ".E.R.D.S.O.E.E.", "..A...I...V...D"]
+        );
+    }
+
+    #[test]
+    fn test_rail_fence_cipher_encrypt() {
+        let cipher = RailFenceCipher {
+            rails: 3,
+            offset: 0,
+        };
+        let ciphertext = cipher.encrypt("WEAREDISCOVEREDFLEEATONCE");
+
+        assert_eq!(ciphertext, "WECRLTEERDSOEEFEAOCAIVDEN");
+    }
+
+    #[test]
+    fn test_rail_fence_cipher_decrypt() {
+        let cipher = RailFenceCipher {
+            rails: 3,
+            offset: 0,
+        };
+        let plaintext = cipher.decrypt("WECRLTEERDSOEEFEAOCAIVDEN");
+
+        assert_eq!(plaintext, "WEAREDISCOVEREDFLEEATONCE");
+    }
+
+    #[test]
+    fn test_rail_fence_cipher_offset() {
+        let cipher = RailFenceCipher {
+            rails: 3,
+            offset: 0,
+        };
+        let offset_cipher = RailFenceCipher {
+            rails: 3,
+            offset: 1,
+        };
+        let ciphertext = cipher.encrypt("WEAREDISCOVEREDFLEEATONCE");
+        let offset_ciphertext = offset_cipher.encrypt("WEAREDISCOVEREDFLEEATONCE");
+        let plaintext = offset_cipher.decrypt(&offset_ciphertext);
+
+        assert_ne!(offset_ciphertext, ciphertext);
+        assert_eq!(plaintext, "WEAREDISCOVEREDFLEEATONCE");
+    }
+
+    #[test]
+    fn test_rail_fence_cipher_max_offset() {
+        let cipher = RailFenceCipher {
+            rails: 3,
+            offset: usize::MAX,
+        };
+        let ciphertext = cipher.encrypt("WEAREDISCOVEREDFLEEATONCE");
+
+        // usize::MAX is 3 past a whole number of 4-step cycles
+        assert_eq!(cipher.rail_pattern(4), vec![1, 0, 1, 2]);
+        assert_eq!(cipher.decrypt(&ciphertext), "WEAREDISCOVEREDFLEEATONCE");
+    }
+}
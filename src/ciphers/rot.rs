@@ -0,0 +1,189 @@
+//! The `rot` module provides the keyless ROT18 and ROT47 rotation ciphers
+//! and a ROT-N over all Unicode scalar values
+
+use super::Cipher;
+
+/// Rotates `c` by `shift` within the contiguous range starting at `first`
+/// and spanning `len` characters. Characters outside the range are unchanged.
+///
+/// # Arguments
+/// * `c` - `char` to be rotated.
+/// * `first` - First `char` of the range.
+/// * `len` - Number of characters in the range.
+/// * `shift` - Number of positions to rotate forward.
+///
+/// # Returns
+/// Rotated `char`.
+fn rotate(c: char, first: char, len: u32, shift: u32) -> char {
+    let offset = (c as u32).wrapping_sub(first as u32);
+    match offset < len {
+        true => char::from_u32(first as u32 + (offset + shift) % len).unwrap(),
+        false => c,
+    }
+}
+
+/// A `Rot18Cipher` applies ROT13 to letters and ROT5 to digits.
+/// Both rotations are half their range, so the cipher is its own inverse
+pub struct Rot18Cipher;
+
+impl Cipher for Rot18Cipher {
+    fn name(&self) -> &'static str {
+        "ROT18"
+    }
+
+    fn encrypt(&self, plaintext: &str) -> String {
+        plaintext
+            .chars()
+            .map(|c| {
+                let c = rotate(c, 'a', 26, 13);
+                let c = rotate(c, 'A', 26, 13);
+                rotate(c, '0', 10, 5)
+            })
+            .collect()
+    }
+
+    fn decrypt(&self, ciphertext: &str) -> String {
+        self.encrypt(ciphertext)
+    }
+
+    /// Only ASCII letters and digits are rotated.
+    fn transforms(&self, c: char) -> bool {
+        c.is_ascii_alphanumeric()
+    }
+}
+
+/// A `Rot47Cipher` rotates all printable ASCII characters from `!` (33) to `~` (126)
+/// by 47 positions, which is half of that 94 character range, so it is its own inverse
+pub struct Rot47Cipher;
+
+impl Cipher for Rot47Cipher {
+    fn name(&self) -> &'static str {
+        "ROT47"
+    }
+
+    fn encrypt(&self, plaintext: &str) -> String {
+        plaintext.chars().map(|c| rotate(c, '!', 94, 47)).collect()
+    }
+
+    fn decrypt(&self, ciphertext: &str) -> String {
+        self.encrypt(ciphertext)
+    }
+
+    /// Only printable ASCII other than the space is rotated.
+    fn transforms(&self, c: char) -> bool {
+        c.is_ascii_graphic()
+    }
+}
+
+/// First code point of the UTF-16 surrogate range, which holds no `char`s.
+const SURROGATE_START: u32 = 0xD800;
+/// Number of code points in the surrogate range D800-DFFF.
+const SURROGATE_LEN: u32 = 0x800;
+/// Number of Unicode scalar values: every code point except the surrogates.
+const SCALAR_COUNT: u32 = 0x11_0000 - SURROGATE_LEN;
+
+/// A `UnicodeRotCipher` shifts every `char` by `shift` Unicode scalar values,
+/// wrapping from U+10FFFF back to U+0000 and stepping over the surrogate
+/// gap D800-DFFF so every result is a valid `char`
+pub struct UnicodeRotCipher {
+    pub shift: i64,
+}
+
+impl UnicodeRotCipher {
+    /// Shifts `c` by `shift` positions among the Unicode scalar values.
+    fn shift_char(c: char, shift: i64) -> char {
+        // Close the surrogate gap so the scalar values are contiguous
+        let value = c as u32;
+        let dense = match value < SURROGATE_START {
+            true => value,
+            false => value - SURROGATE_LEN,
+        };
+
+        let shifted = (dense as i64 + shift).rem_euclid(SCALAR_COUNT as i64) as u32;
+        let value = match shifted < SURROGATE_START {
+            true => shifted,
+            false => shifted + SURROGATE_LEN,
+        };
+        char::from_u32(value).unwrap()
+    }
+}
+
+impl Cipher for UnicodeRotCipher {
+    fn name(&self) -> &'static str {
+        "Unicode ROT"
+    }
+
+    fn encrypt(&self, plaintext: &str) -> String {
+        plaintext
+            .chars()
+            .map(|c| Self::shift_char(c, self.shift))
+            .collect()
+    }
+
+    fn decrypt(&self, ciphertext: &str) -> String {
+        ciphertext
+            .chars()
+            .map(|c| Self::shift_char(c, -self.shift))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rotate() {
+        assert_eq!(rotate('y', 'a', 26, 3), 'b');
+        assert_eq!(rotate('7', '0', 10, 5), '2');
+        assert_eq!(rotate(' ', 'a', 26, 3), ' ');
+    }
+
+    #[test]
+    fn test_rot18_cipher() {
+        let cipher = Rot18Cipher;
+        let ciphertext = cipher.encrypt("Hello World 2024!");
+        let plaintext = cipher.decrypt(&ciphertext);
+
+        assert_eq!(ciphertext, "Uryyb Jbeyq 7579!");
+        assert_eq!(cipher.encrypt(&ciphertext), "Hello World 2024!");
+        assert_eq!(plaintext, "Hello World 2024!");
+    }
+
+    #[test]
+    fn test_rot47_cipher() {
+        let cipher = Rot47Cipher;
+        let ciphertext = cipher.encrypt("Hello World 2024!");
+        let plaintext = cipher.decrypt(&ciphertext);
+
+        assert_eq!(ciphertext, "w6==@ (@C=5 a_acP");
+        assert_eq!(cipher.encrypt(&ciphertext), "Hello World 2024!");
+        assert_eq!(plaintext, "Hello World 2024!");
+    }
+
+    #[test]
+    fn test_unicode_rot_skips_surrogates() {
+        let cipher = UnicodeRotCipher { shift: 1 };
+        let before_gap = cipher.encrypt("\u{D7FF}");
+        let after_gap = cipher.decrypt("\u{E000}");
+        let wrapped = cipher.encrypt("\u{10FFFF}");
+
+        assert_eq!(before_gap, "\u{E000}");
+        assert_eq!(after_gap, "\u{D7FF}");
+        assert_eq!(wrapped, "\u{0}");
+    }
+
+    #[test]
+    fn test_unicode_rot_round_trip() {
+        let cipher = UnicodeRotCipher { shift: 0x800 };
+        let plaintext = "Héllo \u{D7FE}\u{E001} 🦀 \u{10FFFE}";
+        let ciphertext = cipher.encrypt(plaintext);
+
+        assert_ne!(ciphertext, plaintext);
+        assert_eq!(cipher.decrypt(&ciphertext), plaintext);
+        assert_eq!(
+            UnicodeRotCipher { shift: -3 }.encrypt("\u{1}"),
+            "\u{10FFFE}"
+        );
+    }
+}
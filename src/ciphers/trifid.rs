@@ -0,0 +1,197 @@
+//! The `trifid` module provides an implementation of Delastelle's Trifid cipher
+
+use super::Cipher;
+use std::collections::HashSet;
+
+/// Number of symbols in the 3x3x3 cube.
+const CUBE_SIZE: usize = 27;
+
+/// A `TrifidCipher` represents Delastelle's Trifid cipher.
+/// Each symbol of the keyed alphabet sits in a 3x3x3 cube and is split
+/// into its layer, row and column. Within every block of `period` symbols
+/// the coordinates are written out layer first, then row, then column,
+/// and read back in triples to give the ciphertext
+pub struct TrifidCipher {
+    pub alphabet: Vec<char>,
+    pub period: usize,
+}
+
+impl TrifidCipher {
+    /// Creates a Trifid cipher from a keyed alphabet.
+    ///
+    /// # Arguments
+    /// * `alphabet` - The 27 distinct symbols filling the cube, layer by layer.
+    ///   Letters are uppercased.
+    /// * `period` - Number of symbols fractionated together; zero means
+    ///   the whole message is one block.
+    ///
+    /// # Returns
+    /// A `TrifidCipher`, or an error if the alphabet isn't exactly 27 distinct symbols.
+    ///
+    /// # Examples
+    /// ```
+    /// let cipher = TrifidCipher::new("FELIXMARDSTBCGHJKNOPQUVWYZ+", 5).unwrap();
+    /// assert_eq!(cipher.encrypt("AIDETOILECIELTAIDERA"), "FMJFVOISSUFTFPUFEQQC");
+    /// ```
+    pub fn new(alphabet: &str, period: usize) -> Result<TrifidCipher, String> {
+        let alphabet: Vec<char> = alphabet.to_uppercase().chars().collect();
+        if alphabet.len() != CUBE_SIZE {
+            return Err(format!(
+                "Trifid alphabet must have {} symbols, found {}.",
+                CUBE_SIZE,
+                alphabet.len()
+            ));
+        }
+
+        let mut seen = HashSet::new();
+        if let Some(c) = alphabet.iter().find(|c| !seen.insert(**c)) {
+            return Err(format!("Trifid alphabet contains {:?} more than once.", c));
+        }
+
+        Ok(TrifidCipher { alphabet, period })
+    }
+
+    /// Splits a cube index into its layer, row and column.
+    fn coordinates(index: usize) -> [usize; 3] {
+        [index / 9, index / 3 % 3, index % 3]
+    }
+
+    /// Fractionates the symbols one block at a time.
+    ///
+    /// # Arguments
+    /// * `indices` - Cube indices of the symbols to transform.
+    /// * `encrypt` - Whether to encrypt rather than decrypt.
+    ///
+    /// # Returns
+    /// Cube indices of the transformed symbols.
+    fn transform(&self, indices: &[usize], encrypt: bool) -> Vec<usize> {
+        let period = match self.period {
+            0 => indices.len().max(1),
+            period => period,
+        };
+
+        let mut output = Vec::with_capacity(indices.len());
+        for block in indices.chunks(period) {
+            let n = block.len();
+            let coordinates: Vec<[usize; 3]> =
+                block.iter().map(|&i| Self::coordinates(i)).collect();
+
+            // Encryption reads the coordinate rows as one stream in triples;
+            // decryption splits the ciphertext's coordinate stream back into rows
+            let mut stream = Vec::with_capacity(3 * n);
+            match encrypt {
+                true => {
+                    for axis in 0..3 {
+                        stream.extend(coordinates.iter().map(|c| c[axis]));
+                    }
+                    output.extend(stream.chunks(3).map(|t| t[0] * 9 + t[1] * 3 + t[2]));
+                }
+                false => {
+                    stream.extend(coordinates.iter().flatten());
+                    output.extend(
+                        (0..n).map(|i| stream[i] * 9 + stream[n + i] * 3 + stream[2 * n + i]),
+                    );
+                }
+            }
+        }
+
+        output
+    }
+
+    /// Applies the cipher to the symbols of the alphabet, leaving every other
+    /// character in place and restoring the case of lowercase letters.
+    fn apply(&self, text: &str, encrypt: bool) -> String {
+        let chars: Vec<char> = text.chars().collect();
+        let positions: Vec<(usize, usize)> = chars
+            .iter()
+            .enumerate()
+            .filter_map(|(position, c)| {
+                self.alphabet
+                    .iter()
+                    .position(|a| *a == c.to_ascii_uppercase())
+                    .map(|index| (position, index))
+            })
+            .collect();
+        let indices: Vec<usize> = positions.iter().map(|(_, index)| *index).collect();
+
+        let mut output = chars.clone();
+        for ((position, _), index) in positions.iter().zip(self.transform(&indices, encrypt)) {
+            let c = self.alphabet[index];
+            output[*position] = match chars[*position].is_ascii_lowercase() {
+                true => c.to_ascii_lowercase(),
+                false => c,
+            };
+        }
+
+        output.into_iter().collect()
+    }
+}
+
+impl Cipher for TrifidCipher {
+    fn name(&self) -> &'static str {
+        "Trifid cipher"
+    }
+
+    /// Encrypts the given plaintext with the Trifid cube.
+    ///
+    /// # Arguments
+    /// * `plaintext` - A string slice that holds the text to be encrypted.
+    ///
+    /// # Returns
+    /// A `String` containing the encrypted text.
+    fn encrypt(&self, plaintext: &str) -> String {
+        self.apply(plaintext, true)
+    }
+
+    /// Decrypts the given ciphertext with the Trifid cube.
+    ///
+    /// # Arguments
+    /// * `ciphertext` - A string slice that holds the text to be decrypted.
+    ///
+    /// # Returns
+    /// A `String` containing the decrypted text.
+    fn decrypt(&self, ciphertext: &str) -> String {
+        self.apply(ciphertext, false)
+    }
+
+    /// Only the symbols in the cube are enciphered, letters in either case.
+    fn transforms(&self, c: char) -> bool {
+        self.alphabet.contains(&c.to_ascii_uppercase())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_trifid_known_vector() {
+        let cipher = TrifidCipher::new("FELIXMARDSTBCGHJKNOPQUVWYZ+", 5).unwrap();
+        let ciphertext = cipher.encrypt("AIDETOILECIELTAIDERA");
+        let plaintext = cipher.decrypt(&ciphertext);
+
+        assert_eq!(ciphertext, "FMJFVOISSUFTFPUFEQQC");
+        assert_eq!(plaintext, "AIDETOILECIELTAIDERA");
+    }
+
+    #[test]
+    fn test_trifid_preserves_case_and_punctuation() {
+        let cipher = TrifidCipher::new("abcdefghijklmnopqrstuvwxyz.", 7).unwrap();
+        let ciphertext = cipher.encrypt("Meet me, at dawn.");
+        let plaintext = cipher.decrypt(&ciphertext);
+
+        assert_eq!(plaintext, "Meet me, at dawn.");
+    }
+
+    #[test]
+    fn test_trifid_rejects_invalid_alphabet() {
+        let short = TrifidCipher::new("abc", 5);
+        let repeated = TrifidCipher::new("abcdefghijklmnopqrstuvwxyza", 5);
+
+        assert!(short.is_err());
+        assert_eq!(
+            repeated.err(),
+            Some("Trifid alphabet contains 'A' more than once.".to_string())
+        );
+    }
+}
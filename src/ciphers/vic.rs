@@ -0,0 +1,335 @@
+//! The `vic` module provides the building blocks of the VIC hand cipher
+//! and a `VicCipher` that chains them together
+//!
+//! The stages are a chain addition keystream generator, sequentializing of
+//! keywords into column orders, a straddling checkerboard that turns letters
+//! into digits, and two columnar transpositions of those digits. The key
+//! schedule follows the spirit of the original rather than every step of it:
+//! the second transposition is a plain columnar one, not the disrupted variant.
+
+use super::columnar::{ColumnarCipher, ColumnarVariant};
+use super::Cipher;
+
+/// Letters on the top row of the checkerboard, the most common in English ("AT ONE SIR").
+const TOP_ROW: [char; 8] = ['E', 'S', 'T', 'O', 'N', 'I', 'A', 'R'];
+
+/// The remaining letters plus `.` and space, filling the two lower rows.
+const LOWER_ROWS: [char; 20] = [
+    'B', 'C', 'D', 'F', 'G', 'H', 'J', 'K', 'L', 'M', 'P', 'Q', 'U', 'V', 'W', 'X', 'Y', 'Z', '.',
+    ' ',
+];
+
+/// Header columns left blank on the top row; their digits label the lower rows.
+const BLANK_COLUMNS: [usize; 2] = [2, 6];
+
+/// Extends the digits by lagged Fibonacci addition mod 10: each new digit
+/// is the sum of the two digits starting where the previous sum started.
+///
+/// # Arguments
+/// * `seed` - The starting digits; at least two.
+/// * `count` - Number of new digits to generate.
+///
+/// # Returns
+/// The `count` generated digits, without the seed.
+///
+/// # Examples
+/// ```
+/// assert_eq!(chain_addition(&[7, 7, 6, 5, 1], 5), vec![4, 3, 1, 6, 5]);
+/// ```
+pub fn chain_addition(seed: &[u8], count: usize) -> Vec<u8> {
+    let mut digits = seed.to_vec();
+    for i in 0..count {
+        let next = (digits[i] + digits[i + 1]) % 10;
+        digits.push(next);
+    }
+
+    digits.split_off(seed.len())
+}
+
+/// Numbers the items 1 to n in sorted order, ties left to right.
+///
+/// # Arguments
+/// * `items` - Letters, or digits with 0 already mapped to 10 so it sorts last.
+///
+/// # Returns
+/// The rank of every item, starting at 1.
+///
+/// # Examples
+/// ```
+/// assert_eq!(sequentialize(&['S', 'E', 'C', 'R', 'E', 'T']), vec![5, 2, 1, 4, 3, 6]);
+/// ```
+pub fn sequentialize<T: Ord>(items: &[T]) -> Vec<u8> {
+    let mut order: Vec<usize> = (0..items.len()).collect();
+    order.sort_by(|&a, &b| items[a].cmp(&items[b]));
+
+    let mut ranks = vec![0; items.len()];
+    for (rank, &index) in order.iter().enumerate() {
+        ranks[index] = rank as u8 + 1;
+    }
+    ranks
+}
+
+/// Sequentializes digits the VIC way, with 0 ranking after 9.
+fn sequentialize_digits(digits: &[u8]) -> Vec<u8> {
+    let values: Vec<u8> = digits
+        .iter()
+        .map(|&d| if d == 0 { 10 } else { d })
+        .collect();
+    sequentialize(&values)
+}
+
+/// Turns ranks into a key for `ColumnarCipher`, whose columns are read in
+/// alphabetical order of the key letters.
+fn transposition_key(ranks: &[u8]) -> String {
+    ranks
+        .iter()
+        .map(|&rank| (b'A' + rank - 1) as char)
+        .collect()
+}
+
+/// A `StraddlingCheckerboard` turns letters into one or two digits.
+/// The eight most common letters get a single digit from the header,
+/// while the rest are prefixed by the header digit over a blank column
+pub struct StraddlingCheckerboard {
+    pub header: [u8; 10],
+}
+
+impl StraddlingCheckerboard {
+    /// Digits labelling the two lower rows.
+    fn row_digits(&self) -> [u8; 2] {
+        [self.header[BLANK_COLUMNS[0]], self.header[BLANK_COLUMNS[1]]]
+    }
+
+    /// Encodes the text as checkerboard digits.
+    ///
+    /// Letters are uppercased; characters not on the board are dropped.
+    pub fn encode(&self, text: &str) -> Vec<u8> {
+        let top_columns: Vec<usize> = (0..10).filter(|c| !BLANK_COLUMNS.contains(c)).collect();
+        let mut digits = Vec::with_capacity(text.len() * 2);
+
+        for c in text.chars().map(|c| c.to_ascii_uppercase()) {
+            if let Some(position) = TOP_ROW.iter().position(|&t| t == c) {
+                digits.push(self.header[top_columns[position]]);
+            } else if let Some(position) = LOWER_ROWS.iter().position(|&l| l == c) {
+                digits.push(self.row_digits()[position / 10]);
+                digits.push(self.header[position % 10]);
+            }
+        }
+
+        digits
+    }
+
+    /// Decodes checkerboard digits back into text.
+    ///
+    /// A trailing row digit without its column digit is dropped.
+    pub fn decode(&self, digits: &[u8]) -> String {
+        let column_of = |d: u8| self.header.iter().position(|&h| h == d).unwrap();
+        let top_columns: Vec<usize> = (0..10).filter(|c| !BLANK_COLUMNS.contains(c)).collect();
+        let rows = self.row_digits();
+
+        let mut text = String::with_capacity(digits.len());
+        let mut iter = digits.iter();
+        while let Some(&d) = iter.next() {
+            match rows.iter().position(|&r| r == d) {
+                Some(row) => {
+                    if let Some(&column) = iter.next() {
+                        text.push(LOWER_ROWS[row * 10 + column_of(column)]);
+                    }
+                }
+                None => {
+                    let column = column_of(d);
+                    let position = top_columns.iter().position(|&c| c == column).unwrap();
+                    text.push(TOP_ROW[position]);
+                }
+            }
+        }
+
+        text
+    }
+}
+
+/// A `VicCipher` encodes the plaintext on a straddling checkerboard and
+/// transposes the digits twice, with every stage keyed from a numeric key
+/// and a phrase through chain addition
+pub struct VicCipher {
+    pub checkerboard: StraddlingCheckerboard,
+    first_transposition: ColumnarCipher,
+    second_transposition: ColumnarCipher,
+}
+
+impl VicCipher {
+    /// Derives every stage of the cipher from the key and phrase.
+    ///
+    /// The key is chain-added out to ten digits and added to the
+    /// sequentialized first ten letters of the phrase, then to the next ten.
+    /// Thirty more chain-added digits give, in turn, the two transposition
+    /// keys and the checkerboard header.
+    ///
+    /// # Arguments
+    /// * `key` - The numeric key, e.g. a five digit indicator.
+    /// * `phrase` - The key phrase; its letters are repeated as needed.
+    ///
+    /// # Returns
+    /// A `VicCipher`, or an error if the key isn't at least two digits
+    /// or the phrase has no letters.
+    pub fn new(key: &str, phrase: &str) -> Result<VicCipher, String> {
+        let key: Vec<u8> = key
+            .chars()
+            .map(|c| c.to_digit(10).map(|d| d as u8))
+            .collect::<Option<_>>()
+            .filter(|digits: &Vec<u8>| digits.len() >= 2)
+            .ok_or_else(|| "VIC key must be at least two digits.".to_string())?;
+
+        let letters: Vec<char> = phrase
+            .chars()
+            .filter(|c| c.is_ascii_alphabetic())
+            .map(|c| c.to_ascii_uppercase())
+            .collect();
+        if letters.is_empty() {
+            return Err("VIC phrase must contain letters.".to_string());
+        }
+        let letters: Vec<char> = letters.into_iter().cycle().take(20).collect();
+
+        let mut expanded = key.clone();
+        if expanded.len() < 10 {
+            expanded.extend(chain_addition(&key, 10 - key.len()));
+        }
+
+        let seed: Vec<u8> = expanded
+            .iter()
+            .zip(sequentialize(&letters[..10]))
+            .zip(sequentialize(&letters[10..]))
+            .map(|((k, first), second)| (k + first + second) % 10)
+            .collect();
+        let block = chain_addition(&seed, 30);
+
+        let mut header = [0; 10];
+        for (slot, rank) in header.iter_mut().zip(sequentialize_digits(&block[20..])) {
+            *slot = rank % 10;
+        }
+
+        Ok(VicCipher {
+            checkerboard: StraddlingCheckerboard { header },
+            first_transposition: ColumnarCipher::new(
+                transposition_key(&sequentialize_digits(&block[..10])),
+                ColumnarVariant::Standard,
+            ),
+            second_transposition: ColumnarCipher::new(
+                transposition_key(&sequentialize_digits(&block[10..20])),
+                ColumnarVariant::Standard,
+            ),
+        })
+    }
+}
+
+impl Cipher for VicCipher {
+    fn name(&self) -> &'static str {
+        "VIC cipher"
+    }
+
+    /// Encrypts the given plaintext into a string of digits.
+    ///
+    /// Only letters, `.` and spaces are kept, uppercased.
+    ///
+    /// # Arguments
+    /// * `plaintext` - A string slice that holds the text to be encrypted.
+    ///
+    /// # Returns
+    /// A `String` of ciphertext digits.
+    fn encrypt(&self, plaintext: &str) -> String {
+        let digits: String = self
+            .checkerboard
+            .encode(plaintext)
+            .iter()
+            .map(|d| (b'0' + d) as char)
+            .collect();
+        let transposed = self.first_transposition.encrypt(&digits);
+        self.second_transposition.encrypt(&transposed)
+    }
+
+    /// Decrypts the given ciphertext digits; other characters are ignored.
+    ///
+    /// # Arguments
+    /// * `ciphertext` - A string slice that holds the digits to be decrypted.
+    ///
+    /// # Returns
+    /// A `String` containing the decrypted text.
+    fn decrypt(&self, ciphertext: &str) -> String {
+        let digits: String = ciphertext.chars().filter(|c| c.is_ascii_digit()).collect();
+        let transposed = self.second_transposition.decrypt(&digits);
+        let digits: Vec<u8> = self
+            .first_transposition
+            .decrypt(&transposed)
+            .bytes()
+            .map(|b| b - b'0')
+            .collect();
+        self.checkerboard.decode(&digits)
+    }
+
+    /// Letters become one or two digits each.
+    fn preserves_length(&self) -> bool {
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_chain_addition() {
+        let digits = chain_addition(&[7, 7, 6, 5, 1], 7);
+
+        assert_eq!(digits, vec![4, 3, 1, 6, 5, 7, 4]);
+    }
+
+    #[test]
+    fn test_sequentialize() {
+        let letters = sequentialize(&['S', 'E', 'C', 'R', 'E', 'T']);
+        let digits = sequentialize_digits(&[3, 0, 1, 3, 9]);
+
+        assert_eq!(letters, vec![5, 2, 1, 4, 3, 6]);
+        assert_eq!(digits, vec![2, 5, 1, 3, 4]);
+    }
+
+    #[test]
+    fn test_straddling_checkerboard() {
+        let checkerboard = StraddlingCheckerboard {
+            header: [0, 1, 2, 3, 4, 5, 6, 7, 8, 9],
+        };
+        let digits = checkerboard.encode("best. z");
+        let text = checkerboard.decode(&digits);
+
+        assert_eq!(digits, vec![2, 0, 0, 1, 3, 6, 8, 6, 9, 6, 7]);
+        assert_eq!(text, "BEST. Z");
+    }
+
+    #[test]
+    fn test_vic_round_trip() {
+        let cipher = VicCipher::new("77651", "Twas the night before Christmas").unwrap();
+        let plaintext = "WE ARE DISCOVERED. FLEE AT ONCE.";
+        let ciphertext = cipher.encrypt(plaintext);
+        let untransposed: String = cipher
+            .checkerboard
+            .encode(plaintext)
+            .iter()
+            .map(|d| (b'0' + d) as char)
+            .collect();
+
+        assert!(ciphertext.chars().all(|c| c.is_ascii_digit()));
+        assert_eq!(ciphertext.len(), untransposed.len());
+        assert_ne!(ciphertext, untransposed);
+        assert_eq!(cipher.decrypt(&ciphertext), plaintext);
+    }
+
+    #[test]
+    fn test_vic_rejects_invalid_keys() {
+        let letters_key = VicCipher::new("12a", "phrase");
+        let short_key = VicCipher::new("7", "phrase");
+        let empty_phrase = VicCipher::new("12345", "123");
+
+        assert!(letters_key.is_err());
+        assert!(short_key.is_err());
+        assert!(empty_phrase.is_err());
+    }
+}
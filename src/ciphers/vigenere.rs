@@ -1,5 +1,6 @@
 //! The `vigenere` module provides an implementation of the Vigenère cipher
 
+use super::alphabet::Alphabet;
 use super::Cipher;
 
 enum EncryptionDirection {
@@ -16,22 +17,32 @@ pub struct VigenereCipher {
 }
 
 impl VigenereCipher {
-    /// New function to ensure that key is always lowercase.
+    /// New function to ensure that key is always lowercase letters.
+    ///
+    /// Non-letter characters are stripped from the key.
     ///
     /// # Arguments
     /// * `key` - A String that acts as the key for the vigenere cipher.
     ///
     /// # Returns
     /// A `VigenereCipher` instance that is guaranteed to have
-    /// an all lowercase key.
+    /// an all lowercase, letters-only key.
     pub fn new(key: String) -> VigenereCipher {
         VigenereCipher {
-            key: key.to_ascii_lowercase(),
+            key: key
+                .chars()
+                .filter(|c| c.is_ascii_alphabetic())
+                .map(|c| c.to_ascii_lowercase())
+                .collect(),
         }
     }
 }
 
 impl Cipher for VigenereCipher {
+    fn name(&self) -> &'static str {
+        "Vigenère cipher"
+    }
+
     /// Encrypts the given plaintext string slice by shifting by the key's values.
     ///
     /// Always treats key as all lowercase regardless of key's casing.
@@ -99,6 +110,93 @@ impl Cipher for VigenereCipher {
             })
             .collect()
     }
+
+    /// Only ASCII letters are enciphered.
+    fn transforms(&self, c: char) -> bool {
+        c.is_ascii_alphabetic()
+    }
+
+    /// A one-letter key shifts every letter alike, which is just a Caesar cipher.
+    fn key_length_bounds(&self) -> (usize, Option<usize>) {
+        (2, None)
+    }
+}
+
+/// Recovers the key letters under a known plaintext fragment (a "crib").
+///
+/// Each key letter is the ciphertext letter minus the crib letter.
+/// Because the key advances on every character, the fragment starts at
+/// key position `offset % key length`.
+///
+/// # Arguments
+/// * `ciphertext` - The full ciphertext.
+/// * `crib` - Plaintext known to appear in the message.
+/// * `offset` - Character position of the crib in the message.
+///
+/// # Returns
+/// The lowercase key fragment, with `?` wherever either character isn't a letter,
+/// or `None` if the crib runs past the end of the ciphertext.
+///
+/// # Examples
+/// ```
+/// use crypto_cli_tool::ciphers::vigenere::recover_key_fragment;
+///
+/// assert_eq!(recover_key_fragment("lxfopvefrnhr", "tack", 2), Some("monl".to_string()));
+/// ```
+pub fn recover_key_fragment(ciphertext: &str, crib: &str, offset: usize) -> Option<String> {
+    let aligned: Vec<char> = ciphertext.chars().skip(offset).collect();
+    if crib.chars().count() > aligned.len() {
+        return None;
+    }
+
+    Some(
+        aligned
+            .iter()
+            .zip(crib.chars())
+            .map(
+                |(&c, p)| match c.is_ascii_alphabetic() && p.is_ascii_alphabetic() {
+                    true => shift_char(
+                        c.to_ascii_lowercase(),
+                        p.to_ascii_lowercase(),
+                        EncryptionDirection::Decrypt,
+                    ),
+                    false => '?',
+                },
+            )
+            .collect(),
+    )
+}
+
+/// Renders the tabula recta: row `i` is the alphabet shifted left by `i`,
+/// so the cell at row `key` and column `plaintext` is the ciphertext letter.
+///
+/// # Arguments
+/// * `alphabet` - The alphabet heading the rows and columns.
+///
+/// # Returns
+/// The square as lines of text, with the column header first.
+///
+/// # Examples
+/// ```
+/// let square = tabula_recta(&"ABC".parse().unwrap());
+/// assert_eq!(square, vec!["  | A B C", "--+------", "A | A B C", "B | B C A", "C | C A B"]);
+/// ```
+pub fn tabula_recta(alphabet: &Alphabet) -> Vec<String> {
+    let row = |shift: usize| -> String {
+        (0..alphabet.len())
+            .map(|i| alphabet.char_at(i + shift).to_string())
+            .collect::<Vec<String>>()
+            .join(" ")
+    };
+
+    let mut lines = vec![
+        format!("  | {}", row(0)),
+        format!("--+{}", "-".repeat(alphabet.len() * 2)),
+    ];
+    for shift in 0..alphabet.len() {
+        lines.push(format!("{} | {}", alphabet.char_at(shift), row(shift)));
+    }
+    lines
 }
 
 /// Function to shift a single `char` by another `char`'s value.
@@ -172,4 +270,55 @@ mod tests {
 
         assert_eq!(plaintext, "secret")
     }
+
+    #[test]
+    fn test_vigenere_cipher_key_space() {
+        let cipher = VigenereCipher::new("key".to_string());
+
+        assert_eq!(cipher.key_space(), None);
+    }
+
+    #[test]
+    fn test_vigenere_cipher_key_sanitized() {
+        let cipher = VigenereCipher::new("K3y!".to_string());
+
+        assert_eq!(cipher.key, "ky");
+    }
+
+    #[test]
+    fn test_recover_key_fragment() {
+        let fragment = recover_key_fragment("LXFOPVEFRNHR", "tack", 2);
+        let with_space = recover_key_fragment("lxfopv mh hmka", "k at", 5);
+        let too_long = recover_key_fragment("lxfopvefrnhr", "tack", 10);
+
+        assert_eq!(fragment, Some("monl".to_string()));
+        assert_eq!(with_space, Some("l?mo".to_string()));
+        assert_eq!(too_long, None);
+    }
+
+    #[test]
+    fn test_tabula_recta() {
+        let square = tabula_recta(&"ABCDEFGHIJKLMNOPQRSTUVWXYZ".parse().unwrap());
+        let small = tabula_recta(&"ABC".parse().unwrap());
+
+        assert_eq!(square.len(), 28);
+        assert_eq!(
+            square[2],
+            "A | A B C D E F G H I J K L M N O P Q R S T U V W X Y Z"
+        );
+        assert_eq!(
+            square[27],
+            "Z | Z A B C D E F G H I J K L M N O P Q R S T U V W X Y"
+        );
+        assert_eq!(
+            small,
+            vec![
+                "  | A B C",
+                "--+------",
+                "A | A B C",
+                "B | B C A",
+                "C | C A B"
+            ]
+        );
+    }
 }
@@ -0,0 +1,145 @@
+//! The `xor` module provides a repeating-key XOR cipher, which becomes the
+//! Vernam cipher when the key is at least as long as the message
+
+use super::{ByteCipher, Cipher};
+use crate::encoding;
+use std::io::{self, Write};
+
+/// Bytes XORed per write when streaming; a multiple of 3 so a base64 sink
+/// receives whole groups.
+const STREAM_CHUNK: usize = 3 * 1024;
+
+/// A `XorCipher` XORs every byte of the input with the key bytes, repeating
+/// the key as needed. As text, the ciphertext is written as lowercase hex
+pub struct XorCipher {
+    pub key: Vec<u8>,
+}
+
+impl XorCipher {
+    /// XORs the bytes with the repeating key. Applying it twice gives back the input.
+    ///
+    /// # Arguments
+    /// * `bytes` - The raw bytes to transform.
+    ///
+    /// # Returns
+    /// The transformed bytes.
+    ///
+    /// # Examples
+    /// ```
+    /// let cipher = XorCipher { key: vec![0x01] };
+    /// assert_eq!(cipher.apply(&[0x41, 0x42]), vec![0x40, 0x43]);
+    /// ```
+    pub fn apply(&self, bytes: &[u8]) -> Vec<u8> {
+        bytes
+            .iter()
+            .zip(self.key.iter().cycle())
+            .map(|(b, k)| b ^ k)
+            .collect()
+    }
+
+    /// XORs the bytes with the repeating key and writes the result to `sink`
+    /// chunk by chunk instead of building the whole output first.
+    ///
+    /// # Arguments
+    /// * `bytes` - The raw bytes to transform.
+    /// * `sink` - Where the transformed bytes are written.
+    ///
+    /// # Returns
+    /// The first error from writing to the sink, if any.
+    pub fn apply_to<W: Write>(&self, bytes: &[u8], sink: &mut W) -> io::Result<()> {
+        let mut key = self.key.iter().cycle();
+        let mut buffer = Vec::with_capacity(STREAM_CHUNK);
+        for chunk in bytes.chunks(STREAM_CHUNK) {
+            buffer.clear();
+            buffer.extend(chunk.iter().zip(&mut key).map(|(b, k)| b ^ k));
+            sink.write_all(&buffer)?;
+        }
+        Ok(())
+    }
+}
+
+impl ByteCipher for XorCipher {
+    fn encrypt_to(&self, bytes: &[u8], mut sink: &mut dyn Write) -> io::Result<()> {
+        self.apply_to(bytes, &mut sink)
+    }
+
+    fn decrypt_bytes(&self, bytes: &[u8]) -> Vec<u8> {
+        self.apply(bytes)
+    }
+}
+
+impl Cipher for XorCipher {
+    fn name(&self) -> &'static str {
+        "XOR cipher"
+    }
+
+    /// Encrypts the UTF-8 bytes of the plaintext.
+    ///
+    /// # Arguments
+    /// * `plaintext` - A string slice that holds the text to be encrypted.
+    ///
+    /// # Returns
+    /// A `String` containing the encrypted bytes as hex.
+    fn encrypt(&self, plaintext: &str) -> String {
+        encoding::encode_hex(&self.apply(plaintext.as_bytes()))
+    }
+
+    /// Decrypts hex ciphertext back into text.
+    ///
+    /// Invalid hex decrypts to an empty string and bytes that aren't
+    /// valid UTF-8 are replaced with U+FFFD.
+    ///
+    /// # Arguments
+    /// * `ciphertext` - A string slice that holds the hex to be decrypted.
+    ///
+    /// # Returns
+    /// A `String` containing the decrypted text.
+    fn decrypt(&self, ciphertext: &str) -> String {
+        let bytes = encoding::decode_hex(ciphertext).unwrap_or_default();
+        String::from_utf8_lossy(&self.apply(&bytes)).into_owned()
+    }
+
+    /// Every byte becomes two hex digits.
+    fn preserves_length(&self) -> bool {
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_xor_cipher() {
+        let cipher = XorCipher {
+            key: b"key".to_vec(),
+        };
+        let ciphertext = cipher.encrypt("hello");
+        let plaintext = cipher.decrypt(&ciphertext);
+
+        assert_eq!(ciphertext, "030015070a");
+        assert_eq!(plaintext, "hello");
+    }
+
+    #[test]
+    fn test_streaming_xor_base64_matches_buffered() {
+        let cipher = XorCipher {
+            key: b"secret".to_vec(),
+        };
+        let bytes: Vec<u8> = (0..100_000u32).map(|i| (i * 31 % 251) as u8).collect();
+
+        let mut streamed = encoding::Base64Writer::new(Vec::new());
+        cipher.apply_to(&bytes, &mut streamed).unwrap();
+        let streamed = streamed.finish().unwrap();
+
+        let mut buffered = encoding::Base64Writer::new(Vec::new());
+        buffered.write_all(&cipher.apply(&bytes)).unwrap();
+        let buffered = buffered.finish().unwrap();
+
+        assert_eq!(streamed, buffered);
+        assert_eq!(
+            encoding::decode_base64(&String::from_utf8(streamed).unwrap()),
+            Ok(cipher.apply(&bytes))
+        );
+    }
+}
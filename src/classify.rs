@@ -0,0 +1,143 @@
+//! The `classify` module guesses which family of cipher produced a ciphertext
+//!
+//! Monoalphabetic substitution keeps the index of coincidence of English but
+//! moves the letters; polyalphabetic ciphers flatten the frequencies towards
+//! random; transposition keeps the very same letters as English and only
+//! reorders them. The guesses are heuristics and need a few dozen letters.
+
+use crate::analysis::{self, ENGLISH_IOC, RANDOM_IOC};
+use crate::output::OutputError;
+use std::fmt;
+
+/// Per-letter chi-squared against English at which the letters stop
+/// looking like unshifted English.
+const ENGLISH_CHI_SQUARED_SCALE: f64 = 2.0;
+
+/// The broad families of classical ciphers.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Family {
+    Monoalphabetic,
+    Polyalphabetic,
+    Transposition,
+}
+
+impl fmt::Display for Family {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Family::Monoalphabetic => "Monoalphabetic substitution",
+            Family::Polyalphabetic => "Polyalphabetic substitution",
+            Family::Transposition => "Transposition",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// A guessed cipher family with its score from 0 to 1 and why.
+#[derive(Debug)]
+pub struct Guess {
+    pub family: Family,
+    pub score: f64,
+    pub reason: String,
+}
+
+/// Ranks the cipher families by how well they explain the ciphertext.
+///
+/// # Arguments
+/// * `ciphertext` - The text to classify; only its letters are used.
+///
+/// # Returns
+/// One guess per family, most likely first.
+pub fn classify(ciphertext: &str) -> Vec<Guess> {
+    let stats = analysis::analyze(ciphertext);
+    let ioc = stats.index_of_coincidence;
+    let chi_squared = stats.english_chi_squared();
+
+    // How far the IoC sits from random (0) towards English (1)
+    let peakedness = ((ioc - RANDOM_IOC) / (ENGLISH_IOC - RANDOM_IOC)).clamp(0.0, 1.0);
+    // How closely the letters match English without any substitution
+    let english_fit = 1.0 / (1.0 + chi_squared / ENGLISH_CHI_SQUARED_SCALE);
+
+    let mut guesses = vec![
+        Guess {
+            family: Family::Transposition,
+            score: peakedness * english_fit,
+            reason: format!(
+                "chi-squared {:.2} per letter against unshifted English; \
+                 rearranging keeps the plaintext's letters",
+                chi_squared
+            ),
+        },
+        Guess {
+            family: Family::Monoalphabetic,
+            score: peakedness * (1.0 - english_fit),
+            reason: format!(
+                "index of coincidence {:.4} against English {:.4}; \
+                 one alphabet keeps it but moves the letters",
+                ioc, ENGLISH_IOC
+            ),
+        },
+        Guess {
+            family: Family::Polyalphabetic,
+            score: 1.0 - peakedness,
+            reason: format!(
+                "index of coincidence {:.4} against random {:.4}; \
+                 several alphabets flatten it towards random",
+                ioc, RANDOM_IOC
+            ),
+        },
+    ];
+    guesses.sort_by(|a, b| b.score.total_cmp(&a.score));
+    guesses
+}
+
+/// Prints the ranked guesses for the ciphertext.
+pub fn print_report(ciphertext: &str) -> Result<(), OutputError> {
+    for (rank, guess) in classify(ciphertext).iter().enumerate() {
+        outln!(
+            "{}. {} ({:.0}%): {}",
+            rank + 1,
+            guess.family,
+            guess.score * 100.0,
+            guess.reason
+        )?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ciphers::{CaesarCipher, Cipher, ColumnarCipher, ColumnarVariant, VigenereCipher};
+
+    const PLAINTEXT: &str = "It was the best of times, it was the worst of times, it was the \
+        age of wisdom, it was the age of foolishness, it was the epoch of belief, it was the \
+        epoch of incredulity, it was the season of light, it was the season of darkness, it \
+        was the spring of hope, it was the winter of despair.";
+
+    fn top_guess(ciphertext: &str) -> Family {
+        classify(ciphertext)[0].family
+    }
+
+    #[test]
+    fn test_classify_caesar() {
+        let ciphertext = CaesarCipher { key: 7 }.encrypt(PLAINTEXT);
+
+        assert_eq!(top_guess(&ciphertext), Family::Monoalphabetic);
+    }
+
+    #[test]
+    fn test_classify_vigenere() {
+        let cipher = VigenereCipher::new("lemonade".to_string());
+        let ciphertext = cipher.encrypt(PLAINTEXT);
+
+        assert_eq!(top_guess(&ciphertext), Family::Polyalphabetic);
+    }
+
+    #[test]
+    fn test_classify_columnar() {
+        let cipher = ColumnarCipher::new("zebras".to_string(), ColumnarVariant::Standard);
+        let ciphertext = cipher.encrypt(PLAINTEXT);
+
+        assert_eq!(top_guess(&ciphertext), Family::Transposition);
+    }
+}
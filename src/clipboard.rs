@@ -0,0 +1,117 @@
+//! The `clipboard` module reads the input text from the system clipboard
+//!
+//! Clipboard access needs the optional `arboard` dependency, enabled with the
+//! `clipboard` feature. Without it `--from-clipboard` is rejected with a hint
+//! on how to build it.
+
+use std::io;
+
+/// Somewhere the input text can be read from in place of the command line.
+#[cfg(feature = "clipboard")]
+pub trait ClipboardSource {
+    /// Returns the text currently held, or why it couldn't be read.
+    fn read_text(&mut self) -> io::Result<String>;
+}
+
+/// The clipboard of the desktop session the tool runs in.
+#[cfg(feature = "clipboard")]
+pub struct SystemClipboard;
+
+#[cfg(feature = "clipboard")]
+impl ClipboardSource for SystemClipboard {
+    fn read_text(&mut self) -> io::Result<String> {
+        arboard::Clipboard::new()
+            .and_then(|mut clipboard| clipboard.get_text())
+            .map_err(io::Error::other)
+    }
+}
+
+/// Whether this build can read the clipboard.
+pub const AVAILABLE: bool = cfg!(feature = "clipboard");
+
+/// Reads the input text from a clipboard source.
+///
+/// # Arguments
+/// * `source` - The clipboard to read.
+///
+/// # Returns
+/// The clipboard text, or an error if it can't be read or holds no text.
+#[cfg(feature = "clipboard")]
+pub fn read_input(source: &mut dyn ClipboardSource) -> io::Result<String> {
+    let text = source.read_text()?;
+    match text.is_empty() {
+        true => Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "it holds no text to use as input",
+        )),
+        false => Ok(text),
+    }
+}
+
+/// Reads the input text from the system clipboard.
+#[cfg(feature = "clipboard")]
+pub fn read() -> io::Result<String> {
+    read_input(&mut SystemClipboard)
+}
+
+/// Stands in for the clipboard reader when the `clipboard` feature is disabled.
+#[cfg(not(feature = "clipboard"))]
+pub fn read() -> io::Result<String> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        unavailable_message(),
+    ))
+}
+
+/// Explains how to get a build that reads the clipboard.
+pub fn unavailable_message() -> String {
+    "--from-clipboard needs the `clipboard` feature; rebuild with \
+     `cargo build --features clipboard`."
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A clipboard holding fixed contents.
+    #[cfg(feature = "clipboard")]
+    struct MockClipboard(Result<String, io::ErrorKind>);
+
+    #[cfg(feature = "clipboard")]
+    impl ClipboardSource for MockClipboard {
+        fn read_text(&mut self) -> io::Result<String> {
+            self.0.clone().map_err(io::Error::from)
+        }
+    }
+
+    #[cfg(feature = "clipboard")]
+    #[test]
+    fn test_read_input() {
+        assert_eq!(
+            read_input(&mut MockClipboard(Ok("attack at dawn".to_string()))).unwrap(),
+            "attack at dawn"
+        );
+        assert_eq!(
+            read_input(&mut MockClipboard(Ok(String::new())))
+                .unwrap_err()
+                .kind(),
+            io::ErrorKind::InvalidData
+        );
+        assert_eq!(
+            read_input(&mut MockClipboard(Err(io::ErrorKind::NotConnected)))
+                .unwrap_err()
+                .kind(),
+            io::ErrorKind::NotConnected
+        );
+    }
+
+    #[cfg(not(feature = "clipboard"))]
+    #[test]
+    fn test_read_unavailable() {
+        let err = read().unwrap_err();
+
+        assert_eq!(err.kind(), io::ErrorKind::Unsupported);
+        assert_eq!(err.to_string(), unavailable_message());
+    }
+}
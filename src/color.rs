@@ -0,0 +1,96 @@
+//! The `color` module highlights the analysis and verbose output with ANSI colors
+//!
+//! Color is only used when the stream it's written to is a terminal, and
+//! never when `--no-color` is passed or the `NO_COLOR` environment variable
+//! is set, so piped and redirected output stays plain.
+
+use std::io::IsTerminal;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static STDOUT_COLOR: AtomicBool = AtomicBool::new(false);
+static STDERR_COLOR: AtomicBool = AtomicBool::new(false);
+
+/// The output streams, which are colored independently.
+#[derive(Clone, Copy, Debug)]
+pub enum Stream {
+    Stdout,
+    Stderr,
+}
+
+/// The styles used in the output.
+#[derive(Clone, Copy, Debug)]
+pub enum Style {
+    Bold,
+    Green,
+    Yellow,
+    Cyan,
+}
+
+impl Style {
+    /// The SGR parameter selecting the style.
+    fn code(&self) -> u8 {
+        match self {
+            Style::Bold => 1,
+            Style::Green => 32,
+            Style::Yellow => 33,
+            Style::Cyan => 36,
+        }
+    }
+}
+
+/// Decides once whether each stream gets color.
+///
+/// # Arguments
+/// * `no_color` - Whether `--no-color` was passed.
+pub fn init(no_color: bool) {
+    let allowed = !no_color && std::env::var_os("NO_COLOR").is_none_or(|value| value.is_empty());
+    STDOUT_COLOR.store(
+        allowed && std::io::stdout().is_terminal(),
+        Ordering::Relaxed,
+    );
+    STDERR_COLOR.store(
+        allowed && std::io::stderr().is_terminal(),
+        Ordering::Relaxed,
+    );
+}
+
+/// Wraps the text in the escape codes for the style.
+///
+/// # Examples
+/// ```
+/// assert_eq!(ansi("e", Style::Green), "\x1b[32me\x1b[0m");
+/// ```
+fn ansi(text: &str, style: Style) -> String {
+    format!("\x1b[{}m{}\x1b[0m", style.code(), text)
+}
+
+/// Styles the text if color is enabled for the stream it will be written to.
+///
+/// # Arguments
+/// * `text` - The text to style.
+/// * `style` - How to style it.
+/// * `stream` - Where the text will be written.
+///
+/// # Returns
+/// The styled text, or the text unchanged when color is off.
+pub fn paint(text: &str, style: Style, stream: Stream) -> String {
+    let enabled = match stream {
+        Stream::Stdout => &STDOUT_COLOR,
+        Stream::Stderr => &STDERR_COLOR,
+    };
+    match enabled.load(Ordering::Relaxed) {
+        true => ansi(text, style),
+        false => text.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ansi() {
+        assert_eq!(ansi("e", Style::Green), "\x1b[32me\x1b[0m");
+        assert_eq!(ansi("warning:", Style::Yellow), "\x1b[33mwarning:\x1b[0m");
+    }
+}
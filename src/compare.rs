@@ -0,0 +1,147 @@
+//! The `compare` module shows two ciphers' output on the same input side by side
+
+use crate::output::OutputError;
+
+/// Builds a marker line with `^` under every position where the texts differ.
+///
+/// Positions past the end of the shorter text count as differences.
+/// Trailing spaces are trimmed.
+///
+/// # Arguments
+/// * `first` - Output of the first cipher.
+/// * `second` - Output of the second cipher.
+///
+/// # Returns
+/// A `String` of spaces and `^` markers.
+///
+/// # Examples
+/// ```
+/// assert_eq!(diff_markers("abcd", "abxd"), "  ^");
+/// ```
+pub fn diff_markers(first: &str, second: &str) -> String {
+    let first: Vec<char> = first.chars().collect();
+    let second: Vec<char> = second.chars().collect();
+
+    let markers: String = (0..first.len().max(second.len()))
+        .map(|i| match first.get(i) == second.get(i) {
+            true => ' ',
+            false => '^',
+        })
+        .collect();
+
+    markers.trim_end().to_string()
+}
+
+/// Number of characters quoted from each side of a mismatch.
+const MISMATCH_CONTEXT: usize = 20;
+
+/// Summarizes where the actual output first departs from the expected text.
+///
+/// # Arguments
+/// * `expected` - The text the output should have been.
+/// * `actual` - The output produced.
+///
+/// # Returns
+/// `None` when they're equal, otherwise a description of the first
+/// difference with its line and column, both counted from 1, a snippet
+/// of each side and the number of differing positions.
+///
+/// # Examples
+/// ```
+/// let summary = mismatch_summary("abc\ndef", "abc\ndxf").unwrap();
+/// assert!(summary.starts_with("first difference at line 2, column 2"));
+/// ```
+pub fn mismatch_summary(expected: &str, actual: &str) -> Option<String> {
+    if expected == actual {
+        return None;
+    }
+
+    let expected_chars: Vec<char> = expected.chars().collect();
+    let actual_chars: Vec<char> = actual.chars().collect();
+    let position = expected_chars
+        .iter()
+        .zip(&actual_chars)
+        .position(|(a, b)| a != b)
+        .unwrap_or_else(|| expected_chars.len().min(actual_chars.len()));
+
+    let line = expected_chars[..position]
+        .iter()
+        .filter(|&&c| c == '\n')
+        .count()
+        + 1;
+    let column = position
+        - expected_chars[..position]
+            .iter()
+            .rposition(|&c| c == '\n')
+            .map_or(0, |newline| newline + 1)
+        + 1;
+    let snippet =
+        |chars: &[char]| -> String { chars.iter().skip(position).take(MISMATCH_CONTEXT).collect() };
+    let differing = diff_markers(expected, actual)
+        .chars()
+        .filter(|&c| c == '^')
+        .count();
+
+    Some(format!(
+        "first difference at line {}, column {}: expected {:?}, got {:?} \
+         ({} differing position(s); expected {} chars, got {})",
+        line,
+        column,
+        snippet(&expected_chars),
+        snippet(&actual_chars),
+        differing,
+        expected_chars.len(),
+        actual_chars.len()
+    ))
+}
+
+/// Prints both labeled outputs aligned, followed by the difference markers.
+pub fn print_comparison(
+    first_label: &str,
+    first: &str,
+    second_label: &str,
+    second: &str,
+) -> Result<(), OutputError> {
+    let width = first_label
+        .chars()
+        .count()
+        .max(second_label.chars().count())
+        + 1;
+
+    outln!("{:<width$} {}", format!("{}:", first_label), first)?;
+    outln!("{:<width$} {}", format!("{}:", second_label), second)?;
+    outln!("{:<width$} {}", "", diff_markers(first, second))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_diff_markers() {
+        let markers = diff_markers("khoor zruog", "sixzb aafyo");
+        let partial = diff_markers("abcdef", "abXdeYZ");
+
+        assert_eq!(markers, "^^^^^ ^^^^^");
+        assert_eq!(partial, "  ^  ^^");
+    }
+
+    #[test]
+    fn test_mismatch_summary() {
+        let changed = mismatch_summary("khoor\nzruog", "khoor\nzrxog");
+        let truncated = mismatch_summary("khoor zruog", "khoor");
+
+        assert_eq!(mismatch_summary("khoor", "khoor"), None);
+        assert_eq!(
+            changed.unwrap(),
+            "first difference at line 2, column 3: expected \"uog\", got \"xog\" \
+             (1 differing position(s); expected 11 chars, got 11)"
+        );
+        assert_eq!(
+            truncated.unwrap(),
+            "first difference at line 1, column 6: expected \" zruog\", got \"\" \
+             (6 differing position(s); expected 11 chars, got 5)"
+        );
+    }
+}
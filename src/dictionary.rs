@@ -0,0 +1,210 @@
+//! The `dictionary` module scores decrypted text by how many of its words are real words
+
+use std::collections::HashSet;
+use std::io::{self, BufRead};
+
+/// Reads a dictionary with one word per line into a set of lowercase words.
+///
+/// The file is streamed line by line. Blank lines are skipped.
+///
+/// # Arguments
+/// * `reader` - Source of the dictionary.
+///
+/// # Returns
+/// The set of words, or the error from reading the dictionary.
+pub fn load<R: BufRead>(reader: R) -> io::Result<HashSet<String>> {
+    let mut words = HashSet::new();
+    for line in reader.lines() {
+        let word = line?.trim().to_lowercase();
+        if !word.is_empty() {
+            words.insert(word);
+        }
+    }
+
+    Ok(words)
+}
+
+/// Splits the text into lowercase words at every non-letter.
+fn tokens(text: &str) -> impl Iterator<Item = String> + '_ {
+    text.split(|c: char| !c.is_alphabetic())
+        .filter(|token| !token.is_empty())
+        .map(|token| token.to_lowercase())
+}
+
+/// Counts how many of the text's words appear in the dictionary.
+///
+/// # Arguments
+/// * `text` - The decrypted text to score.
+/// * `dictionary` - Known lowercase words.
+///
+/// # Returns
+/// The number of words found and the total number of words.
+///
+/// # Examples
+/// ```
+/// let dictionary = load("attack\nat\n".as_bytes()).unwrap();
+/// assert_eq!(word_matches("Attack at dawn!", &dictionary), (2, 3));
+/// ```
+pub fn word_matches(text: &str, dictionary: &HashSet<String>) -> (usize, usize) {
+    tokens(text).fold((0, 0), |(found, total), token| {
+        match dictionary.contains(&token) {
+            true => (found + 1, total + 1),
+            false => (found, total + 1),
+        }
+    })
+}
+
+/// Fraction of the text's words found in the dictionary, or 0.0 when it has no words.
+pub fn word_fraction(text: &str, dictionary: &HashSet<String>) -> f64 {
+    match word_matches(text, dictionary) {
+        (_, 0) => 0.0,
+        (found, total) => found as f64 / total as f64,
+    }
+}
+
+/// Formats the dictionary confidence line printed after decryption.
+pub fn confidence(text: &str, dictionary: &HashSet<String>) -> String {
+    let (found, total) = word_matches(text, dictionary);
+    let fraction = word_fraction(text, dictionary);
+    format!(
+        "Dictionary confidence: {:.1}% ({}/{} words)",
+        fraction * 100.0,
+        found,
+        total
+    )
+}
+
+/// Score of every dictionary word in a segmentation. The list has no
+/// frequencies, so each word is equally likely and fewer words score higher.
+const KNOWN_WORD_SCORE: f64 = -1.0;
+
+/// Score of a letter that isn't part of any dictionary word.
+const UNKNOWN_LETTER_SCORE: f64 = -10.0;
+
+/// Reinserts spaces into runs of letters, splitting each run into the most
+/// likely sequence of dictionary words by dynamic programming.
+///
+/// Letters no dictionary word covers are kept together as one token.
+/// Other characters, including existing spaces, are left in place, and
+/// the letters keep their case.
+///
+/// # Arguments
+/// * `text` - Text whose spaces were stripped, such as a brute force candidate.
+/// * `dictionary` - Known lowercase words.
+///
+/// # Returns
+/// The text with a space between every pair of adjacent words.
+///
+/// # Examples
+/// ```
+/// let dictionary = load("hello\nworld\n".as_bytes()).unwrap();
+/// assert_eq!(segment("helloworld", &dictionary), "hello world");
+/// ```
+pub fn segment(text: &str, dictionary: &HashSet<String>) -> String {
+    let longest = dictionary
+        .iter()
+        .map(|word| word.chars().count())
+        .max()
+        .unwrap_or(1);
+    let mut segmented = String::with_capacity(text.len() + text.len() / 4);
+    let mut run: Vec<char> = Vec::new();
+
+    for c in text.chars() {
+        if c.is_alphabetic() {
+            run.push(c);
+            continue;
+        }
+        push_words(&mut segmented, &run, dictionary, longest);
+        run.clear();
+        segmented.push(c);
+    }
+    push_words(&mut segmented, &run, dictionary, longest);
+
+    segmented
+}
+
+/// Appends a run of letters to `segmented` split into space-separated words.
+fn push_words(segmented: &mut String, run: &[char], dictionary: &HashSet<String>, longest: usize) {
+    let words: Vec<String> = segment_run(run, dictionary, longest)
+        .into_iter()
+        .map(|(start, end)| run[start..end].iter().collect())
+        .collect();
+    segmented.push_str(&words.join(" "));
+}
+
+/// Splits a run of letters into the best scoring words.
+///
+/// # Returns
+/// The `(start, end)` character ranges of the words, in order.
+fn segment_run(run: &[char], dictionary: &HashSet<String>, longest: usize) -> Vec<(usize, usize)> {
+    // best[i] is the score of the best split of the first i letters, with
+    // where its last piece starts and whether that piece is a known word
+    let mut best: Vec<(f64, usize, bool)> = vec![(0.0, 0, true); run.len() + 1];
+    for end in 1..=run.len() {
+        best[end] = (f64::NEG_INFINITY, end - 1, false);
+        for start in end.saturating_sub(longest)..end {
+            let word: String = run[start..end]
+                .iter()
+                .flat_map(|c| c.to_lowercase())
+                .collect();
+            let known = dictionary.contains(&word);
+            let score = match (known, end - start) {
+                (true, _) => KNOWN_WORD_SCORE,
+                (false, 1) => UNKNOWN_LETTER_SCORE,
+                (false, _) => continue,
+            };
+            let total = best[start].0 + score;
+            if total > best[end].0 {
+                best[end] = (total, start, known);
+            }
+        }
+    }
+
+    // Walk back from the end, merging neighbouring unknown letters
+    let mut pieces: Vec<(usize, usize)> = Vec::new();
+    let mut end = run.len();
+    let mut previous_known = true;
+    while end > 0 {
+        let (_, start, known) = best[end];
+        match (known, previous_known, pieces.last_mut()) {
+            (false, false, Some(piece)) => piece.0 = start,
+            _ => pieces.push((start, end)),
+        }
+        previous_known = known;
+        end = start;
+    }
+    pieces.reverse();
+    pieces
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_word_matches() {
+        let dictionary = load("the\nenemy\nattacks\nat\ndawn\n\n".as_bytes()).unwrap();
+        let plaintext = word_matches("The enemy attacks at dawn.", &dictionary);
+        let garbage = word_matches("Wkh hqhpb dwwdfnv dw gdzq.", &dictionary);
+
+        assert_eq!(plaintext, (5, 5));
+        assert_eq!(garbage, (0, 5));
+        assert_eq!(
+            confidence("The enemy attacks at noon.", &dictionary),
+            "Dictionary confidence: 80.0% (4/5 words)"
+        );
+    }
+
+    #[test]
+    fn test_segment() {
+        let dictionary =
+            load("hello\nworld\nhell\nlow\nor\nattack\nat\ndawn\n".as_bytes()).unwrap();
+
+        assert_eq!(segment("helloworld", &dictionary), "hello world");
+        assert_eq!(segment("AttackAtDawn!", &dictionary), "Attack At Dawn!");
+        assert_eq!(segment("helloxyzworld", &dictionary), "hello xyz world");
+        assert_eq!(segment("hello, world", &dictionary), "hello, world");
+        assert_eq!(segment("", &dictionary), "");
+        assert_eq!(segment("hello\0world", &dictionary), "hello\0world");
+    }
+}
@@ -0,0 +1,396 @@
+//! The `encoding` module converts between raw bytes and their hex or base64 text forms
+
+use std::io::{self, Write};
+
+/// How the input argument or file should be interpreted.
+#[derive(clap::ArgEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum InputFormat {
+    #[clap(name = "text")]
+    Text,
+    #[clap(name = "hex")]
+    Hex,
+    #[clap(name = "base64")]
+    Base64,
+    #[clap(name = "base32")]
+    Base32,
+}
+
+/// How raw output bytes are written as text.
+#[derive(clap::ArgEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ByteFormat {
+    #[clap(name = "hex")]
+    Hex,
+    #[clap(name = "base64")]
+    Base64,
+    #[clap(name = "base32")]
+    Base32,
+}
+
+const BASE64_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Encodes the bytes as lowercase hex.
+pub fn encode_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+const BASE32_ALPHABET: &[u8; 32] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+
+/// A `HexWriter` hex-encodes every byte written to it and passes the digits
+/// on to the inner sink, so the encoded output never has to be held in memory.
+pub struct HexWriter<W: Write> {
+    inner: W,
+}
+
+impl<W: Write> HexWriter<W> {
+    pub fn new(inner: W) -> Self {
+        HexWriter { inner }
+    }
+
+    /// Returns the inner sink. Hex has no padding, so there is nothing to flush.
+    pub fn finish(self) -> W {
+        self.inner
+    }
+}
+
+impl<W: Write> Write for HexWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.inner.write_all(encode_hex(buf).as_bytes())?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// A `Base64Writer` base64-encodes the bytes written to it in whole 3-byte
+/// groups, carrying up to two leftover bytes over to the next write so that
+/// chunk boundaries never split a group.
+pub struct Base64Writer<W: Write> {
+    inner: W,
+    pending: Vec<u8>,
+}
+
+impl<W: Write> Base64Writer<W> {
+    pub fn new(inner: W) -> Self {
+        Base64Writer {
+            inner,
+            pending: Vec::with_capacity(2),
+        }
+    }
+
+    /// Writes the last partial group with `=` padding and returns the inner sink.
+    ///
+    /// # Returns
+    /// The inner sink, or the error from writing the final group.
+    pub fn finish(mut self) -> io::Result<W> {
+        if !self.pending.is_empty() {
+            let group = base64_group(&self.pending);
+            self.inner.write_all(&group)?;
+        }
+        self.inner.flush()?;
+        Ok(self.inner)
+    }
+}
+
+impl<W: Write> Write for Base64Writer<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let mut bytes = buf;
+        if !self.pending.is_empty() {
+            let needed = (3 - self.pending.len()).min(bytes.len());
+            self.pending.extend_from_slice(&bytes[..needed]);
+            bytes = &bytes[needed..];
+            if self.pending.len() < 3 {
+                return Ok(buf.len());
+            }
+            let group = base64_group(&self.pending);
+            self.inner.write_all(&group)?;
+            self.pending.clear();
+        }
+
+        let whole = bytes.len() - bytes.len() % 3;
+        let encoded: Vec<u8> = bytes[..whole].chunks(3).flat_map(base64_group).collect();
+        self.inner.write_all(&encoded)?;
+        self.pending.extend_from_slice(&bytes[whole..]);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+/// Encodes one group of up to three bytes as four base64 characters,
+/// padding short groups with `=`.
+fn base64_group(group: &[u8]) -> [u8; 4] {
+    let buffer = group
+        .iter()
+        .enumerate()
+        .fold(0u32, |acc, (i, &b)| acc | (b as u32) << (16 - 8 * i));
+    let mut out = [b'='; 4];
+    for (i, slot) in out.iter_mut().enumerate().take(group.len() + 1) {
+        *slot = BASE64_ALPHABET[(buffer >> (18 - 6 * i)) as usize & 0x3f];
+    }
+    out
+}
+
+/// Decodes hex text into bytes, ignoring whitespace.
+///
+/// # Arguments
+/// * `text` - Pairs of hex digits in either case.
+///
+/// # Returns
+/// The decoded bytes, or an error naming the first invalid digit.
+///
+/// # Examples
+/// ```
+/// assert_eq!(decode_hex("4869"), Ok(b"Hi".to_vec()));
+/// ```
+pub fn decode_hex(text: &str) -> Result<Vec<u8>, String> {
+    let digits: Vec<char> = text.chars().filter(|c| !c.is_whitespace()).collect();
+    if !digits.len().is_multiple_of(2) {
+        return Err("hex input has an odd number of digits".to_string());
+    }
+
+    digits
+        .chunks(2)
+        .map(|pair| {
+            let high = pair[0].to_digit(16);
+            let low = pair[1].to_digit(16);
+            match (high, low) {
+                (Some(high), Some(low)) => Ok((high * 16 + low) as u8),
+                (None, _) => Err(format!("invalid hex digit {:?}", pair[0])),
+                (_, None) => Err(format!("invalid hex digit {:?}", pair[1])),
+            }
+        })
+        .collect()
+}
+
+/// Decodes standard padded or unpadded base64 into bytes, ignoring whitespace.
+///
+/// # Arguments
+/// * `text` - Base64 text using the `+` and `/` alphabet.
+///
+/// # Returns
+/// The decoded bytes, or an error naming the first invalid character.
+///
+/// # Examples
+/// ```
+/// assert_eq!(decode_base64("SGk="), Ok(b"Hi".to_vec()));
+/// ```
+pub fn decode_base64(text: &str) -> Result<Vec<u8>, String> {
+    let symbols: Vec<char> = text
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .collect::<String>()
+        .trim_end_matches('=')
+        .chars()
+        .collect();
+    if symbols.len() % 4 == 1 {
+        return Err("base64 input has an invalid length".to_string());
+    }
+
+    let mut bytes = Vec::with_capacity(symbols.len() * 3 / 4);
+    let mut buffer: u32 = 0;
+    let mut bits = 0;
+    for c in symbols {
+        let value = BASE64_ALPHABET
+            .iter()
+            .position(|&b| b as char == c)
+            .ok_or_else(|| format!("invalid base64 character {:?}", c))?;
+        buffer = (buffer << 6) | value as u32;
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            bytes.push((buffer >> bits) as u8);
+        }
+    }
+
+    Ok(bytes)
+}
+
+/// Encodes the bytes as RFC 4648 base32.
+///
+/// # Arguments
+/// * `bytes` - The bytes to encode.
+/// * `pad` - Whether to pad the output with `=` to a multiple of 8 characters.
+///
+/// # Examples
+/// ```
+/// assert_eq!(encode_base32(b"foo", true), "MZXW6===");
+/// assert_eq!(encode_base32(b"foo", false), "MZXW6");
+/// ```
+pub fn encode_base32(bytes: &[u8], pad: bool) -> String {
+    let mut text = String::with_capacity(bytes.len().div_ceil(5) * 8);
+    for group in bytes.chunks(5) {
+        let buffer = group
+            .iter()
+            .enumerate()
+            .fold(0u64, |acc, (i, &b)| acc | (b as u64) << (32 - 8 * i));
+        let symbols = (group.len() * 8).div_ceil(5);
+        for i in 0..8 {
+            match i < symbols {
+                true => {
+                    text.push(BASE32_ALPHABET[(buffer >> (35 - 5 * i)) as usize & 0x1f] as char)
+                }
+                false if pad => text.push('='),
+                false => {}
+            }
+        }
+    }
+    text
+}
+
+/// Decodes padded or unpadded base32 into bytes, ignoring whitespace and case.
+///
+/// # Arguments
+/// * `text` - Base32 text using the RFC 4648 `A-Z2-7` alphabet.
+///
+/// # Returns
+/// The decoded bytes, or an error naming the first invalid character.
+///
+/// # Examples
+/// ```
+/// assert_eq!(decode_base32("mzxw6==="), Ok(b"foo".to_vec()));
+/// ```
+pub fn decode_base32(text: &str) -> Result<Vec<u8>, String> {
+    let symbols: Vec<char> = text
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .collect::<String>()
+        .trim_end_matches('=')
+        .chars()
+        .collect();
+    if matches!(symbols.len() % 8, 1 | 3 | 6) {
+        return Err("base32 input has an invalid length".to_string());
+    }
+
+    let mut bytes = Vec::with_capacity(symbols.len() * 5 / 8);
+    let mut buffer: u32 = 0;
+    let mut bits = 0;
+    for c in symbols {
+        let value = BASE32_ALPHABET
+            .iter()
+            .position(|&b| b as char == c.to_ascii_uppercase())
+            .ok_or_else(|| format!("invalid base32 character {:?}", c))?;
+        buffer = (buffer << 5) | value as u32;
+        bits += 5;
+        if bits >= 8 {
+            bits -= 8;
+            bytes.push((buffer >> bits) as u8);
+        }
+    }
+
+    Ok(bytes)
+}
+
+/// Decodes the input according to its format.
+///
+/// # Returns
+/// The raw bytes, or an error describing the malformed input.
+pub fn decode(text: &str, format: InputFormat) -> Result<Vec<u8>, String> {
+    match format {
+        InputFormat::Text => Ok(text.as_bytes().to_vec()),
+        InputFormat::Hex => decode_hex(text),
+        InputFormat::Base64 => decode_base64(text),
+        InputFormat::Base32 => decode_base32(text),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hex_round_trip() {
+        let bytes = decode_hex("00ff 7A10").unwrap();
+        let encoded = encode_hex(&bytes);
+
+        assert_eq!(bytes, vec![0x00, 0xff, 0x7a, 0x10]);
+        assert_eq!(encoded, "00ff7a10");
+        assert!(decode_hex("abc").is_err());
+        assert_eq!(decode_hex("zz"), Err("invalid hex digit 'z'".to_string()));
+    }
+
+    #[test]
+    fn test_decode_base64() {
+        let padded = decode_base64("aGVsbG8gd29ybGQ=").unwrap();
+        let unpadded = decode_base64("aGVsbG8").unwrap();
+
+        assert_eq!(padded, b"hello world".to_vec());
+        assert_eq!(unpadded, b"hello".to_vec());
+        assert!(decode_base64("aGV*").is_err());
+    }
+
+    #[test]
+    fn test_base32_round_trip() {
+        let vectors = [
+            ("", ""),
+            ("f", "MY======"),
+            ("fo", "MZXQ===="),
+            ("foo", "MZXW6==="),
+            ("foob", "MZXW6YQ="),
+            ("fooba", "MZXW6YTB"),
+            ("foobar", "MZXW6YTBOI======"),
+        ];
+        for (plain, encoded) in vectors {
+            assert_eq!(encode_base32(plain.as_bytes(), true), encoded);
+            assert_eq!(decode_base32(encoded), Ok(plain.as_bytes().to_vec()));
+            assert_eq!(
+                decode_base32(&encode_base32(plain.as_bytes(), false)),
+                Ok(plain.as_bytes().to_vec())
+            );
+        }
+        assert_eq!(encode_base32(b"foobar", false), "MZXW6YTBOI");
+    }
+
+    #[test]
+    fn test_decode_base32() {
+        assert_eq!(decode_base32("mzxw 6ytb oi"), Ok(b"foobar".to_vec()));
+        assert_eq!(
+            decode_base32("MZXW1==="),
+            Err("invalid base32 character '1'".to_string())
+        );
+        assert!(decode_base32("MZX").is_err());
+    }
+
+    #[test]
+    fn test_base64_writer() {
+        let mut writer = Base64Writer::new(Vec::new());
+        writer.write_all(b"hello world").unwrap();
+        let encoded = writer.finish().unwrap();
+
+        assert_eq!(encoded, b"aGVsbG8gd29ybGQ=".to_vec());
+        assert_eq!(
+            decode_base64("aGVsbG8gd29ybGQ="),
+            Ok(b"hello world".to_vec())
+        );
+    }
+
+    #[test]
+    fn test_base64_writer_chunk_boundaries() {
+        let bytes: Vec<u8> = (0..=255).cycle().take(1000).collect();
+        let mut whole = Base64Writer::new(Vec::new());
+        whole.write_all(&bytes).unwrap();
+        let whole = whole.finish().unwrap();
+
+        let mut chunked = Base64Writer::new(Vec::new());
+        for chunk in bytes.chunks(7) {
+            chunked.write_all(chunk).unwrap();
+        }
+        let chunked = chunked.finish().unwrap();
+
+        assert_eq!(chunked, whole);
+        assert_eq!(decode_base64(&String::from_utf8(whole).unwrap()), Ok(bytes));
+    }
+
+    #[test]
+    fn test_hex_writer() {
+        let mut writer = HexWriter::new(Vec::new());
+        writer.write_all(&[0x00, 0xff]).unwrap();
+        writer.write_all(&[0x7a]).unwrap();
+
+        assert_eq!(writer.finish(), b"00ff7a".to_vec());
+    }
+}
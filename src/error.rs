@@ -0,0 +1,163 @@
+//! The `error` module defines the error that ends a run early
+//!
+//! Each variant maps to one of the exit codes in the `exit` module, and
+//! keeps the error that caused it, if any, as its source so the whole
+//! chain can be reported.
+
+use crate::ciphers::CipherError;
+use crate::exit::ExitCode;
+use crate::output::OutputError;
+use std::error::Error as StdError;
+use std::fmt;
+use std::io;
+use std::num::ParseIntError;
+
+/// Why a run failed.
+#[derive(Debug)]
+pub enum Error {
+    /// A bad flag, argument or combination of them.
+    Usage(String),
+    /// A value that doesn't parse, e.g. in a job file or environment variable.
+    Parse {
+        context: String,
+        source: Box<dyn StdError + Send + Sync>,
+    },
+    /// A key the cipher can't use, or a search that recovered none.
+    Cipher(CipherError),
+    /// Reading or writing a file or stream failed.
+    Io { context: String, source: io::Error },
+    /// The input couldn't be decoded or the output encoded.
+    Decode(String),
+    /// The output differs from what was expected.
+    Mismatch(String),
+    /// The output couldn't be encoded or written.
+    Output(OutputError),
+}
+
+impl Error {
+    /// Wraps an I/O error with what was being done when it happened.
+    pub fn io(context: impl Into<String>, source: io::Error) -> Self {
+        Error::Io {
+            context: context.into(),
+            source,
+        }
+    }
+
+    /// The exit code the error ends the process with.
+    pub fn exit_code(&self) -> ExitCode {
+        match self {
+            Error::Usage(_) | Error::Parse { .. } => ExitCode::Usage,
+            Error::Cipher(_) => ExitCode::InvalidKey,
+            Error::Io { .. } => ExitCode::Io,
+            Error::Decode(_) => ExitCode::Decode,
+            Error::Mismatch(_) => ExitCode::Mismatch,
+            Error::Output(OutputError::Encode(_)) => ExitCode::Decode,
+            Error::Output(OutputError::Io(_)) => ExitCode::Io,
+        }
+    }
+
+    /// The error followed by each of its causes, separated by colons.
+    ///
+    /// # Examples
+    /// ```
+    /// let err = Error::io("Could not read key.txt", io::ErrorKind::NotFound.into());
+    /// assert_eq!(err.chain(), "Could not read key.txt: entity not found");
+    /// ```
+    pub fn chain(&self) -> String {
+        let mut message = self.to_string();
+        let mut source = self.source();
+        while let Some(cause) = source {
+            message.push_str(&format!(": {}", cause));
+            source = cause.source();
+        }
+        message
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Usage(message) | Error::Decode(message) | Error::Mismatch(message) => {
+                write!(f, "{}", message)
+            }
+            Error::Parse { context, .. } | Error::Io { context, .. } => write!(f, "{}", context),
+            Error::Cipher(err) => write!(f, "{}", err),
+            Error::Output(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl StdError for Error {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        match self {
+            Error::Parse { source, .. } => Some(source.as_ref()),
+            Error::Io { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(err: io::Error) -> Self {
+        Error::io("I/O error", err)
+    }
+}
+
+impl From<ParseIntError> for Error {
+    fn from(err: ParseIntError) -> Self {
+        Error::Parse {
+            context: "Invalid number".to_string(),
+            source: Box::new(err),
+        }
+    }
+}
+
+impl From<CipherError> for Error {
+    fn from(err: CipherError) -> Self {
+        Error::Cipher(err)
+    }
+}
+
+impl From<OutputError> for Error {
+    fn from(err: OutputError) -> Self {
+        Error::Output(err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display() {
+        assert_eq!(
+            Error::Usage("--in-place needs exactly one input file.".to_string()).to_string(),
+            "--in-place needs exactly one input file."
+        );
+        assert_eq!(
+            Error::from(CipherError(
+                "Caesar cipher requires an integer key.".to_string()
+            ))
+            .to_string(),
+            "Caesar cipher requires an integer key."
+        );
+        assert_eq!(
+            Error::from("x".parse::<i32>().unwrap_err()).chain(),
+            "Invalid number: invalid digit found in string"
+        );
+    }
+
+    #[test]
+    fn test_io_source_is_preserved() {
+        let err = Error::io(
+            "Could not read key.txt",
+            io::Error::new(io::ErrorKind::NotFound, "no such file"),
+        );
+
+        assert_eq!(err.to_string(), "Could not read key.txt");
+        assert_eq!(err.chain(), "Could not read key.txt: no such file");
+        assert_eq!(err.exit_code(), ExitCode::Io);
+        let source = err.source().unwrap().downcast_ref::<io::Error>().unwrap();
+        assert_eq!(source.kind(), io::ErrorKind::NotFound);
+    }
+}
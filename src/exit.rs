@@ -0,0 +1,27 @@
+//! The `exit` module defines the process exit codes scripts can rely on
+//!
+//! | Code | Meaning                                    |
+//! |------|--------------------------------------------|
+//! | 0    | success                                    |
+//! | 1    | usage or argument error                    |
+//! | 2    | invalid key                                |
+//! | 3    | I/O error                                  |
+//! | 4    | decode/encode error                        |
+//! | 5    | output differs from expected or round trip |
+
+use std::process;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ExitCode {
+    Success = 0,
+    Usage = 1,
+    InvalidKey = 2,
+    Io = 3,
+    Decode = 4,
+    Mismatch = 5,
+}
+
+/// Exits the process with the given code.
+pub fn exit(code: ExitCode) -> ! {
+    process::exit(code as i32)
+}
@@ -0,0 +1,29 @@
+//! The `interrupt` module lets long searches stop early on Ctrl-C
+//!
+//! Once the handler is installed, Ctrl-C only raises a flag. Search loops
+//! check it between keys and return what they have found so far, so the
+//! best candidates are still printed instead of being lost.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+static INTERRUPTED: AtomicBool = AtomicBool::new(false);
+
+/// Replaces the default Ctrl-C behavior with raising the interrupt flag.
+///
+/// Installing fails only if another handler is already set, in which
+/// case Ctrl-C keeps its default behavior.
+pub fn install() {
+    if ctrlc::set_handler(|| INTERRUPTED.store(true, Ordering::Relaxed)).is_err() {
+        crate::log::warn("could not install the Ctrl-C handler");
+    }
+}
+
+/// The flag raised by Ctrl-C, for search loops to poll.
+pub fn flag() -> &'static AtomicBool {
+    &INTERRUPTED
+}
+
+/// Whether Ctrl-C has been pressed since the handler was installed.
+pub fn requested() -> bool {
+    INTERRUPTED.load(Ordering::Relaxed)
+}
@@ -0,0 +1,124 @@
+//! The `job` module reads a batch job from a TOML file
+//!
+//! A job describes an invocation once so it can be rerun exactly:
+//!
+//! ```toml
+//! algorithm = "caesar,vigenere"
+//! direction = "encrypt"
+//! key = [3, "lemon"]
+//! input_file = "plain.txt"
+//! output_file = "cipher.txt"
+//! normalize = "nfc"
+//! ```
+//!
+//! Every field is optional. Values are parsed exactly like the matching
+//! command line flags, and flags given on the command line take precedence.
+
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+
+/// A job file's fields, as the text the matching flags would take.
+#[derive(Debug, Default, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct Job {
+    pub algorithm: Option<String>,
+    pub direction: Option<String>,
+    #[serde(default)]
+    pub key: Keys,
+    pub key2: Option<String>,
+    pub input_file: Option<PathBuf>,
+    pub output_file: Option<PathBuf>,
+    pub normalize: Option<String>,
+    pub translit: Option<String>,
+}
+
+/// One key or a list of keys, each a string or an integer.
+#[derive(Debug, Default, Deserialize, PartialEq)]
+#[serde(untagged)]
+pub enum Keys {
+    #[default]
+    None,
+    One(KeyValue),
+    Many(Vec<KeyValue>),
+}
+
+/// A key as written in TOML.
+#[derive(Debug, Deserialize, PartialEq)]
+#[serde(untagged)]
+pub enum KeyValue {
+    Integer(i64),
+    Text(String),
+}
+
+impl Keys {
+    /// The keys as the strings `--key` would have been given.
+    pub fn to_strings(&self) -> Vec<String> {
+        let text = |value: &KeyValue| match value {
+            KeyValue::Integer(number) => number.to_string(),
+            KeyValue::Text(text) => text.clone(),
+        };
+        match self {
+            Keys::None => Vec::new(),
+            Keys::One(value) => vec![text(value)],
+            Keys::Many(values) => values.iter().map(text).collect(),
+        }
+    }
+}
+
+/// Parses a job from TOML text.
+///
+/// # Arguments
+/// * `text` - The contents of the job file.
+///
+/// # Returns
+/// The job, or a description of why it isn't a valid job.
+///
+/// # Examples
+/// ```
+/// let job = parse("algorithm = \"caesar\"\nkey = 3\n").unwrap();
+/// assert_eq!(job.key.to_strings(), vec!["3"]);
+/// ```
+pub fn parse(text: &str) -> Result<Job, String> {
+    toml::from_str(text).map_err(|err| err.message().to_string())
+}
+
+impl Job {
+    /// Resolves relative input and output paths against `base`, the job
+    /// file's directory, so a job runs the same from anywhere.
+    pub fn relative_to(self, base: &Path) -> Job {
+        Job {
+            input_file: self.input_file.map(|file| base.join(file)),
+            output_file: self.output_file.map(|file| base.join(file)),
+            ..self
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_job() {
+        let job = parse(
+            "algorithm = \"caesar,vigenere\"\n\
+             direction = \"encrypt\"\n\
+             key = [3, \"lemon\"]\n\
+             input_file = \"plain.txt\"\n",
+        )
+        .unwrap();
+
+        assert_eq!(job.algorithm.as_deref(), Some("caesar,vigenere"));
+        assert_eq!(job.direction.as_deref(), Some("encrypt"));
+        assert_eq!(job.key.to_strings(), vec!["3", "lemon"]);
+        assert_eq!(job.input_file, Some(PathBuf::from("plain.txt")));
+        assert_eq!(job.output_file, None);
+    }
+
+    #[test]
+    fn test_parse_job_rejects_unknown_fields() {
+        let job = parse("algorithm = \"caesar\"\nkee = 3\n");
+
+        assert!(job.unwrap_err().contains("unknown field `kee`"));
+    }
+}
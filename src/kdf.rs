@@ -0,0 +1,111 @@
+//! The `kdf` module stretches a memorable passphrase into a fixed-length alphabetic key
+//!
+//! This is a PBKDF2-like construction over 64-bit FNV-1a with a SplitMix64 finalizer.
+//! It is meant to make similar passphrases produce very different keys for the
+//! classical ciphers, not to provide modern cryptographic strength.
+
+/// Number of hashing rounds applied to every output block.
+const ITERATIONS: u32 = 10_000;
+
+const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+/// Hashes the given bytes, continuing from `state`.
+///
+/// FNV-1a followed by the SplitMix64 finalizer so a single changed
+/// input bit flips roughly half of the output bits.
+fn hash(state: u64, bytes: &[u8]) -> u64 {
+    let mut h = state;
+    for &b in bytes {
+        h ^= b as u64;
+        h = h.wrapping_mul(FNV_PRIME);
+    }
+
+    h ^= h >> 30;
+    h = h.wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    h ^= h >> 27;
+    h = h.wrapping_mul(0x94d0_49bb_1331_11eb);
+    h ^ (h >> 31)
+}
+
+/// Hashes a key into a 64-bit seed for deterministic pseudo-random choices.
+///
+/// # Examples
+/// ```
+/// assert_eq!(seed("key"), seed("key"));
+/// assert_ne!(seed("key"), seed("kez"));
+/// ```
+pub fn seed(key: &str) -> u64 {
+    hash(FNV_OFFSET_BASIS, key.as_bytes())
+}
+
+/// Derives a deterministic lowercase alphabetic key from a passphrase.
+///
+/// Each output block is seeded from the salt, passphrase and block index,
+/// then re-hashed with the passphrase `ITERATIONS` times. Every byte of the
+/// resulting blocks is reduced mod 26 to a letter.
+///
+/// # Arguments
+/// * `passphrase` - The memorable passphrase to stretch.
+/// * `salt` - Optional salt; different salts give unrelated keys.
+/// * `length` - Number of letters in the derived key.
+///
+/// # Returns
+/// A `String` of `length` lowercase letters.
+///
+/// # Examples
+/// ```
+/// let key = derive_key("correct horse", "pepper", 12);
+/// assert_eq!(key.len(), 12);
+/// ```
+pub fn derive_key(passphrase: &str, salt: &str, length: usize) -> String {
+    let mut key = String::with_capacity(length);
+    let mut block: u32 = 0;
+
+    while key.len() < length {
+        let mut u = hash(FNV_OFFSET_BASIS, salt.as_bytes());
+        u = hash(u, passphrase.as_bytes());
+        u = hash(u, &block.to_be_bytes());
+
+        let mut t = u;
+        for _ in 1..ITERATIONS {
+            u = hash(u, passphrase.as_bytes());
+            t ^= u;
+        }
+
+        for b in t.to_be_bytes() {
+            if key.len() == length {
+                break;
+            }
+            key.push((b'a' + b % 26) as char);
+        }
+        block += 1;
+    }
+
+    key
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_derive_key_is_deterministic() {
+        let first = derive_key("correct horse", "pepper", 20);
+        let second = derive_key("correct horse", "pepper", 20);
+
+        assert_eq!(first, second);
+        assert_eq!(first.len(), 20);
+        assert!(first.chars().all(|c| c.is_ascii_lowercase()));
+    }
+
+    #[test]
+    fn test_derive_key_salt_diverges() {
+        let first = derive_key("correct horse", "pepper", 20);
+        let second = derive_key("correct horse", "paprika", 20);
+        let similar = derive_key("correct horsf", "pepper", 20);
+
+        assert_ne!(first, second);
+        assert_ne!(first, similar);
+    }
+}
@@ -0,0 +1,79 @@
+//! The `layout` module arranges the final output text for transport
+
+/// Wraps the text onto lines of at most `cols` characters.
+///
+/// Newlines are inserted between lines; the last line may be short.
+/// Joining the lines back together recovers the original text.
+///
+/// # Arguments
+/// * `text` - The text to wrap.
+/// * `cols` - Maximum number of characters per line; must be positive.
+///
+/// # Returns
+/// The wrapped `String`.
+///
+/// # Examples
+/// ```
+/// assert_eq!(wrap("abcdefg", 3), "abc\ndef\ng");
+/// ```
+pub fn wrap(text: &str, cols: usize) -> String {
+    let chars: Vec<char> = text.chars().collect();
+    chars
+        .chunks(cols)
+        .map(|line| line.iter().collect::<String>())
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+/// Splits the text into groups of `size` characters separated by spaces.
+///
+/// Any whitespace already in the text is dropped first so groups stay even.
+/// `size` must be positive.
+///
+/// # Examples
+/// ```
+/// assert_eq!(group("abc defgh", 3), "abc def gh");
+/// ```
+pub fn group(text: &str, size: usize) -> String {
+    let chars: Vec<char> = ungroup(text).chars().collect();
+    chars
+        .chunks(size)
+        .map(|chunk| chunk.iter().collect::<String>())
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+/// Removes all whitespace, undoing `group` and `wrap` before decryption.
+pub fn ungroup(text: &str) -> String {
+    text.chars().filter(|c| !c.is_whitespace()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ciphers::{Cipher, PlayfairCipher, VigenereCipher};
+
+    #[test]
+    fn test_wrap() {
+        let cipher = VigenereCipher::new("lemon".to_string());
+        let ciphertext = cipher.encrypt("attackatdawnandretreatatdusk");
+        let wrapped = wrap(&ciphertext, 8);
+        let lines: Vec<&str> = wrapped.lines().collect();
+        let unwrapped: String = lines.concat();
+
+        assert_eq!(lines.len(), 4);
+        assert!(lines[..3].iter().all(|line| line.chars().count() == 8));
+        assert_eq!(lines[3].chars().count(), 4);
+        assert_eq!(cipher.decrypt(&unwrapped), "attackatdawnandretreatatdusk");
+    }
+
+    #[test]
+    fn test_group_ungroup_playfair() {
+        let cipher = PlayfairCipher::new("keyword".to_string());
+        let grouped = group(&cipher.encrypt("MEETMEATNOON"), 5);
+        let plaintext = cipher.decrypt(&ungroup(&grouped));
+
+        assert_eq!(grouped, "NKKUN KRVSE ES");
+        assert_eq!(plaintext, "MEETMEATNOON");
+    }
+}
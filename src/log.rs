@@ -0,0 +1,52 @@
+//! The `log` module provides leveled diagnostics on stderr, controlled by the number of `-v` flags
+//!
+//! Level 0 prints nothing extra, 1 prints warnings, 2 adds per-cipher
+//! explanations and 3 adds a per-character trace.
+
+use crate::color::{self, Stream, Style};
+use std::sync::atomic::{AtomicU8, Ordering};
+
+static LEVEL: AtomicU8 = AtomicU8::new(0);
+
+pub const WARN: u8 = 1;
+pub const INFO: u8 = 2;
+pub const TRACE: u8 = 3;
+
+/// Sets the verbosity level from the number of `-v` flags.
+pub fn set_level(level: u8) {
+    LEVEL.store(level, Ordering::Relaxed);
+}
+
+/// Whether messages at `level` are currently printed.
+pub fn enabled(level: u8) -> bool {
+    LEVEL.load(Ordering::Relaxed) >= level
+}
+
+/// Prints a warning when running with `-v` or more.
+pub fn warn(message: &str) {
+    if enabled(WARN) {
+        eprintln!(
+            "{} {}",
+            color::paint("warning:", Style::Yellow, Stream::Stderr),
+            message
+        );
+    }
+}
+
+/// Prints an explanation when running with `-vv` or more.
+pub fn info(message: &str) {
+    if enabled(INFO) {
+        eprintln!(
+            "{} {}",
+            color::paint("info:", Style::Cyan, Stream::Stderr),
+            message
+        );
+    }
+}
+
+/// Prints a trace line when running with `-vvv`.
+pub fn trace(message: &str) {
+    if enabled(TRACE) {
+        eprintln!("trace: {}", message);
+    }
+}
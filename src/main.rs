@@ -1,11 +1,62 @@
+/// Like `println!`, but returns an `OutputError` to propagate instead of
+/// panicking when stdout can't be written, and exits quietly when it is a
+/// closed pipe.
+macro_rules! outln {
+    ($($arg:tt)*) => {
+        $crate::output::write_line(&format!($($arg)*))
+    };
+}
+
+mod analysis;
 mod ciphers;
-use ciphers::Cipher;
+mod classify;
+mod clipboard;
+mod color;
+mod compare;
+mod dictionary;
+mod encoding;
+mod error;
+mod exit;
+mod interrupt;
+mod job;
+mod kdf;
+mod layout;
+mod log;
+mod normalize;
+mod output;
+mod partial_key;
+mod phonetic;
+mod qr;
+mod region;
+mod symbolic;
+mod transform;
+mod translit;
+mod wordlist;
+use ciphers::vigenere;
+use ciphers::{
+    AlbertiCipher, Alphabet, AlphabetCaesarCipher, BazeriesCipher, BifidCipher, ByteCaesarCipher,
+    ByteCipher, CaesarCipher, Cipher, CipherError, ColumnarCipher, ColumnarVariant, DigrafidCipher,
+    DigraphStep, GromarkCipher, LetterMerge, PlayfairCipher, QuagmireCipher, RailFenceCipher,
+    ReversedCaesarCipher, Rot18Cipher, Rot47Cipher, ShuffleCipher, SquareFill, TrifidCipher,
+    UnicodeRotCipher, VicCipher, VigenereCipher, XorCipher,
+};
 
-use clap::Parser;
-use std::process;
+use clap::{ArgEnum, Parser};
+use color::{Stream, Style};
+use encoding::{ByteFormat, InputFormat};
+use error::Error;
+use exit::ExitCode;
+use normalize::Normalization;
+use output::OutputEncoding;
+use regex::Regex;
+use std::collections::HashSet;
+use std::fs::{self, File};
+use std::io::{self, BufRead, BufReader, Write};
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
+use std::time::Instant;
 
-#[derive(clap::ArgEnum, Clone, Debug)]
+#[derive(clap::ArgEnum, Clone, Debug, PartialEq, Eq)]
 enum Algorithm {
     #[clap(name = "caesar", alias = "c")]
     Caesar,
@@ -13,8 +64,178 @@ enum Algorithm {
     Vigenère,
     #[clap(name = "playfair", alias = "p")]
     Playfair,
+    #[clap(name = "columnar", alias = "col")]
+    Columnar,
+    #[clap(name = "railfence", alias = "r")]
+    RailFence,
+    #[clap(name = "bifid")]
+    Bifid,
+    #[clap(name = "trifid")]
+    Trifid,
+    #[clap(name = "quagmire1", alias = "q1")]
+    Quagmire,
+    #[clap(name = "vic")]
+    Vic,
+    #[clap(name = "gromark")]
+    Gromark,
+    #[clap(name = "alberti")]
+    Alberti,
+    #[clap(name = "shuffle")]
+    Shuffle,
+    #[clap(name = "bazeries")]
+    Bazeries,
+    #[clap(name = "digrafid")]
+    Digrafid,
+    #[clap(name = "xor", alias = "vernam")]
+    Xor,
+    #[clap(name = "byte-caesar")]
+    ByteCaesar,
+    #[clap(name = "rot18")]
+    Rot18,
+    #[clap(name = "rot47")]
+    Rot47,
+    #[clap(name = "unicode-rot")]
+    UnicodeRot,
+}
+
+impl Algorithm {
+    /// Whether the algorithm substitutes each character on its own, so the
+    /// input could be processed in chunks rather than held in memory at once.
+    fn is_streaming_capable(&self) -> bool {
+        matches!(
+            self,
+            Algorithm::Caesar
+                | Algorithm::Vigenère
+                | Algorithm::Quagmire
+                | Algorithm::Gromark
+                | Algorithm::Alberti
+                | Algorithm::Xor
+                | Algorithm::ByteCaesar
+                | Algorithm::Rot18
+                | Algorithm::Rot47
+                | Algorithm::UnicodeRot
+        )
+    }
+
+    /// Whether the algorithm fractionates its input in blocks of `--period`
+    /// letters (pairs for Digrafid), or for Alberti, turns its disk every
+    /// `--period` letters.
+    fn uses_period(&self) -> bool {
+        matches!(
+            self,
+            Algorithm::Bifid | Algorithm::Trifid | Algorithm::Alberti | Algorithm::Digrafid
+        )
+    }
+
+    /// Whether the algorithm consumes a `--key`.
+    fn requires_key(&self) -> bool {
+        !matches!(self, Algorithm::Rot18 | Algorithm::Rot47)
+    }
+
+    /// Whether the supplied key has the type this algorithm expects.
+    fn accepts_key(&self, key: Option<&KeyType>) -> bool {
+        matches!(
+            (self.sample_key(), key),
+            (Some(KeyType::Integer(_)), Some(KeyType::Integer(_)))
+                | (Some(KeyType::Text(_)), Some(KeyType::Text(_)))
+                | (None, None)
+        )
+    }
+
+    /// Whether encrypting with the key is the same as decrypting, like ROT13.
+    fn is_self_inverse(&self, key: Option<&KeyType>, options: &CipherOptions) -> bool {
+        match (self, key) {
+            (Algorithm::Rot18 | Algorithm::Rot47, _) => true,
+            (Algorithm::Caesar, _) if options.reverse_alphabet => true,
+            (Algorithm::Caesar, Some(KeyType::Integer(shift))) => {
+                let modulus = options.alphabet.as_ref().map_or(26, |a| a.len() as i32);
+                (2 * shift).rem_euclid(modulus) == 0
+            }
+            _ => false,
+        }
+    }
+
+    /// A valid key for the algorithm, used to probe cipher properties
+    /// when the user hasn't supplied one.
+    fn sample_key(&self) -> Option<KeyType> {
+        match self {
+            Algorithm::Caesar
+            | Algorithm::RailFence
+            | Algorithm::UnicodeRot
+            | Algorithm::ByteCaesar => Some(KeyType::Integer(3)),
+            Algorithm::Vic => Some(KeyType::Integer(77651)),
+            Algorithm::Gromark => Some(KeyType::Integer(23452)),
+            Algorithm::Bazeries => Some(KeyType::Integer(3752)),
+            Algorithm::Vigenère
+            | Algorithm::Playfair
+            | Algorithm::Columnar
+            | Algorithm::Quagmire
+            | Algorithm::Bifid
+            | Algorithm::Alberti
+            | Algorithm::Shuffle
+            | Algorithm::Digrafid
+            | Algorithm::Xor => Some(KeyType::Text("key".to_string())),
+            Algorithm::Trifid => Some(KeyType::Text("abcdefghijklmnopqrstuvwxyz.".to_string())),
+            Algorithm::Rot18 | Algorithm::Rot47 => None,
+        }
+    }
+
+    /// A valid `--key2` for the algorithms that need a second key, used by
+    /// `selftest` and shown by `examples`.
+    fn sample_key2(&self) -> Option<&'static str> {
+        match self {
+            Algorithm::Quagmire => Some("flower"),
+            // Hayhanen's key phrase, from "I Dream of Jeanie with the Light Brown Hair"
+            Algorithm::Vic => Some("idreamofjeaniewiththelightbrownhair"),
+            Algorithm::Gromark => Some("enigma"),
+            Algorithm::Digrafid => Some("vocabulary"),
+            Algorithm::Alberti => Some(SELFTEST_INDEX),
+            _ => None,
+        }
+    }
+
+    /// The name the algorithm is given on the command line, e.g. `railfence`.
+    fn cli_name(&self) -> &'static str {
+        self.to_possible_value().unwrap().get_name()
+    }
+
+    /// The human-friendly name of the algorithm's cipher, e.g. "Vigenère cipher",
+    /// for output that has no cipher built from the user's key.
+    fn display_name(&self) -> &'static str {
+        let options = CipherOptions {
+            key2: self.sample_key2().map(String::from),
+            ..CipherOptions::default()
+        };
+        build_cipher(self, self.sample_key().as_ref(), &options)
+            .map_or(self.cli_name(), |cipher| cipher.name())
+    }
+
+    /// Arguments of a ready-to-run command encrypting the sample text with
+    /// the algorithm's sample keys, without the program name.
+    fn example_args(&self) -> Vec<String> {
+        let mut args = vec!["-a", self.cli_name(), "-d", "encrypt"]
+            .into_iter()
+            .map(String::from)
+            .collect::<Vec<String>>();
+        match self.sample_key() {
+            Some(KeyType::Integer(key)) => args.extend(["-k".to_string(), key.to_string()]),
+            Some(KeyType::Text(key)) => args.extend(["-k".to_string(), key]),
+            None => {}
+        }
+        if let Some(key2) = self.sample_key2() {
+            args.extend(["--key2".to_string(), key2.to_string()]);
+        }
+        args.push(SELFTEST_SAMPLE.to_string());
+        args
+    }
 }
 
+/// Default cap on the input size, in bytes, for ciphers that need the whole input.
+const DEFAULT_MAX_MEMORY: &str = "268435456";
+
+/// Largest key space that brute force mode will enumerate.
+const MAX_BRUTE_FORCE_KEYS: u64 = 10_000;
+
 #[derive(clap::ArgEnum, Clone, Debug)]
 enum Direction {
     #[clap(name = "encrypt", alias = "e")]
@@ -23,6 +244,34 @@ enum Direction {
     Decrypt,
 }
 
+#[derive(clap::ArgEnum, Clone, Debug)]
+enum Variant {
+    #[clap(name = "standard")]
+    Standard,
+    #[clap(name = "myszkowski")]
+    Myszkowski,
+}
+
+#[derive(clap::ArgEnum, Clone, Copy, Debug)]
+enum FillOrder {
+    #[clap(name = "rows")]
+    Rows,
+    #[clap(name = "columns")]
+    Columns,
+    #[clap(name = "reverse")]
+    Reverse,
+}
+
+#[derive(clap::ArgEnum, Clone, Debug)]
+enum OutputCase {
+    #[clap(name = "upper", alias = "u")]
+    Upper,
+    #[clap(name = "lower", alias = "l")]
+    Lower,
+    #[clap(name = "preserve", alias = "p")]
+    Preserve,
+}
+
 #[derive(Clone, Debug)]
 enum KeyType {
     Integer(i32),
@@ -41,68 +290,2991 @@ impl FromStr for KeyType {
     }
 }
 
+#[derive(clap::Subcommand, Debug)]
+enum Command {
+    /// Print letter frequency analysis of the input
+    Analyze { input_text: String },
+    /// Guess the cipher family that produced the ciphertext
+    Classify { input_text: String },
+    /// Print the Vigenère tabula recta
+    Tabula {
+        // alphabet heading the square's rows and columns
+        #[clap(long, default_value = "ABCDEFGHIJKLMNOPQRSTUVWXYZ")]
+        alphabet: Alphabet,
+    },
+    /// Encrypt the input with two ciphers and highlight where they differ
+    Compare {
+        // first algorithm to compare
+        #[clap(long, arg_enum)]
+        first: Algorithm,
+
+        // key for the first algorithm
+        #[clap(long, allow_hyphen_values = true)]
+        first_key: Option<KeyType>,
+
+        // second algorithm to compare
+        #[clap(long, arg_enum)]
+        second: Algorithm,
+
+        // key for the second algorithm
+        #[clap(long, allow_hyphen_values = true)]
+        second_key: Option<KeyType>,
+
+        input_text: String,
+    },
+    /// Round-trip a built-in sample through every cipher and report the results
+    Selftest,
+    /// Print a ready-to-copy example command for every algorithm
+    Examples,
+    /// Encrypt the input; the same as `-d encrypt`
+    Encrypt(Args),
+    /// Decrypt the input; the same as `-d decrypt`
+    Decrypt(Args),
+}
+
 #[derive(Parser, Debug)]
 // #[command(version, about, long_about = None)]
+#[clap(subcommand_negates_reqs = true, args_conflicts_with_subcommands = true)]
+struct Cli {
+    #[clap(subcommand)]
+    command: Option<Command>,
+
+    #[clap(flatten)]
+    args: Args,
+}
+
+#[derive(clap::Args, Debug)]
 struct Args {
-    // encryption algorithm to use
-    #[clap(short, long, arg_enum)]
-    algorithm: Algorithm,
+    // encryption algorithm(s) to use, comma separated to chain them
+    #[clap(
+        short,
+        long,
+        arg_enum,
+        use_value_delimiter = true,
+        required_unless_present_any = &["all-algorithms", "unicode-rot", "job"]
+    )]
+    algorithm: Vec<Algorithm>,
+
+    // run the input through every algorithm compatible with the key
+    #[clap(long, conflicts_with_all = &["algorithm", "brute-force"])]
+    all_algorithms: bool,
 
-    // encrypt/decrypt direction
+    // shorthand for `-a unicode-rot -k N`: shift every character by N
+    // Unicode scalar values, skipping the surrogate range
+    #[clap(
+        long,
+        value_name = "N",
+        allow_hyphen_values = true,
+        conflicts_with_all = &["algorithm", "all-algorithms", "key"]
+    )]
+    unicode_rot: Option<i32>,
+
+    // encrypt/decrypt direction; required unless given by the encrypt or
+    // decrypt subcommand or the job file
     #[clap(short, long, arg_enum)]
-    direction: Direction,
+    direction: Option<Direction>,
+
+    // encryption/decryption key, repeated once per keyed algorithm;
+    // negative shifts can be passed directly, e.g. `-k -3`, and Caesar
+    // also takes `today` (day of month), `dow` (weekday) or a percentage
+    // of the alphabet like `50%` as the shift; a Vigenère decryption key
+    // may mark up to three unknown letters with `?`, e.g. `k?y`
+    #[clap(short = 'k', long, allow_hyphen_values = true)]
+    key: Vec<KeyType>,
+
+    // read the key from this environment variable instead of --key,
+    // keeping it out of shell history and process listings
+    #[clap(
+        long,
+        value_name = "VARNAME",
+        conflicts_with_all = &["key", "unicode-rot"]
+    )]
+    key_from_env: Option<String>,
+
+    // second keyword for ciphers that take two: the Quagmire I indicator,
+    // with --key scrambling its plaintext alphabet, the VIC key phrase, the
+    // Gromark keyword mixing its cipher alphabet, with --key as the primer,
+    // the Alberti index letter, with --key mixing the disk, or the keyword
+    // of the vertical Digrafid grid, with --key keying the horizontal one
+    #[clap(long, value_name = "KEY")]
+    key2: Option<String>,
 
-    // encryption/decryption key
-    #[clap(short = 'k', long)]
-    key: KeyType,
+    // outer layer, usually a transposition, applied to the output of the
+    // --algorithm chain when encrypting and peeled off first when decrypting
+    #[clap(long, arg_enum, conflicts_with_all = &["all-algorithms", "brute-force"])]
+    outer_algorithm: Option<Algorithm>,
+
+    // key for the outer layer
+    #[clap(long, allow_hyphen_values = true, requires = "outer-algorithm")]
+    outer_key: Option<KeyType>,
 
     // in decryption mode, brute force
     #[clap(short = 'b', long)]
     brute_force: bool,
 
-    input_text: String,
+    // in brute force mode, try every word in this file as a Vigenère/Playfair key;
+    // Ctrl-C stops the search and prints the best candidates so far
+    #[clap(long, value_name = "FILE", requires = "brute-force")]
+    wordlist: Option<PathBuf>,
+
+    // in brute force and wildcard key searches, print only the best key,
+    // e.g. to feed into another tool
+    #[clap(long, conflicts_with_all = &["all-algorithms", "crib"])]
+    key_only: bool,
+
+    // print how many keys a brute force, wordlist or wildcard search would
+    // try and roughly how long it would take, then exit without searching
+    #[clap(long)]
+    benchmark_keyspace: bool,
+
+    // run searches estimated to try more than 10000 keys without stopping to ask
+    #[clap(long)]
+    yes: bool,
+
+    // Vigenère decrypt: recover the key letters under this known plaintext
+    #[clap(long, value_name = "TEXT")]
+    crib: Option<String>,
+
+    // character position of the crib; slides it across the ciphertext if omitted
+    #[clap(long, value_name = "N", requires = "crib")]
+    crib_offset: Option<usize>,
+
+    // print how long the encryption/decryption took to stderr
+    #[clap(long)]
+    time: bool,
+
+    // after decrypting, print the share of output words found in this
+    // word-per-line dictionary to stderr
+    #[clap(long, value_name = "FILE")]
+    dictionary: Option<PathBuf>,
+
+    // in brute force mode, stop at the first key whose output is mostly
+    // dictionary words, listing every key if none is
+    #[clap(long, requires_all = &["brute-force", "dictionary"])]
+    first_match: bool,
+
+    // after decrypting, reinsert spaces between the --dictionary words of
+    // each plaintext, for text whose spaces the cipher stripped
+    #[clap(long, requires = "dictionary")]
+    segment: bool,
+
+    // after encrypting, decrypt the output again and warn if it doesn't
+    // reproduce the input (e.g. Playfair padding)
+    #[clap(long)]
+    verify: bool,
+
+    // like --verify, but a failed round trip is an error and no output is written
+    #[clap(long)]
+    strict_roundtrip: bool,
+
+    // fail instead of warning when the ciphertext can't be valid, e.g.
+    // Playfair ciphertext with an odd number of letters
+    #[clap(long)]
+    strict: bool,
+
+    // verbosity: -v warnings, -vv per-cipher explanation, -vvv per-character trace
+    #[clap(short = 'v', long, parse(from_occurrences))]
+    verbose: u8,
+
+    // never color the output; it is also plain when piped or NO_COLOR is set
+    #[clap(long, global = true)]
+    no_color: bool,
+
+    // columnar transposition read-out variant
+    #[clap(long, arg_enum, default_value = "standard")]
+    variant: Variant,
+
+    // rail fence starting position within the zigzag cycle
+    #[clap(long, default_value = "0")]
+    offset: usize,
+
+    // custom alphabet for Caesar to rotate within, e.g. 0123456789, `greek`
+    // for the 24 Greek letters in both cases (a word-final sigma is written ς),
+    // or `cyrillic` for the 33 Russian letters, Ё included, in both cases
+    #[clap(long)]
+    alphabet: Option<Alphabet>,
+
+    // shift Caesar along the reversed alphabet Z..A, flipping each letter
+    // as in Atbash before shifting; a key of 0 is Atbash
+    #[clap(long, conflicts_with = "alphabet")]
+    reverse_alphabet: bool,
+
+    // Playfair square size: 5 (A-Z with one letter merged into another)
+    // or 6 (A-Z and 0-9)
+    #[clap(long, default_value = "5", possible_values = ["5", "6"])]
+    square_size: usize,
+
+    // how the Playfair keyword and the rest of the alphabet fill the
+    // square: row by row, column by column, or row by row with the rest
+    // of the alphabet reversed
+    #[clap(long, arg_enum, default_value = "rows")]
+    fill_order: FillOrder,
+
+    // the letter the 5x5 Playfair square leaves out and the letter read
+    // in its place, as FROM=TO
+    #[clap(long, value_name = "FROM=TO", default_value = "J=I")]
+    merge: LetterMerge,
+
+    // print the column read order computed from each columnar key
+    #[clap(long)]
+    show_schedule: bool,
+
+    // print the grid a lone columnar or rail fence cipher writes the
+    // plaintext into: the rows under the key, or the zigzag across the rails
+    #[clap(long)]
+    show_grid: bool,
+
+    // block length for the fractionating ciphers (bifid, trifid, and
+    // digrafid, in pairs), or the letters between Alberti disk turns;
+    // defaults to 5
+    #[clap(long, value_name = "N")]
+    period: Option<usize>,
+
+    // print to stderr the command that decrypts this encryption's output
+    #[clap(long, conflicts_with_all = &["all-algorithms", "brute-force"])]
+    print_decrypt_cmd: bool,
+
+    // print how each key is parsed and cleaned, and the Playfair square,
+    // then exit without encrypting or decrypting
+    #[clap(long, conflicts_with_all = &["all-algorithms", "brute-force"])]
+    explain_key: bool,
+
+    // Playfair: keep spaces and punctuation at their positions instead of dropping them
+    #[clap(long)]
+    keep_punctuation: bool,
+
+    // stretch text keys into derived keys of this length
+    #[clap(long, value_name = "LEN")]
+    derive_key: Option<usize>,
+
+    // stretch Vigenère keys into derived keys of this length, with the
+    // same unsalted derivation as --derive-key rather than repetition
+    #[clap(long, value_name = "LEN", conflicts_with = "derive-key")]
+    key_stretch_to: Option<usize>,
+
+    // salt mixed into derived keys
+    #[clap(long, default_value = "", requires = "derive-key")]
+    salt: String,
+
+    // only transform the parts of the input matching this regex
+    // (or its first capture group); decrypt with the same regex
+    #[clap(long, value_name = "REGEX")]
+    region: Option<Regex>,
+
+    // report control characters and unusual whitespace in the input
+    #[clap(long)]
+    warn_control: bool,
+
+    // fail instead of passing characters through when no cipher in the
+    // chain changes them, e.g. spaces and punctuation for Caesar
+    #[clap(long)]
+    fail_on_unencrypted: bool,
+
+    // encrypt only the first N characters of the input and print them as a
+    // preview; transposition and fractionating ciphers mix the whole text,
+    // so their preview doesn't match the start of the full ciphertext
+    #[clap(
+        long,
+        value_name = "N",
+        conflicts_with_all = &["all-algorithms", "brute-force", "explain-key"]
+    )]
+    preview: Option<usize>,
+
+    // encipher each whitespace-separated word independently,
+    // restarting the key at the start of every word
+    #[clap(long, conflicts_with = "region")]
+    per_word: bool,
+
+    // encipher each line independently, restarting the key on every line
+    // and keeping empty lines and `\n`/`\r\n` endings byte for byte
+    #[clap(long, conflicts_with_all = &["region", "per-word"])]
+    by_line: bool,
+
+    // casing applied to the final output
+    #[clap(long, arg_enum, default_value = "preserve")]
+    output_case: OutputCase,
+
+    // split the output into space-separated groups of this many characters
+    #[clap(long, value_name = "SIZE")]
+    group: Option<usize>,
+
+    // strip all whitespace from the input, e.g. to decrypt grouped ciphertext
+    #[clap(long)]
+    ungroup: bool,
+
+    // wrap the output onto lines of this many characters
+    #[clap(long, value_name = "COLS")]
+    wrap: Option<usize>,
+
+    // byte encoding of the written output
+    #[clap(long, arg_enum, default_value = "utf8")]
+    output_encoding: OutputEncoding,
+
+    // prefix the output with a byte order mark (utf8 and utf16le)
+    #[clap(long)]
+    bom: bool,
+
+    // read the input from these files, concatenated in order
+    #[clap(short = 'i', long, conflicts_with = "input-text")]
+    input_file: Vec<PathBuf>,
+
+    // read the input from the system clipboard (needs the clipboard feature)
+    #[clap(long, conflicts_with_all = &["input-text", "input-file"])]
+    from_clipboard: bool,
+
+    // Unicode normalization applied to the input first, so accented letters
+    // encipher the same however they were typed; decrypt with the same form
+    #[clap(long, arg_enum, value_name = "FORM")]
+    normalize: Option<Normalization>,
+
+    // spell out letters the ASCII ciphers skip, e.g. `de` turns ä into ae
+    // and ß into ss; lossy, so decrypting doesn't restore them
+    #[clap(long, arg_enum, value_name = "LANG")]
+    translit: Option<translit::Language>,
+
+    // keyless transform run before the ciphers when encrypting and after
+    // them when decrypting; repeat to apply several, first given first
+    #[clap(long, arg_enum, value_name = "NAME", multiple_occurrences = true)]
+    transform: Vec<transform::Transform>,
+
+    // shift the digits by this key, modulo 10, apart from the letters the
+    // ciphers encipher; runs after the ciphers when encrypting
+    #[clap(long, value_name = "N", allow_hyphen_values = true)]
+    digit_key: Option<i32>,
+
+    // refuse inputs larger than this many bytes unless every algorithm
+    // substitutes character by character
+    #[clap(long, value_name = "BYTES", default_value = DEFAULT_MAX_MEMORY)]
+    max_memory: u64,
+
+    // write the output text back to the input file instead of printing the report;
+    // a lone xor or byte-caesar reads and writes the file as raw bytes
+    #[clap(long, requires = "input-file")]
+    in_place: bool,
+
+    // compare the output text with this file instead of printing it,
+    // exiting with code 5 and a summary of the first difference on mismatch
+    #[clap(long, value_name = "PATH", conflicts_with = "in-place")]
+    expect_file: Option<PathBuf>,
+
+    // separator inserted between concatenated input files
+    #[clap(long, default_value = "", requires = "input-file")]
+    separator: String,
+
+    // how to interpret the input; hex, base64 and base32 give raw bytes to a lone
+    // XOR or byte Caesar cipher and decode to text for every other cipher
+    #[clap(long, arg_enum, default_value = "text")]
+    input_format: InputFormat,
+
+    // how the bytes a lone XOR or byte Caesar cipher produces from hex,
+    // base64 or base32 input are written out
+    #[clap(long, arg_enum, default_value = "hex")]
+    byte_format: ByteFormat,
+
+    // leave the `=` padding off base32 output
+    #[clap(long)]
+    no_padding: bool,
+
+    // print the output as plain text or, in builds with the `qr` feature,
+    // as a QR code drawn with block characters
+    #[clap(long, arg_enum, default_value = "text")]
+    format: qr::OutputFormat,
+
+    // spell the output out in NATO phonetic words when encrypting, and read
+    // the input as phonetic words when decrypting
+    #[clap(long)]
+    phonetic: bool,
+
+    // read the algorithm, direction, keys, files and transforms from this
+    // TOML job file; flags given on the command line take precedence
+    #[clap(long, value_name = "FILE")]
+    job: Option<PathBuf>,
+
+    // write the output text to this file instead of printing the report
+    #[clap(long, value_name = "PATH", conflicts_with_all = &["in-place", "expect-file"])]
+    output_file: Option<PathBuf>,
+
+    #[clap(required_unless_present_any = &["input-file", "from-clipboard", "job", "explain-key"])]
+    input_text: Option<String>,
 }
 
 fn main() {
-    let args = Args::parse();
+    // Usage errors exit with 1 rather than clap's default of 2, which
+    // the exit code contract reserves for invalid keys
+    let cli = match Cli::try_parse() {
+        Ok(cli) => cli,
+        Err(err) => {
+            let _ = err.print();
+            exit::exit(match err.use_stderr() {
+                true => ExitCode::Usage,
+                false => ExitCode::Success,
+            });
+        }
+    };
+    if let Err(err) = run(cli) {
+        eprintln!("Error: {}", err.chain());
+        exit::exit(err.exit_code());
+    }
+}
+
+/// Runs the parsed command line.
+///
+/// # Returns
+/// The error that ended the run early, which `main` reports along with
+/// its causes before exiting with the matching code.
+fn run(cli: Cli) -> Result<(), Error> {
+    // The encrypt and decrypt subcommands take the same arguments as the
+    // top level, with the direction set by the subcommand
+    let (command, mut args) = match cli.command {
+        Some(Command::Encrypt(args)) => (None, with_direction(args, Direction::Encrypt)?),
+        Some(Command::Decrypt(args)) => (None, with_direction(args, Direction::Decrypt)?),
+        command => (command, cli.args),
+    };
+    log::set_level(args.verbose);
+    color::init(args.no_color);
+    if let Some(path) = args.job.clone() {
+        apply_job(&mut args, &path)?;
+    }
+    if let Some(shift) = args.unicode_rot {
+        args.algorithm = vec![Algorithm::UnicodeRot];
+        args.key = vec![KeyType::Integer(shift)];
+    }
+    if let Some(name) = &args.key_from_env {
+        args.key = vec![key_from_env(name)?];
+    }
+    if args.from_clipboard {
+        if !clipboard::AVAILABLE {
+            return Err(Error::Usage(clipboard::unavailable_message()));
+        }
+        args.input_text =
+            Some(clipboard::read().map_err(|err| Error::io("Could not read the clipboard", err))?);
+    }
+    // The outer layer is the last link of the chain, with its key after the others
+    if let Some(outer) = args.outer_algorithm.clone() {
+        match (outer.requires_key(), args.outer_key.take()) {
+            (true, Some(key)) => args.key.push(key),
+            (true, None) => {
+                return Err(Error::Usage(format!(
+                    "--outer-algorithm {} needs an --outer-key.",
+                    outer.cli_name()
+                )))
+            }
+            (false, Some(_)) => {
+                return Err(Error::Usage(format!(
+                    "--outer-algorithm {} takes no key.",
+                    outer.cli_name()
+                )))
+            }
+            (false, None) => {}
+        }
+        args.algorithm.push(outer);
+    }
+
+    match &command {
+        Some(Command::Analyze { input_text }) => {
+            analysis::print_report(input_text)?;
+            return Ok(());
+        }
+        Some(Command::Classify { input_text }) => {
+            classify::print_report(input_text)?;
+            return Ok(());
+        }
+        Some(Command::Tabula { alphabet }) => {
+            for line in vigenere::tabula_recta(alphabet) {
+                outln!("{}", line)?;
+            }
+            return Ok(());
+        }
+        Some(Command::Compare {
+            first,
+            first_key,
+            second,
+            second_key,
+            input_text,
+        }) => {
+            let options = CipherOptions::default();
+            let first = build_cipher_checked(first, first_key.as_ref(), &options)?;
+            let second = build_cipher_checked(second, second_key.as_ref(), &options)?;
+
+            compare::print_comparison(
+                first.name(),
+                &first.encrypt(input_text),
+                second.name(),
+                &second.encrypt(input_text),
+            )?;
+            return Ok(());
+        }
+        Some(Command::Selftest) => {
+            run_selftest()?;
+            return Ok(());
+        }
+        Some(Command::Examples) => {
+            for (i, algorithm) in Algorithm::value_variants().iter().enumerate() {
+                if i > 0 {
+                    outln!("")?;
+                }
+                outln!("# {}", algorithm.display_name())?;
+                outln!("{}", example_command(algorithm))?;
+            }
+            return Ok(());
+        }
+        Some(Command::Encrypt(_) | Command::Decrypt(_)) | None => {}
+    }
+
+    let direction = args.direction.clone().ok_or_else(|| {
+        Error::Usage(
+            "No direction given: pass --direction or use the encrypt or decrypt subcommand."
+                .to_string(),
+        )
+    })?;
 
     // Check for invalid combination of arguments:
     // Brute force can only be done in decrypt mode
-    if args.brute_force && matches!(args.direction, Direction::Encrypt) {
-        eprintln!("Error: Brute force mode cannot be used with encryption.");
-        process::exit(1); // Exit with a non-zero status code to indicate an error
+    if args.brute_force && matches!(direction, Direction::Encrypt) {
+        return Err(Error::Usage(
+            "Brute force mode cannot be used with encryption.".to_string(),
+        ));
+    }
+    // and round trip verification only in encrypt mode
+    if args.verify && matches!(direction, Direction::Decrypt) {
+        return Err(Error::Usage(
+            "--verify only applies to encryption.".to_string(),
+        ));
+    }
+    if args.strict_roundtrip && matches!(direction, Direction::Decrypt) {
+        return Err(Error::Usage(
+            "--strict-roundtrip only applies to encryption.".to_string(),
+        ));
+    }
+    if args.preview.is_some() && matches!(direction, Direction::Decrypt) {
+        return Err(Error::Usage(
+            "--preview only applies to encryption.".to_string(),
+        ));
+    }
+    if args.segment && matches!(direction, Direction::Encrypt) {
+        return Err(Error::Usage(
+            "--segment only applies to decryption.".to_string(),
+        ));
+    }
+    if args.print_decrypt_cmd && matches!(direction, Direction::Decrypt) {
+        return Err(Error::Usage(
+            "--print-decrypt-cmd only applies to encryption.".to_string(),
+        ));
+    }
+
+    let options = CipherOptions {
+        variant: match args.variant {
+            Variant::Standard => ColumnarVariant::Standard,
+            Variant::Myszkowski => ColumnarVariant::Myszkowski,
+        },
+        square_size: args.square_size,
+        fill: match args.fill_order {
+            FillOrder::Rows => SquareFill::Rows,
+            FillOrder::Columns => SquareFill::Columns,
+            FillOrder::Reverse => SquareFill::Reverse,
+        },
+        merge: args.merge,
+        keep_punctuation: args.keep_punctuation,
+        offset: args.offset,
+        alphabet: args.alphabet.clone(),
+        reverse_alphabet: args.reverse_alphabet,
+        period: args.period.unwrap_or(DEFAULT_PERIOD),
+        key2: args.key2.clone(),
+    };
+
+    match args.period {
+        Some(0) => return Err(Error::Usage("--period must be positive.".to_string())),
+        Some(_)
+            if !args.all_algorithms
+                && !args
+                    .algorithm
+                    .iter()
+                    .any(|algorithm| algorithm.uses_period()) =>
+        {
+            return Err(Error::Usage(
+                "--period is unused: only bifid, trifid, alberti and digrafid take a period."
+                    .to_string(),
+            ))
+        }
+        _ => {}
+    }
+
+    if args.group == Some(0) {
+        return Err(Error::Usage("--group must be positive.".to_string()));
+    }
+    if args.wrap == Some(0) {
+        return Err(Error::Usage("--wrap must be positive.".to_string()));
+    }
+
+    if args.square_size == 6 && args.merge != LetterMerge::default() {
+        return Err(Error::Usage(
+            "--merge is unused: the 6x6 square holds every letter.".to_string(),
+        ));
+    }
+
+    if args.explain_key {
+        let mut keys = args.key.iter();
+        for algorithm in &args.algorithm {
+            let key = match algorithm.requires_key() {
+                true => keys.next(),
+                false => None,
+            };
+            for line in describe_key(algorithm, key, &options) {
+                outln!("{}", line)?;
+            }
+        }
+        return Ok(());
+    }
+
+    if args.show_schedule && !args.algorithm.contains(&Algorithm::Columnar) {
+        return Err(Error::Usage(
+            "--show-schedule only applies to the columnar algorithm.".to_string(),
+        ));
+    }
+
+    if args.show_grid
+        && !matches!(
+            args.algorithm.as_slice(),
+            [Algorithm::Columnar | Algorithm::RailFence]
+        )
+    {
+        return Err(Error::Usage(
+            "--show-grid only applies to a single columnar or rail fence algorithm.".to_string(),
+        ));
+    }
+
+    if args.in_place && args.input_file.len() != 1 {
+        return Err(Error::Usage(
+            "--in-place needs exactly one input file.".to_string(),
+        ));
+    }
+
+    if args.format == qr::OutputFormat::Qr && !qr::AVAILABLE {
+        return Err(Error::Usage(qr::unavailable_message()));
+    }
+
+    let streaming = !args.all_algorithms
+        && args
+            .algorithm
+            .iter()
+            .all(|algorithm| algorithm.is_streaming_capable());
+    if !streaming {
+        let size = match &args.input_text {
+            Some(text) => text.len() as u64,
+            None => input_files_size(&args.input_file, &args.separator)?,
+        };
+        if size > args.max_memory {
+            return Err(Error::Usage(format!(
+                "Input is {} bytes, over the --max-memory limit of {} bytes. \
+                 Raise the limit or use a character-by-character algorithm \
+                 such as caesar, vigenere or xor.",
+                size, args.max_memory
+            )));
+        }
+    }
+
+    // A file encrypted in place by a lone byte cipher is read and written
+    // back as raw bytes, so it needn't be text
+    let raw_input = match (args.in_place, args.algorithm.as_slice(), args.input_format) {
+        (true, [Algorithm::Xor | Algorithm::ByteCaesar], InputFormat::Text) => {
+            let path = &args.input_file[0];
+            Some(
+                fs::read(path)
+                    .map_err(|err| Error::io(format!("Could not read {}", path.display()), err))?,
+            )
+        }
+        _ => None,
+    };
+
+    let input_text = match (&args.input_text, &raw_input) {
+        (Some(text), _) => text.clone(),
+        (None, Some(_)) => String::new(),
+        (None, None) => read_input_files(&args.input_file, &args.separator)?,
+    };
+
+    let input_text = match args.ungroup {
+        true => layout::ungroup(&input_text),
+        false => input_text,
+    };
+
+    let raw_in_place = raw_input.is_some();
+    let (input_text, input_bytes) = match (raw_input, args.input_format) {
+        (Some(bytes), _) => (input_text, Some(bytes)),
+        (None, InputFormat::Text) => (input_text, None),
+        (None, format) => {
+            let bytes = encoding::decode(&input_text, format)
+                .map_err(|message| Error::Decode(format!("Could not decode input: {}", message)))?;
+            match (args.algorithm.as_slice(), String::from_utf8(bytes.clone())) {
+                ([Algorithm::Xor | Algorithm::ByteCaesar], _) => (input_text, Some(bytes)),
+                (_, Ok(text)) => (text, None),
+                (_, Err(_)) => {
+                    return Err(Error::Decode(
+                        "Decoded input isn't valid UTF-8 text.".to_string(),
+                    ))
+                }
+            }
+        }
+    };
+    let input_text = match (args.phonetic, &direction) {
+        (true, Direction::Decrypt) => phonetic::decode(&input_text).map_err(|message| {
+            Error::Decode(format!("Could not read phonetic input: {}", message))
+        })?,
+        _ => input_text,
+    };
+    let input_text = match args.translit {
+        Some(language) => translit::transliterate(&input_text, language),
+        None => input_text,
+    };
+    let input_text = match args.normalize {
+        Some(form) => normalize::normalize(&input_text, form),
+        None => input_text,
+    };
+
+    if args.warn_control {
+        if let Some(warning) = control_character_warning(&input_text) {
+            eprintln!("{}", warning);
+        }
+    }
+
+    // Loaded once for brute force matching, segmenting and scoring the decryption
+    let dictionary =
+        match args.first_match || args.segment || matches!(direction, Direction::Decrypt) {
+            true => args
+                .dictionary
+                .as_deref()
+                .map(load_dictionary)
+                .transpose()?,
+            false => None,
+        };
+    let segment_words = dictionary.as_ref().filter(|_| args.segment);
+
+    let start = Instant::now();
+
+    if args.brute_force {
+        let candidates = match (&args.wordlist, args.algorithm.as_slice()) {
+            (Some(path), _) => count_wordlist_keys(path),
+            (None, [algorithm]) => {
+                build_cipher(algorithm, algorithm.sample_key().as_ref(), &options)
+                    .ok()
+                    .and_then(|probe| probe.key_space())
+                    .filter(|&size| size <= MAX_BRUTE_FORCE_KEYS)
+            }
+            _ => None,
+        };
+        if let Some(candidates) = candidates {
+            if !confirm_search(&args, &options, &input_text, candidates)? {
+                return Ok(());
+            }
+        }
+        match &args.wordlist {
+            Some(path) => wordlist_attack(
+                &args.algorithm,
+                path,
+                &options,
+                &input_text,
+                segment_words,
+                args.key_only,
+            )?,
+            None => brute_force(
+                &args.algorithm,
+                &options,
+                &input_text,
+                dictionary.as_ref().filter(|_| args.first_match),
+                segment_words,
+                args.key_only,
+            )?,
+        }
+        if args.time {
+            print_elapsed(start);
+        }
+        return Ok(());
     }
 
-    let cipher: Box<dyn Cipher> = match args.algorithm {
-        Algorithm::Caesar => {
-            if let KeyType::Integer(key) = args.key {
-                Box::new(ciphers::caesar::CaesarCipher { key })
-            } else {
-                panic!("Caesar cipher requires an integer key.");
+    if let Some(crib) = &args.crib {
+        crib_attack(
+            &args.algorithm,
+            &direction,
+            crib,
+            args.crib_offset,
+            &input_text,
+        )?;
+        return Ok(());
+    }
+
+    if args.all_algorithms {
+        if args.key.len() > 1 {
+            return Err(Error::Usage(
+                "--all-algorithms takes at most one key.".to_string(),
+            ));
+        }
+        run_all_algorithms(args.key.first(), &options, &direction, &input_text)?;
+        return Ok(());
+    }
+
+    // Each keyed algorithm in a chain needs its own key
+    let keyed_count = args.algorithm.iter().filter(|a| a.requires_key()).count();
+    if keyed_count != args.key.len() {
+        return Err(Error::Usage(format!(
+            "{} keyed algorithm(s) given but {} key(s) supplied.",
+            keyed_count,
+            args.key.len()
+        )));
+    }
+
+    // A Vigenère key with `?` wildcards is solved by trying every letter
+    // in the unknown positions
+    if let ([Algorithm::Vigenère], [KeyType::Text(pattern)]) =
+        (args.algorithm.as_slice(), args.key.as_slice())
+    {
+        if partial_key::has_wildcards(pattern) {
+            if matches!(direction, Direction::Encrypt) {
+                return Err(Error::Usage(
+                    "Wildcards in the key only apply to decryption.".to_string(),
+                ));
+            }
+            // Keys with too many wildcards are rejected by the attack itself
+            let candidates = partial_key::candidate_count(pattern);
+            if candidates <= 26u64.pow(partial_key::MAX_WILDCARDS as u32)
+                && !confirm_search(&args, &options, &input_text, candidates)?
+            {
+                return Ok(());
             }
+            partial_key_attack(pattern, &options, &input_text, segment_words, args.key_only)?;
+            if args.time {
+                print_elapsed(start);
+            }
+            return Ok(());
         }
-        Algorithm::Vigenère => {
-            if let KeyType::Text(key) = args.key {
-                Box::new(ciphers::vigenere::VigenereCipher::new(key))
-            } else {
-                panic!("Vigenère cipher requires a text key.");
+    }
+
+    if args.key_only {
+        return Err(Error::Usage(
+            "--key-only only applies to brute force and wildcard key searches.".to_string(),
+        ));
+    }
+
+    // Passphrases are stretched into fixed-length keys before use
+    let keys: Vec<KeyType> = match args.derive_key {
+        Some(length) => args
+            .key
+            .iter()
+            .map(|key| match key {
+                KeyType::Text(passphrase) => {
+                    KeyType::Text(kdf::derive_key(passphrase, &args.salt, length))
+                }
+                KeyType::Integer(_) => key.clone(),
+            })
+            .collect(),
+        None => args.key.clone(),
+    };
+
+    let mut key_iter = keys.iter();
+    let cipher_keys: Vec<Option<KeyType>> = args
+        .algorithm
+        .iter()
+        .map(|algorithm| {
+            let key = match algorithm.requires_key() {
+                true => key_iter.next(),
+                false => None,
+            };
+            // Caesar also accepts symbolic keys like `today` and percentages
+            // of the alphabet, resolved to a shift, and short Vigenère keys
+            // can be stretched to a longer derived key
+            let resolved_key = match (algorithm, key) {
+                (Algorithm::Caesar, Some(KeyType::Text(name))) => {
+                    symbolic::resolve(name, &symbolic::Date::today())
+                        .or_else(|| percent_shift(name))
+                        .map(KeyType::Integer)
+                }
+                (Algorithm::Vigenère, Some(KeyType::Text(key))) => args
+                    .key_stretch_to
+                    .map(|length| KeyType::Text(kdf::derive_key(key, "", length))),
+                _ => None,
+            };
+            resolved_key.or_else(|| key.cloned())
+        })
+        .collect();
+    let ciphers: Vec<Box<dyn Cipher>> = args
+        .algorithm
+        .iter()
+        .zip(&cipher_keys)
+        .map(|(algorithm, key)| {
+            explain_key(algorithm, key.as_ref(), &options);
+            let cipher = build_cipher_checked(algorithm, key.as_ref(), &options)?;
+            if let Some(KeyType::Text(key)) = key {
+                if let Some(note) = key_length_note(algorithm, key, cipher.as_ref(), &options) {
+                    eprintln!("{}", note);
+                }
             }
+            Ok(cipher)
+        })
+        .collect::<Result<_, CipherError>>()?;
+    let ciphers: Vec<Box<dyn Cipher>> = args
+        .transform
+        .iter()
+        .map(|transform| transform.layer())
+        .chain(ciphers)
+        .chain(
+            args.digit_key
+                .map(|key| Box::new(transform::DigitShift::new(key)) as Box<dyn Cipher>),
+        )
+        .collect();
+
+    if args.fail_on_unencrypted && input_bytes.is_none() {
+        if let Some(message) = unencrypted_characters(&input_text, &ciphers) {
+            return Err(Error::Decode(message));
         }
-        Algorithm::Playfair => {
-            if let KeyType::Text(key) = args.key {
-                Box::new(ciphers::playfair::PlayfairCipher::new(key))
-            } else {
-                panic!("Playfair cipher requires a text key.")
+    }
+
+    if let Some(length) = args.preview {
+        print_preview(
+            &args.algorithm,
+            &ciphers,
+            &input_text,
+            input_bytes.as_deref(),
+            keys.first(),
+            length,
+            &args,
+        )?;
+        return Ok(());
+    }
+
+    if let (Direction::Decrypt, Some(Algorithm::Playfair), Some(Some(KeyType::Text(key)))) =
+        (&direction, args.algorithm.last(), cipher_keys.last())
+    {
+        if let Some(message) = odd_playfair_ciphertext(key, &options, &input_text) {
+            match args.strict {
+                true => return Err(Error::Decode(message)),
+                false => eprintln!("Warning: {}", message),
             }
         }
+    }
+
+    if let (Direction::Decrypt, [Algorithm::Xor | Algorithm::ByteCaesar], None) =
+        (&direction, args.algorithm.as_slice(), &input_bytes)
+    {
+        encoding::decode_hex(&input_text).map_err(|message| {
+            Error::Decode(format!("Could not decode hex ciphertext: {}", message))
+        })?;
+    }
+
+    let regions = match (args.per_word, args.by_line) {
+        (true, _) => Some(region::words()),
+        (_, true) => Some(region::lines()),
+        _ => args.region.clone(),
     };
+    if let (true, Some(bytes)) = (raw_in_place, &input_bytes) {
+        let cipher = build_byte_cipher(&args.algorithm[0], keys.first())?;
+        let output_bytes = match direction {
+            Direction::Encrypt => {
+                let mut encrypted = Vec::with_capacity(bytes.len());
+                cipher
+                    .encrypt_to(bytes, &mut encrypted)
+                    .map_err(|err| Error::io("Could not encrypt input", err))?;
+                encrypted
+            }
+            Direction::Decrypt => cipher.decrypt_bytes(bytes),
+        };
+        output::write_bytes_in_place(&args.input_file[0], &output_bytes)?;
+        return Ok(());
+    }
+
+    if let (Some(bytes), Direction::Encrypt, true) =
+        (&input_bytes, &direction, streams_byte_ciphertext(&args))
+    {
+        let cipher = build_byte_cipher(&args.algorithm[0], keys.first())?;
+        let encrypt = |sink: &mut dyn Write| {
+            encode_byte_stream(
+                |sink| cipher.encrypt_to(bytes, sink),
+                args.byte_format,
+                !args.no_padding,
+                sink,
+            )
+        };
+        match &args.output_file {
+            Some(path) => output::stream_in_place(path, encrypt)?,
+            None => output::stream(|stdout| {
+                write!(
+                    stdout,
+                    "Algorithm: {}\nDirection: {:?}\nOutput: ",
+                    ciphers[0], direction
+                )?;
+                encrypt(stdout)?;
+                stdout.write_all(b"\n\n")
+            })?,
+        }
+        if args.time {
+            print_elapsed(start);
+        }
+        return Ok(());
+    }
 
-    let output_text = match args.direction {
-        Direction::Encrypt => cipher.encrypt(&args.input_text),
-        Direction::Decrypt => cipher.decrypt(&args.input_text),
+    let output_text = match (&input_bytes, &regions, keys.first()) {
+        (Some(bytes), _, key) => byte_output(
+            build_byte_cipher(&args.algorithm[0], key)?.as_ref(),
+            bytes,
+            &direction,
+            args.byte_format,
+            !args.no_padding,
+        )?,
+        (_, Some(pattern), _) => region::apply_to_regions(&input_text, pattern, |text| {
+            apply_chain(&ciphers, &direction, text)
+        }),
+        _ => apply_chain(&ciphers, &direction, &input_text),
+    };
+    if let Direction::Encrypt = direction {
+        if let Some(warning) = identity_warning(&input_text, &output_text) {
+            eprintln!("{}", warning);
+        }
+    }
+    let output_text = segmented(output_text, segment_words);
+    if (args.verify || args.strict_roundtrip) && input_bytes.is_none() {
+        let decrypted = match &regions {
+            Some(pattern) => region::apply_to_regions(&output_text, pattern, |text| {
+                apply_chain(&ciphers, &Direction::Decrypt, text)
+            }),
+            None => apply_chain(&ciphers, &Direction::Decrypt, &output_text),
+        };
+        if let Some(warning) = round_trip_warning(&input_text, &decrypted) {
+            eprintln!("{}", warning);
+            if let Some(note) = length_change_note(&ciphers) {
+                eprintln!("{}", note);
+            }
+            if args.strict_roundtrip {
+                return Err(Error::Mismatch(
+                    "--strict-roundtrip: refusing to output ciphertext \
+                     that doesn't decrypt back to the input."
+                        .to_string(),
+                ));
+            }
+        }
+    }
+    if let (Some(words), Direction::Decrypt) = (&dictionary, &direction) {
+        eprintln!("{}", dictionary::confidence(&output_text, words));
+    }
+    let output_text = apply_output_case(&output_text, &args.output_case);
+    let output_text = match args.group {
+        Some(size) => layout::group(&output_text, size),
+        None => output_text,
     };
+    let output_text = match (args.phonetic, &direction) {
+        (true, Direction::Encrypt) => phonetic::encode(&output_text),
+        _ => output_text,
+    };
+    if args.time {
+        print_elapsed(start);
+    }
+    if args.print_decrypt_cmd {
+        eprintln!(
+            "Decrypt with: {}",
+            decrypt_command(&args, &cipher_keys, &output_text)
+        );
+        if let ([algorithm], [key]) = (&args.algorithm[..], &cipher_keys[..]) {
+            if algorithm.is_self_inverse(key.as_ref(), &options) {
+                eprintln!(
+                    "Note: {} is its own inverse, so encrypting again also decrypts.",
+                    ciphers[0]
+                );
+            }
+        }
+    }
+
+    if let Some(path) = &args.expect_file {
+        return check_expected_output(path, &output_text);
+    }
+
+    if let Some(path) = &args.output_file {
+        output::write_in_place(path, &output_text, args.output_encoding, args.bom)?;
+        return Ok(());
+    }
+
+    if args.in_place {
+        let path = &args.input_file[0];
+        output::write_in_place(path, &output_text, args.output_encoding, args.bom)?;
+        return Ok(());
+    }
 
-    println!("Algorithm: {:?}", args.algorithm);
-    println!("Direction: {:?}", args.direction);
-    println!("Output: {}\n", output_text);
+    let algorithm_names: Vec<String> = ciphers.iter().map(|cipher| cipher.to_string()).collect();
+    let mut report = format!(
+        "Algorithm: {}\nDirection: {:?}\n",
+        algorithm_names.join(" -> "),
+        direction
+    );
+    if args.show_schedule {
+        for line in column_schedules(&args.algorithm, &keys, &options) {
+            report.push_str(&line);
+            report.push('\n');
+        }
+    }
+    if args.show_grid {
+        for line in transposition_grid(&args.algorithm, &keys, &options, &direction, &input_text) {
+            report.push_str(&line);
+            report.push('\n');
+        }
+    }
+    if log::enabled(log::INFO) {
+        for line in playfair_breakdown(&args.algorithm, &keys, &options, &direction, &input_text) {
+            report.push_str(&line);
+            report.push('\n');
+        }
+    }
+    match (args.format, args.wrap) {
+        (qr::OutputFormat::Qr, _) => match qr::render(&output_text) {
+            Ok(code) => report.push_str(&format!("Output:\n{}\n\n", code)),
+            Err(err) => {
+                return Err(Error::Decode(format!(
+                    "Could not render the output as a QR code: {}",
+                    err
+                )))
+            }
+        },
+        (qr::OutputFormat::Text, Some(cols)) => report.push_str(&format!(
+            "Output:\n{}\n\n",
+            layout::wrap(&output_text, cols)
+        )),
+        (qr::OutputFormat::Text, None) => report.push_str(&format!("Output: {}\n\n", output_text)),
+    }
+
+    output::write(&report, args.output_encoding, args.bom)?;
+    Ok(())
+}
+
+/// Compares the output with the contents of the expected file, ignoring
+/// one trailing newline in the file, and fails with a summary of the
+/// first difference if they don't match.
+fn check_expected_output(path: &Path, output_text: &str) -> Result<(), Error> {
+    let expected = fs::read_to_string(path)
+        .map_err(|err| Error::io(format!("Could not read {}", path.display()), err))?;
+    let expected = expected
+        .strip_suffix('\n')
+        .map(|text| text.strip_suffix('\r').unwrap_or(text))
+        .unwrap_or(&expected);
+
+    match compare::mismatch_summary(expected, output_text) {
+        Some(summary) => Err(Error::Mismatch(format!(
+            "Output does not match {}: {}",
+            path.display(),
+            summary
+        ))),
+        None => Ok(()),
+    }
+}
+
+/// Logs how the key will be used, warning about keys that are
+/// altered or have no effect.
+fn explain_key(algorithm: &Algorithm, key: Option<&KeyType>, options: &CipherOptions) {
+    match (algorithm, key) {
+        (Algorithm::Caesar, Some(KeyType::Integer(shift))) => {
+            let modulus = options.alphabet.as_ref().map_or(26, |a| a.len() as i32);
+            if shift.rem_euclid(modulus) == 0 && !options.reverse_alphabet {
+                log::warn(&format!(
+                    "Caesar shift of {} leaves letters unchanged",
+                    shift
+                ));
+            }
+            log::info(&format!("Caesar: shifting letters by {}", shift));
+        }
+        (Algorithm::Vigenère, Some(KeyType::Text(key))) => {
+            if !key.chars().all(|c| c.is_ascii_alphabetic()) {
+                log::warn(&format!(
+                    "stripped non-letter characters from the Vigenère key {:?}",
+                    key
+                ));
+            }
+            log::info(&format!(
+                "Vigenère: shifting letters by the repeating key {}",
+                color::paint(
+                    &format!("{:?}", VigenereCipher::new(key.clone()).key),
+                    Style::Bold,
+                    Stream::Stderr
+                )
+            ));
+        }
+        (_, Some(KeyType::Integer(key))) => {
+            log::info(&format!("{}: key {}", algorithm.display_name(), key))
+        }
+        (_, Some(KeyType::Text(key))) => {
+            log::info(&format!("{}: key {:?}", algorithm.display_name(), key))
+        }
+        (_, None) => log::info(&format!("{}: keyless", algorithm.display_name())),
+    }
+}
+
+/// Describes how a key is parsed and cleaned before the cipher uses it.
+///
+/// # Arguments
+/// * `algorithm` - The algorithm the key belongs to.
+/// * `key` - The key as given on the command line.
+/// * `options` - Options that change how the key is used, such as the square size.
+///
+/// # Returns
+/// The lines of the explanation, ending with the square for Playfair.
+fn describe_key(
+    algorithm: &Algorithm,
+    key: Option<&KeyType>,
+    options: &CipherOptions,
+) -> Vec<String> {
+    let key = match key {
+        Some(key) => key,
+        None => return vec![format!("{}: no key", algorithm.display_name())],
+    };
+    let mut lines = vec![match key {
+        KeyType::Integer(value) => format!(
+            "{} key: parsed as the integer {}",
+            algorithm.display_name(),
+            value
+        ),
+        KeyType::Text(text) => format!(
+            "{} key: parsed as the text {:?}",
+            algorithm.display_name(),
+            text
+        ),
+    }];
+
+    match (algorithm, key) {
+        (Algorithm::Caesar, KeyType::Text(name)) => {
+            match symbolic::resolve(name, &symbolic::Date::today()).or_else(|| percent_shift(name))
+            {
+                Some(shift) => lines.push(format!("  resolved to a shift of {}", shift)),
+                None => lines.push("  not a shift: Caesar needs an integer key".to_string()),
+            }
+        }
+        (Algorithm::Vigenère, KeyType::Text(text)) => {
+            lines.push(format!(
+                "  lowercased, non-letters stripped: {:?}",
+                VigenereCipher::new(text.clone()).key
+            ));
+        }
+        (Algorithm::Playfair, _) => {
+            let text = match key {
+                KeyType::Integer(value) => value.to_string(),
+                KeyType::Text(text) => text.clone(),
+            };
+            let cipher =
+                PlayfairCipher::with_merge(text, options.square_size, options.fill, options.merge);
+            lines.push(match options.square_size {
+                5 => format!(
+                    "  uppercased, {} read as {}, non-letters and repeats stripped: {:?}",
+                    options.merge.from, options.merge.to, cipher.key
+                ),
+                _ => format!(
+                    "  uppercased, non-alphanumerics and repeats stripped: {:?}",
+                    cipher.key
+                ),
+            });
+            lines.push("  square:".to_string());
+            for row in cipher.square() {
+                let row: Vec<String> = row.iter().map(|c| c.to_string()).collect();
+                lines.push(format!("    {}", row.join(" ")));
+            }
+        }
+        _ => lines.push("  used as given".to_string()),
+    }
+    lines
+}
+
+/// Checks the key's length against the lengths the cipher makes full use of.
+///
+/// Vigenère keys are measured in letters and Playfair keys in distinct
+/// characters of the square, counting `J` on its own; other keys in characters.
+///
+/// # Returns
+/// A note when the key is too short to be more than a simpler cipher or
+/// has more distinct characters than the cipher can use, otherwise `None`.
+fn key_length_note(
+    algorithm: &Algorithm,
+    key: &str,
+    cipher: &dyn Cipher,
+    options: &CipherOptions,
+) -> Option<String> {
+    let length = match algorithm {
+        Algorithm::Vigenère => key.chars().filter(|c| c.is_ascii_alphabetic()).count(),
+        Algorithm::Playfair => key
+            .chars()
+            .filter(|c| match options.square_size {
+                5 => c.is_ascii_alphabetic(),
+                _ => c.is_ascii_alphanumeric(),
+            })
+            .map(|c| c.to_ascii_uppercase())
+            .collect::<HashSet<char>>()
+            .len(),
+        _ => key.chars().count(),
+    };
+
+    match cipher.key_length_bounds() {
+        (min, _) if length < min => Some(format!(
+            "Note: the {} key {:?} has {} usable character(s); with fewer than {} \
+             the cipher degenerates into a simpler one",
+            cipher, key, length, min
+        )),
+        (_, Some(max)) if length > max => Some(format!(
+            "Note: the {} key {:?} has {} distinct characters, more than the {} \
+             the cipher can use",
+            cipher, key, length, max
+        )),
+        _ => None,
+    }
+}
+
+/// Logs each character's substitution when running with `-vvv`.
+fn trace_layer(input: &str, output: &str) {
+    if !log::enabled(log::TRACE) {
+        return;
+    }
+
+    log::trace(&format!("{:?} -> {:?}", input, output));
+    if input.chars().count() == output.chars().count() {
+        for (before, after) in input.chars().zip(output.chars()) {
+            log::trace(&format!("  {:?} -> {:?}", before, after));
+        }
+    }
+}
+
+/// Describes the column read order of every columnar transposition in the chain.
+///
+/// Orders are 1-based; Myszkowski columns sharing a letter share a number.
+fn column_schedules(
+    algorithms: &[Algorithm],
+    keys: &[KeyType],
+    options: &CipherOptions,
+) -> Vec<String> {
+    let mut key_iter = keys.iter();
+    algorithms
+        .iter()
+        .filter_map(|algorithm| {
+            let key = match algorithm.requires_key() {
+                true => key_iter.next(),
+                false => None,
+            };
+            match (algorithm, key) {
+                (Algorithm::Columnar, Some(KeyType::Text(key))) => {
+                    let cipher = ColumnarCipher::new(key.clone(), options.variant);
+                    let order: Vec<String> = cipher
+                        .column_ranks()
+                        .iter()
+                        .map(|rank| (rank + 1).to_string())
+                        .collect();
+                    Some(format!(
+                        "Column order for {}: {}",
+                        cipher.key,
+                        order.join(" ")
+                    ))
+                }
+                _ => None,
+            }
+        })
+        .collect()
+}
+
+/// Lays out the grid a lone columnar or rail fence cipher writes the
+/// plaintext into, decrypting the input first when decrypting.
+fn transposition_grid(
+    algorithms: &[Algorithm],
+    keys: &[KeyType],
+    options: &CipherOptions,
+    direction: &Direction,
+    input_text: &str,
+) -> Vec<String> {
+    let plaintext = |cipher: &dyn Cipher| match direction {
+        Direction::Encrypt => input_text.to_string(),
+        Direction::Decrypt => cipher.decrypt(input_text),
+    };
+    let (title, grid) = match (algorithms, keys) {
+        ([Algorithm::Columnar], [KeyType::Text(key)]) => {
+            let cipher = ColumnarCipher::new(key.clone(), options.variant);
+            ("Grid:", cipher.grid(&plaintext(&cipher)))
+        }
+        ([Algorithm::RailFence], [KeyType::Integer(rails)]) if *rails >= 1 => {
+            let cipher = RailFenceCipher {
+                rails: *rails as usize,
+                offset: options.offset,
+            };
+            ("Rails:", cipher.grid(&plaintext(&cipher)))
+        }
+        _ => return Vec::new(),
+    };
+
+    std::iter::once(title.to_string())
+        .chain(grid.iter().map(|line| format!("  {}", line)))
+        .collect()
+}
+
+/// Describes each digraph, the rule applied and its result when running Playfair alone.
+fn playfair_breakdown(
+    algorithms: &[Algorithm],
+    keys: &[KeyType],
+    options: &CipherOptions,
+    direction: &Direction,
+    input_text: &str,
+) -> Vec<String> {
+    let key = match (algorithms, keys) {
+        ([Algorithm::Playfair], [KeyType::Text(key)]) => key,
+        _ => return Vec::new(),
+    };
+
+    let cipher = PlayfairCipher::with_merge(
+        key.clone(),
+        options.square_size,
+        options.fill,
+        options.merge,
+    );
+    let steps: Vec<DigraphStep> = match direction {
+        Direction::Encrypt => cipher.explain_encrypt(input_text),
+        Direction::Decrypt => cipher.explain_decrypt(input_text),
+    };
+    steps
+        .iter()
+        .map(|step| {
+            format!(
+                "{}{} -> {}{} ({})",
+                step.input.0, step.input.1, step.output.0, step.output.1, step.rule
+            )
+        })
+        .collect()
+}
+
+/// Share of dictionary words at which `--first-match` accepts a brute force key.
+const FIRST_MATCH_THRESHOLD: f64 = 0.6;
+
+/// Reads the dictionary file, failing with an I/O error if it can't be read.
+fn load_dictionary(path: &Path) -> Result<HashSet<String>, Error> {
+    File::open(path)
+        .and_then(|file| dictionary::load(BufReader::new(file)))
+        .map_err(|err| Error::io(format!("Could not read {}", path.display()), err))
+}
+
+/// Reinserts spaces between the dictionary words of a decryption when
+/// `--segment` is given, and returns it unchanged otherwise.
+fn segmented(plaintext: String, segment: Option<&HashSet<String>>) -> String {
+    match segment {
+        Some(words) => dictionary::segment(&plaintext, words),
+        None => plaintext,
+    }
+}
+
+/// Decrypts the input with every key of the algorithm and prints each candidate.
+///
+/// With a `segment` dictionary, spaces are reinserted into each candidate
+/// before it is scored or printed. With a `first_match` dictionary, stops at and prints only the first key
+/// whose decryption reaches `FIRST_MATCH_THRESHOLD`, falling back to every
+/// candidate if none does.
+///
+/// Fails if more than one algorithm is given, or if the cipher's key
+/// space is unbounded or larger than `MAX_BRUTE_FORCE_KEYS`.
+fn brute_force(
+    algorithms: &[Algorithm],
+    options: &CipherOptions,
+    input_text: &str,
+    first_match: Option<&HashSet<String>>,
+    segment: Option<&HashSet<String>>,
+    key_only: bool,
+) -> Result<(), Error> {
+    let algorithm = match algorithms {
+        [algorithm] => algorithm,
+        _ => {
+            return Err(Error::Usage(
+                "Brute force mode only supports a single algorithm.".to_string(),
+            ));
+        }
+    };
+
+    let probe = build_cipher_checked(algorithm, algorithm.sample_key().as_ref(), options)?;
+    let key_space = match probe.key_space() {
+        Some(size) if size <= MAX_BRUTE_FORCE_KEYS => size,
+        _ => {
+            return Err(Error::Usage(format!(
+                "Brute force is not supported for the {}: its key space is too large to enumerate.",
+                probe
+            )));
+        }
+    };
+
+    let (candidates, matched) = brute_force_candidates(
+        algorithm,
+        key_space,
+        options,
+        input_text,
+        first_match,
+        segment,
+    )?;
+    if first_match.is_some() && !matched {
+        eprintln!("No key reached the dictionary threshold; listing every candidate.");
+    }
+    if key_only {
+        return print_best_key(&candidates);
+    }
+
+    outln!("Algorithm: {}", probe)?;
+    outln!("Direction: {:?}", Direction::Decrypt)?;
+    for candidate in candidates {
+        outln!("Key {}: {}", candidate.key, candidate.plaintext)?;
+    }
+    Ok(())
+}
+
+/// Decrypts with every key from 1 to `key_space`, segmenting each
+/// plaintext into words with a `segment` dictionary.
+///
+/// # Returns
+/// Every candidate in key order, or with a `first_match` dictionary only
+/// the first one whose words reach the threshold, along with whether one
+/// did, or the error building a cipher for one of the keys.
+fn brute_force_candidates(
+    algorithm: &Algorithm,
+    key_space: u64,
+    options: &CipherOptions,
+    input_text: &str,
+    first_match: Option<&HashSet<String>>,
+    segment: Option<&HashSet<String>>,
+) -> Result<(Vec<wordlist::Candidate>, bool), CipherError> {
+    let mut candidates = Vec::with_capacity(key_space as usize);
+    for key in 1..=key_space {
+        let cipher = build_cipher_checked(algorithm, Some(&KeyType::Integer(key as i32)), options)?;
+        let plaintext = segmented(cipher.decrypt(input_text), segment);
+        let candidate = wordlist::Candidate {
+            key: key.to_string(),
+            score: analysis::english_score(&plaintext),
+            plaintext,
+        };
+        match first_match {
+            Some(words)
+                if dictionary::word_fraction(&candidate.plaintext, words)
+                    >= FIRST_MATCH_THRESHOLD =>
+            {
+                return Ok((vec![candidate], true));
+            }
+            _ => candidates.push(candidate),
+        }
+    }
+    Ok((candidates, false))
+}
+
+/// Runs the input through every algorithm whose key type matches the key,
+/// printing each result labeled by algorithm and noting the skipped ones.
+fn run_all_algorithms(
+    key: Option<&KeyType>,
+    options: &CipherOptions,
+    direction: &Direction,
+    input_text: &str,
+) -> Result<(), Error> {
+    outln!("Direction: {:?}", direction)?;
+    for algorithm in Algorithm::value_variants() {
+        if !algorithm.accepts_key(key) {
+            outln!("{}: skipped (incompatible key)", algorithm.display_name())?;
+            continue;
+        }
+
+        let cipher = match build_cipher(algorithm, key, options) {
+            Ok(cipher) => cipher,
+            Err(message) => {
+                outln!("{}: skipped ({})", algorithm.display_name(), message)?;
+                continue;
+            }
+        };
+        let output = match direction {
+            Direction::Encrypt => cipher.encrypt(input_text),
+            Direction::Decrypt => cipher.decrypt(input_text),
+        };
+        outln!("{}: {}", cipher, output)?;
+    }
+    Ok(())
+}
+
+/// Number of crib positions printed when sliding the crib.
+const CRIB_TOP_CANDIDATES: usize = 5;
+
+/// Recovers and prints the Vigenère key fragment under a known plaintext crib.
+///
+/// With an offset, prints the fragment at that position. Without one, slides
+/// the crib across the ciphertext and prints the most word-like fragments,
+/// since keys are often words.
+fn crib_attack(
+    algorithms: &[Algorithm],
+    direction: &Direction,
+    crib: &str,
+    offset: Option<usize>,
+    input_text: &str,
+) -> Result<(), Error> {
+    if !matches!(algorithms, [Algorithm::Vigenère]) || matches!(direction, Direction::Encrypt) {
+        return Err(Error::Usage(
+            "--crib only supports decrypting a single Vigenère algorithm.".to_string(),
+        ));
+    }
+
+    let mut fragments: Vec<(usize, String)> = match offset {
+        Some(offset) => vigenere::recover_key_fragment(input_text, crib, offset)
+            .map(|fragment| vec![(offset, fragment)])
+            .unwrap_or_default(),
+        None => (0..input_text.chars().count())
+            .filter_map(|offset| {
+                vigenere::recover_key_fragment(input_text, crib, offset)
+                    .map(|fragment| (offset, fragment))
+            })
+            .collect(),
+    };
+    if fragments.is_empty() {
+        return Err(Error::Usage(
+            "The crib doesn't fit in the ciphertext at that position.".to_string(),
+        ));
+    }
+    // Fully recovered fragments first, then the most English-like
+    let unknowns = |fragment: &str| fragment.matches('?').count();
+    fragments.sort_by(|a, b| {
+        unknowns(&a.1)
+            .cmp(&unknowns(&b.1))
+            .then(
+                analysis::english_score(&b.1)
+                    .partial_cmp(&analysis::english_score(&a.1))
+                    .unwrap(),
+            )
+            .then(a.0.cmp(&b.0))
+    });
+
+    outln!("Algorithm: {}", Algorithm::Vigenère.display_name())?;
+    outln!("Direction: {:?}", Direction::Decrypt)?;
+    for (offset, fragment) in fragments.iter().take(CRIB_TOP_CANDIDATES) {
+        outln!("Crib at {}: key fragment {}", offset, fragment)?;
+    }
+    Ok(())
+}
+
+/// Most keys a search may try before it needs `--yes` to go ahead.
+const CONFIRM_SEARCH_KEYS: u64 = 10_000;
+
+/// Counts the keys the wordlist attack would try: its non-blank lines.
+///
+/// # Returns
+/// The count, or `None` if the file can't be read; the attack itself
+/// reports that error.
+fn count_wordlist_keys(path: &Path) -> Option<u64> {
+    let file = File::open(path).ok()?;
+    let mut count = 0;
+    for line in BufReader::new(file).lines() {
+        if !line.ok()?.trim().is_empty() {
+            count += 1;
+        }
+    }
+    Some(count)
+}
+
+/// Estimates a search's work from the time one decryption with the
+/// algorithm's sample key takes, and decides whether to run it.
+///
+/// With `--benchmark-keyspace`, prints the estimate and returns false.
+/// Otherwise fails with a usage error when the search would try more than
+/// `CONFIRM_SEARCH_KEYS` keys and `--yes` wasn't given.
+///
+/// # Returns
+/// Whether the search should go ahead.
+fn confirm_search(
+    args: &Args,
+    options: &CipherOptions,
+    input_text: &str,
+    candidates: u64,
+) -> Result<bool, Error> {
+    let per_key = match args.algorithm.as_slice() {
+        [algorithm] => build_cipher(algorithm, algorithm.sample_key().as_ref(), options)
+            .map(|cipher| {
+                let start = Instant::now();
+                cipher.decrypt(input_text);
+                start.elapsed().as_secs_f64()
+            })
+            .unwrap_or(0.0),
+        _ => 0.0,
+    };
+    let estimate = format!(
+        "Estimated work: {} candidate keys, about {}",
+        candidates,
+        format_seconds(per_key * candidates as f64)
+    );
+
+    if args.benchmark_keyspace {
+        outln!("{}", estimate)?;
+        return Ok(false);
+    }
+    if candidates > CONFIRM_SEARCH_KEYS && !args.yes {
+        eprintln!("{}", estimate);
+        return Err(Error::Usage(format!(
+            "The search would try more than {} keys; pass --yes to run it anyway.",
+            CONFIRM_SEARCH_KEYS
+        )));
+    }
+    Ok(true)
+}
+
+/// Formats a duration estimate in the largest whole unit that fits.
+fn format_seconds(seconds: f64) -> String {
+    match seconds {
+        s if s < 1.0 => "under a second".to_string(),
+        s if s < 60.0 => format!("{:.0} s", s),
+        s if s < 3600.0 => format!("{:.0} min", s / 60.0),
+        s => format!("{:.1} h", s / 3600.0),
+    }
+}
+
+/// Number of candidates printed by the wordlist attack.
+const WORDLIST_TOP_CANDIDATES: usize = 5;
+
+/// Tries every word of the wordlist as the key and prints the most English-like decryptions.
+///
+/// Only Vigenère and Playfair are supported; fails otherwise or if the
+/// wordlist can't be read.
+fn wordlist_attack(
+    algorithms: &[Algorithm],
+    path: &Path,
+    options: &CipherOptions,
+    input_text: &str,
+    segment: Option<&HashSet<String>>,
+    key_only: bool,
+) -> Result<(), Error> {
+    let algorithm = match algorithms {
+        [algorithm @ (Algorithm::Vigenère | Algorithm::Playfair)] => algorithm,
+        _ => {
+            return Err(Error::Usage(
+                "Wordlist brute force only supports a single Vigenère or Playfair algorithm."
+                    .to_string(),
+            ));
+        }
+    };
+
+    interrupt::install();
+    let candidates = File::open(path).and_then(|file| {
+        wordlist::rank_keys(
+            BufReader::new(file),
+            input_text,
+            |key| build_cipher(algorithm, Some(&KeyType::Text(key.to_string())), options).ok(),
+            WORDLIST_TOP_CANDIDATES,
+            interrupt::flag(),
+        )
+    });
+    let candidates =
+        candidates.map_err(|err| Error::io(format!("Could not read {}", path.display()), err))?;
+    if interrupt::requested() {
+        eprintln!("Interrupted; showing the best candidates found so far.");
+    }
+
+    print_candidates(algorithm, &candidates, segment, key_only)
+}
+
+/// Decrypts with every key matching a Vigenère key whose unknown letters
+/// are marked with `?` and prints the best scoring candidates.
+fn partial_key_attack(
+    pattern: &str,
+    options: &CipherOptions,
+    input_text: &str,
+    segment: Option<&HashSet<String>>,
+    key_only: bool,
+) -> Result<(), Error> {
+    let keys = partial_key::expand(pattern).map_err(CipherError)?;
+
+    interrupt::install();
+    let candidates = wordlist::rank_candidates(
+        keys.into_iter().map(Ok),
+        input_text,
+        |key| {
+            build_cipher(
+                &Algorithm::Vigenère,
+                Some(&KeyType::Text(key.to_string())),
+                options,
+            )
+            .ok()
+        },
+        WORDLIST_TOP_CANDIDATES,
+        interrupt::flag(),
+    )
+    .unwrap_or_default();
+    if interrupt::requested() {
+        eprintln!("Interrupted; showing the best candidates found so far.");
+    }
+
+    print_candidates(&Algorithm::Vigenère, &candidates, segment, key_only)
+}
+
+/// Prints ranked key candidates, best first, segmenting each plaintext
+/// into words with a `segment` dictionary, or with `key_only` just the best key.
+fn print_candidates(
+    algorithm: &Algorithm,
+    candidates: &[wordlist::Candidate],
+    segment: Option<&HashSet<String>>,
+    key_only: bool,
+) -> Result<(), Error> {
+    if key_only {
+        return print_best_key(candidates);
+    }
+    outln!("Algorithm: {}", algorithm.display_name())?;
+    outln!("Direction: {:?}", Direction::Decrypt)?;
+    for (rank, candidate) in candidates.iter().enumerate() {
+        outln!(
+            "{}. Key {} (score {:.3}): {}",
+            rank + 1,
+            candidate.key,
+            candidate.score,
+            segmented(candidate.plaintext.clone(), segment)
+        )?;
+    }
+    Ok(())
+}
+
+/// Prints only the key of the best scoring candidate, the earlier one on
+/// ties, and nothing else; fails if there is no candidate.
+fn print_best_key(candidates: &[wordlist::Candidate]) -> Result<(), Error> {
+    let best =
+        candidates.iter().fold(
+            None,
+            |best: Option<&wordlist::Candidate>, candidate| match best {
+                Some(best) if best.score >= candidate.score => Some(best),
+                _ => Some(candidate),
+            },
+        );
+    match best {
+        Some(candidate) => {
+            outln!("{}", candidate.key)?;
+            Ok(())
+        }
+        None => Err(CipherError("No key was recovered.".to_string()).into()),
+    }
+}
+
+/// Plaintext round-tripped by `selftest`; even-length capitals with no
+/// repeated pairs or `J`, so the lossy ciphers give it back unchanged.
+const SELFTEST_SAMPLE: &str = "ATTACKATDAWN";
+
+/// Index letter given to the Alberti disk during `selftest`.
+const SELFTEST_INDEX: &str = "k";
+
+/// Encrypts and decrypts the sample with every algorithm's sample key,
+/// printing a pass or fail line with the time taken for each.
+///
+/// # Returns
+/// A mismatch error unless every cipher gave the sample back unchanged.
+fn run_selftest() -> Result<(), Error> {
+    let mut passed = 0;
+    let algorithms = Algorithm::value_variants();
+    for algorithm in algorithms {
+        let options = CipherOptions {
+            key2: algorithm.sample_key2().map(String::from),
+            ..CipherOptions::default()
+        };
+        let start = Instant::now();
+        let result =
+            build_cipher(algorithm, algorithm.sample_key().as_ref(), &options).map(|cipher| {
+                (
+                    cipher.name(),
+                    cipher.decrypt(&cipher.encrypt(SELFTEST_SAMPLE)),
+                )
+            });
+        let elapsed = start.elapsed().as_secs_f64() * 1000.0;
+
+        match result {
+            Ok((name, plaintext)) if plaintext == SELFTEST_SAMPLE => {
+                passed += 1;
+                outln!("{}: pass ({:.3} ms)", name, elapsed)?;
+            }
+            Ok((name, plaintext)) => {
+                outln!("{}: fail, got {:?} ({:.3} ms)", name, plaintext, elapsed)?
+            }
+            Err(err) => outln!(
+                "{}: fail, {} ({:.3} ms)",
+                algorithm.display_name(),
+                err,
+                elapsed
+            )?,
+        }
+    }
+
+    outln!("{}/{} ciphers passed", passed, algorithms.len())?;
+    match passed == algorithms.len() {
+        true => Ok(()),
+        false => Err(Error::Mismatch(format!(
+            "{} cipher(s) failed the self test.",
+            algorithms.len() - passed
+        ))),
+    }
+}
+
+/// Prints the wall-clock time since `start` to stderr, leaving stdout untouched.
+fn print_elapsed(start: Instant) {
+    eprintln!("Elapsed: {:.3} ms", start.elapsed().as_secs_f64() * 1000.0);
+}
+
+/// Fills in every setting the command line left unset from the job file,
+/// then checks that the required ones are now present.
+///
+/// Fails with an I/O error if the file can't be read and a parse or usage
+/// error if it isn't a valid job.
+fn apply_job(args: &mut Args, path: &Path) -> Result<(), Error> {
+    let text = fs::read_to_string(path)
+        .map_err(|err| Error::io(format!("Could not read {}", path.display()), err))?;
+    let invalid = |err: String| Error::Parse {
+        context: format!("Invalid job file {}", path.display()),
+        source: err.into(),
+    };
+    let job = job::parse(&text)
+        .map_err(invalid)?
+        .relative_to(path.parent().unwrap_or_else(|| Path::new("")));
+
+    if let (Some(names), true) = (&job.algorithm, args.algorithm.is_empty()) {
+        if !args.all_algorithms && args.unicode_rot.is_none() {
+            args.algorithm = names
+                .split(',')
+                .map(|name| Algorithm::from_str(name.trim(), true))
+                .collect::<Result<_, _>>()
+                .map_err(invalid)?;
+        }
+    }
+    if let (Some(direction), None) = (&job.direction, &args.direction) {
+        args.direction = Some(Direction::from_str(direction, true).map_err(invalid)?);
+    }
+    if args.key.is_empty() && args.key_from_env.is_none() && args.unicode_rot.is_none() {
+        args.key = job
+            .key
+            .to_strings()
+            .iter()
+            .map(|key| KeyType::from_str(key).unwrap())
+            .collect();
+    }
+    if args.key2.is_none() {
+        args.key2 = job.key2;
+    }
+    if args.input_file.is_empty() && args.input_text.is_none() && !args.from_clipboard {
+        args.input_file = job.input_file.into_iter().collect();
+    }
+    if args.output_file.is_none() && !args.in_place && args.expect_file.is_none() {
+        args.output_file = job.output_file;
+    }
+    if let (Some(form), None) = (&job.normalize, &args.normalize) {
+        args.normalize = Some(Normalization::from_str(form, true).map_err(invalid)?);
+    }
+    if let (Some(language), None) = (&job.translit, &args.translit) {
+        args.translit = Some(translit::Language::from_str(language, true).map_err(invalid)?);
+    }
+
+    if args.direction.is_none() {
+        return Err(Error::Usage(
+            "No direction given by --direction or the job file.".to_string(),
+        ));
+    }
+    if args.algorithm.is_empty() && !args.all_algorithms {
+        return Err(Error::Usage(
+            "No algorithm given by --algorithm or the job file.".to_string(),
+        ));
+    }
+    if args.input_text.is_none() && args.input_file.is_empty() && !args.from_clipboard {
+        return Err(Error::Usage(
+            "No input given on the command line or by the job file.".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Sets the direction chosen by the encrypt or decrypt subcommand,
+/// rejecting a `--direction` given as well.
+fn with_direction(mut args: Args, direction: Direction) -> Result<Args, Error> {
+    if args.direction.is_some() {
+        return Err(Error::Usage(format!(
+            "The {} subcommand sets the direction; leave out --direction.",
+            direction.to_possible_value().unwrap().get_name()
+        )));
+    }
+    args.direction = Some(direction);
+    Ok(args)
+}
+
+/// Builds the shell command printed by `examples` for the algorithm.
+fn example_command(algorithm: &Algorithm) -> String {
+    std::iter::once(env!("CARGO_PKG_NAME").to_string())
+        .chain(algorithm.example_args().iter().map(|arg| shell_quote(arg)))
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+/// Quotes the word for a POSIX shell, leaving plain words bare.
+///
+/// # Examples
+/// ```
+/// assert_eq!(shell_quote("lemon"), "lemon");
+/// assert_eq!(shell_quote("it's"), "'it'\\''s'");
+/// ```
+fn shell_quote(word: &str) -> String {
+    let plain = |c: char| c.is_ascii_alphanumeric() || "_-.,/:=@%+".contains(c);
+    match !word.is_empty() && word.chars().all(plain) {
+        true => word.to_string(),
+        false => format!("'{}'", word.replace('\'', "'\\''")),
+    }
+}
+
+/// Reconstructs the command line that decrypts the output just produced:
+/// the same algorithms, resolved keys and cipher settings, with the output
+/// as the input, or the file it was written to.
+///
+/// A key read with `--key-from-env` is referred to by its variable rather
+/// than printed.
+fn decrypt_command(args: &Args, cipher_keys: &[Option<KeyType>], output_text: &str) -> String {
+    let mut words: Vec<String> = vec![env!("CARGO_PKG_NAME").to_string()];
+    let mut push = |parts: &[&str]| words.extend(parts.iter().map(|part| part.to_string()));
+
+    let names: Vec<&str> = args.algorithm.iter().map(Algorithm::cli_name).collect();
+    push(&["-a", &names.join(","), "-d", "decrypt"]);
+
+    match &args.key_from_env {
+        Some(name) => {
+            push(&["--key-from-env", name]);
+            if let Some(length) = args.derive_key {
+                push(&["--derive-key", &length.to_string(), "--salt", &args.salt]);
+            }
+            if let Some(length) = args.key_stretch_to {
+                push(&["--key-stretch-to", &length.to_string()]);
+            }
+        }
+        None => {
+            for key in cipher_keys.iter().flatten() {
+                match key {
+                    KeyType::Integer(number) => push(&["-k", &number.to_string()]),
+                    KeyType::Text(text) => push(&["-k", text]),
+                }
+            }
+        }
+    }
+    if let Some(key2) = &args.key2 {
+        push(&["--key2", key2]);
+    }
+    if let Variant::Myszkowski = args.variant {
+        push(&["--variant", "myszkowski"]);
+    }
+    if args.offset != 0 {
+        push(&["--offset", &args.offset.to_string()]);
+    }
+    if let Some(alphabet) = &args.alphabet {
+        push(&["--alphabet", &alphabet.to_string()]);
+    }
+    if args.reverse_alphabet {
+        push(&["--reverse-alphabet"]);
+    }
+    if args.square_size != 5 {
+        push(&["--square-size", &args.square_size.to_string()]);
+    }
+    match args.fill_order {
+        FillOrder::Rows => {}
+        FillOrder::Columns => push(&["--fill-order", "columns"]),
+        FillOrder::Reverse => push(&["--fill-order", "reverse"]),
+    }
+    if args.merge != LetterMerge::default() {
+        push(&["--merge", &args.merge.to_string()]);
+    }
+    if let Some(period) = args.period {
+        push(&["--period", &period.to_string()]);
+    }
+    if args.keep_punctuation {
+        push(&["--keep-punctuation"]);
+    }
+    for transform in &args.transform {
+        push(&[
+            "--transform",
+            transform.to_possible_value().unwrap().get_name(),
+        ]);
+    }
+    if let Some(key) = args.digit_key {
+        push(&["--digit-key", &key.to_string()]);
+    }
+    if let Some(region) = &args.region {
+        push(&["--region", region.as_str()]);
+    }
+    if args.per_word {
+        push(&["--per-word"]);
+    }
+    if args.by_line {
+        push(&["--by-line"]);
+    }
+    if args.group.is_some() {
+        push(&["--ungroup"]);
+    }
+    if args.phonetic {
+        push(&["--phonetic"]);
+    }
+    // Byte input to a lone byte cipher comes out encoded in --byte-format
+    if let ([Algorithm::Xor | Algorithm::ByteCaesar], false) = (
+        args.algorithm.as_slice(),
+        args.input_format == InputFormat::Text,
+    ) {
+        push(&[
+            "--input-format",
+            args.byte_format.to_possible_value().unwrap().get_name(),
+        ]);
+    }
+
+    match (&args.output_file, args.in_place) {
+        (Some(path), _) => push(&["-i", &path.display().to_string()]),
+        (None, true) => push(&[
+            "--in-place",
+            "-i",
+            &args.input_file[0].display().to_string(),
+        ]),
+        (None, false) if output_text.starts_with('-') => push(&["--", output_text]),
+        (None, false) => push(&[output_text]),
+    }
+
+    words
+        .iter()
+        .map(|word| shell_quote(word))
+        .collect::<Vec<String>>()
+        .join(" ")
+}
+
+/// Reads the key from an environment variable, parsed like `--key`.
+///
+/// Fails with a usage error if the variable is unset, empty or not Unicode.
+fn key_from_env(name: &str) -> Result<KeyType, Error> {
+    match std::env::var(name) {
+        Ok(value) if !value.is_empty() => Ok(KeyType::from_str(&value).unwrap()),
+        Ok(_) => Err(Error::Usage(format!(
+            "Environment variable {} is empty.",
+            name
+        ))),
+        Err(std::env::VarError::NotPresent) => Err(Error::Usage(format!(
+            "Environment variable {} is not set.",
+            name
+        ))),
+        Err(std::env::VarError::NotUnicode(_)) => Err(Error::Usage(format!(
+            "Environment variable {} is not valid Unicode.",
+            name
+        ))),
+    }
+}
+
+/// Parses a Caesar key given as a percentage of the alphabet, like `50%`.
+///
+/// # Arguments
+/// * `key` - The key as given on the command line.
+///
+/// # Returns
+/// The rounded shift wrapped into 0..26, or `None` if the key isn't a
+/// number followed by `%`.
+///
+/// # Examples
+/// ```
+/// assert_eq!(percent_shift("50%"), Some(13));
+/// assert_eq!(percent_shift("-25%"), Some(19));
+/// ```
+fn percent_shift(key: &str) -> Option<i32> {
+    let percent: f64 = key.strip_suffix('%')?.trim().parse().ok()?;
+    let shift = (percent / 100.0 * 26.0).round();
+    shift
+        .is_finite()
+        .then(|| (shift as i64).rem_euclid(26) as i32)
+}
+
+/// Block length used by the fractionating ciphers.
+const DEFAULT_PERIOD: usize = 5;
+
+/// Cipher-specific settings that aren't part of the key itself.
+struct CipherOptions {
+    variant: ColumnarVariant,
+    square_size: usize,
+    fill: SquareFill,
+    merge: LetterMerge,
+    keep_punctuation: bool,
+    offset: usize,
+    alphabet: Option<Alphabet>,
+    reverse_alphabet: bool,
+    period: usize,
+    key2: Option<String>,
+}
+
+impl Default for CipherOptions {
+    fn default() -> Self {
+        CipherOptions {
+            variant: ColumnarVariant::Standard,
+            square_size: 5,
+            fill: SquareFill::Rows,
+            merge: LetterMerge::default(),
+            keep_punctuation: false,
+            offset: 0,
+            alphabet: None,
+            reverse_alphabet: false,
+            period: DEFAULT_PERIOD,
+            key2: None,
+        }
+    }
+}
+
+/// Builds the cipher for a single algorithm from its key, if it takes one.
+///
+/// Returns an error if the key type doesn't match what the algorithm
+/// expects or the key can't be used.
+fn build_cipher(
+    algorithm: &Algorithm,
+    key: Option<&KeyType>,
+    options: &CipherOptions,
+) -> Result<Box<dyn Cipher>, String> {
+    match algorithm {
+        Algorithm::Caesar => match key {
+            Some(KeyType::Integer(key)) => Ok(match &options.alphabet {
+                Some(alphabet) => Box::new(AlphabetCaesarCipher {
+                    key: *key,
+                    alphabet: alphabet.clone(),
+                }),
+                None if options.reverse_alphabet => Box::new(ReversedCaesarCipher { key: *key }),
+                None => Box::new(CaesarCipher { key: *key }),
+            }),
+            _ => Err("Caesar cipher requires an integer key.".to_string()),
+        },
+        Algorithm::Vigenère => match key {
+            Some(KeyType::Text(key)) => {
+                let cipher = VigenereCipher::new(key.clone());
+                match cipher.key.is_empty() {
+                    true => Err("Vigenère cipher requires a key with letters.".to_string()),
+                    false => Ok(Box::new(cipher)),
+                }
+            }
+            _ => Err("Vigenère cipher requires a text key.".to_string()),
+        },
+        Algorithm::Playfair => match key {
+            Some(KeyType::Text(key)) => {
+                let mut cipher = match (options.square_size, options.fill, options.merge) {
+                    (5, SquareFill::Rows, merge) if merge == LetterMerge::default() => {
+                        PlayfairCipher::new(key.clone())
+                    }
+                    (size, fill, merge) => {
+                        PlayfairCipher::with_merge(key.clone(), size, fill, merge)
+                    }
+                };
+                cipher.keep_punctuation = options.keep_punctuation;
+                Ok(Box::new(cipher))
+            }
+            _ => Err("Playfair cipher requires a text key.".to_string()),
+        },
+        Algorithm::Columnar => match key {
+            Some(KeyType::Text(key)) if key.is_empty() => {
+                Err("Columnar cipher requires a non-empty key.".to_string())
+            }
+            Some(KeyType::Text(key)) => {
+                Ok(Box::new(ColumnarCipher::new(key.clone(), options.variant)))
+            }
+            _ => Err("Columnar cipher requires a text key.".to_string()),
+        },
+        Algorithm::RailFence => match key {
+            Some(KeyType::Integer(rails)) if *rails < 1 => {
+                Err("Rail fence cipher requires at least one rail.".to_string())
+            }
+            Some(KeyType::Integer(rails)) => Ok(Box::new(RailFenceCipher {
+                rails: *rails as usize,
+                offset: options.offset,
+            })),
+            _ => Err("Rail fence cipher requires an integer key.".to_string()),
+        },
+        Algorithm::Bifid => match key {
+            Some(KeyType::Text(key)) => Ok(Box::new(BifidCipher::new(key, options.period))),
+            _ => Err("Bifid cipher requires a text key.".to_string()),
+        },
+        Algorithm::Trifid => match key {
+            Some(KeyType::Text(key)) => Ok(Box::new(TrifidCipher::new(key, options.period)?)),
+            _ => Err("Trifid cipher requires a 27 symbol alphabet as its key.".to_string()),
+        },
+        Algorithm::Digrafid => match (key, &options.key2) {
+            (Some(KeyType::Text(key)), Some(key2)) => {
+                Ok(Box::new(DigrafidCipher::new(key, key2, options.period)))
+            }
+            (Some(KeyType::Text(_)), None) => {
+                Err("Digrafid cipher requires a second keyword in --key2.".to_string())
+            }
+            _ => Err("Digrafid cipher requires a text key.".to_string()),
+        },
+        Algorithm::Quagmire => match (key, &options.key2) {
+            (Some(KeyType::Text(key)), Some(indicator)) => {
+                Ok(Box::new(QuagmireCipher::new(key, indicator)?))
+            }
+            (Some(KeyType::Text(_)), None) => {
+                Err("Quagmire I cipher requires an indicator keyword in --key2.".to_string())
+            }
+            _ => Err("Quagmire I cipher requires a text key.".to_string()),
+        },
+        Algorithm::Vic => {
+            let digits = match key {
+                Some(KeyType::Integer(number)) => number.to_string(),
+                Some(KeyType::Text(digits)) => digits.clone(),
+                None => String::new(),
+            };
+            match &options.key2 {
+                Some(phrase) => Ok(Box::new(VicCipher::new(&digits, phrase)?)),
+                None => Err("VIC cipher requires a key phrase in --key2.".to_string()),
+            }
+        }
+        Algorithm::Gromark => {
+            let primer = match key {
+                Some(KeyType::Integer(number)) => number.to_string(),
+                Some(KeyType::Text(digits)) => digits.clone(),
+                None => String::new(),
+            };
+            match &options.key2 {
+                Some(keyword) => Ok(Box::new(GromarkCipher::new(&primer, keyword)?)),
+                None => Err("Gromark cipher requires an alphabet keyword in --key2.".to_string()),
+            }
+        }
+        Algorithm::Alberti => match (key, &options.key2) {
+            (Some(KeyType::Text(key)), Some(index)) => {
+                Ok(Box::new(AlbertiCipher::new(key, index, options.period)?))
+            }
+            (Some(KeyType::Text(_)), None) => {
+                Err("Alberti cipher requires an index letter in --key2.".to_string())
+            }
+            _ => Err("Alberti cipher requires a text key.".to_string()),
+        },
+        Algorithm::Bazeries => match key {
+            Some(KeyType::Integer(number)) if *number > 0 => {
+                Ok(Box::new(BazeriesCipher::new(*number as u32)?))
+            }
+            _ => Err("Bazeries cipher requires a positive integer key.".to_string()),
+        },
+        Algorithm::Shuffle => match key {
+            Some(KeyType::Integer(seed)) => Ok(Box::new(ShuffleCipher::new(&seed.to_string()))),
+            Some(KeyType::Text(key)) => Ok(Box::new(ShuffleCipher::new(key))),
+            None => Err("Shuffle transposition requires a key.".to_string()),
+        },
+        Algorithm::Xor => match key {
+            Some(KeyType::Text(key)) => Ok(Box::new(XorCipher {
+                key: key.as_bytes().to_vec(),
+            })),
+            _ => Err("XOR cipher requires a text key.".to_string()),
+        },
+        Algorithm::ByteCaesar => match key {
+            Some(KeyType::Integer(key)) => Ok(Box::new(ByteCaesarCipher::new(*key))),
+            _ => Err("Byte Caesar cipher requires an integer key.".to_string()),
+        },
+        Algorithm::Rot18 => Ok(Box::new(Rot18Cipher)),
+        Algorithm::Rot47 => Ok(Box::new(Rot47Cipher)),
+        Algorithm::UnicodeRot => match key {
+            Some(KeyType::Integer(shift)) => Ok(Box::new(UnicodeRotCipher {
+                shift: *shift as i64,
+            })),
+            _ => Err("Unicode ROT requires an integer key.".to_string()),
+        },
+    }
+}
+
+/// Builds the cipher, failing with a `CipherError` if the key doesn't fit.
+fn build_cipher_checked(
+    algorithm: &Algorithm,
+    key: Option<&KeyType>,
+    options: &CipherOptions,
+) -> Result<Box<dyn Cipher>, CipherError> {
+    build_cipher(algorithm, key, options).map_err(CipherError)
+}
+
+/// Runs the text through a chain of ciphers.
+///
+/// Encryption applies the ciphers left-to-right and decryption
+/// peels them off right-to-left, so the same chain round-trips.
+fn apply_chain(ciphers: &[Box<dyn Cipher>], direction: &Direction, text: &str) -> String {
+    match direction {
+        Direction::Encrypt => ciphers.iter().fold(text.to_string(), |acc, cipher| {
+            let output = cipher.encrypt(&acc);
+            trace_layer(&acc, &output);
+            output
+        }),
+        Direction::Decrypt => ciphers.iter().rev().fold(text.to_string(), |acc, cipher| {
+            let output = cipher.decrypt(&acc);
+            trace_layer(&acc, &output);
+            output
+        }),
+    }
+}
+
+/// Encrypts the first `length` characters of the input (or bytes, for
+/// decoded XOR input) and prints them labelled as a preview, warning when
+/// an algorithm in the chain mixes the whole text so the preview isn't
+/// the start of the full ciphertext.
+fn print_preview(
+    algorithms: &[Algorithm],
+    ciphers: &[Box<dyn Cipher>],
+    input_text: &str,
+    input_bytes: Option<&[u8]>,
+    key: Option<&KeyType>,
+    length: usize,
+    args: &Args,
+) -> Result<(), Error> {
+    let mixing: Vec<String> = algorithms
+        .iter()
+        .filter(|algorithm| !algorithm.is_streaming_capable())
+        .map(|algorithm| algorithm.cli_name().to_string())
+        .collect();
+    if !mixing.is_empty() {
+        eprintln!(
+            "Warning: {} doesn't encrypt character by character, so this \
+             preview isn't representative of the full ciphertext.",
+            mixing.join(", ")
+        );
+    }
+
+    let preview = match input_bytes {
+        Some(bytes) => byte_output(
+            build_byte_cipher(&algorithms[0], key)?.as_ref(),
+            &bytes[..length.min(bytes.len())],
+            &Direction::Encrypt,
+            args.byte_format,
+            !args.no_padding,
+        )?,
+        _ => {
+            let prefix: String = input_text.chars().take(length).collect();
+            apply_chain(ciphers, &Direction::Encrypt, &prefix)
+        }
+    };
+    outln!("Preview of the first {} characters:", length)?;
+    outln!("{}", preview)?;
+    Ok(())
+}
+
+/// Reads and concatenates the contents of every input file in order.
+///
+/// Returns an error naming the first file that couldn't be read.
+fn read_input_files(paths: &[PathBuf], separator: &str) -> Result<String, Error> {
+    let mut contents = Vec::with_capacity(paths.len());
+    for path in paths {
+        let text = fs::read(path)
+            .and_then(|bytes| {
+                String::from_utf8(bytes)
+                    .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+            })
+            .map_err(|err| Error::io(format!("Could not read {}", path.display()), err))?;
+        contents.push(text);
+    }
+
+    Ok(contents.join(separator))
+}
+
+/// Adds up the size in bytes of the input files and separators, without reading them.
+///
+/// Returns an error naming the first file whose size couldn't be read.
+fn input_files_size(paths: &[PathBuf], separator: &str) -> Result<u64, Error> {
+    let separators = paths.len().saturating_sub(1) as u64 * separator.len() as u64;
+    paths.iter().try_fold(separators, |total, path| {
+        fs::metadata(path)
+            .map(|metadata| total + metadata.len())
+            .map_err(|err| Error::io(format!("Could not read {}", path.display()), err))
+    })
+}
+
+/// Describes the control characters and unusual whitespace in the input,
+/// which pass through the ciphers but can make output confusing.
+///
+/// Line breaks are expected in file input and aren't reported.
+///
+/// # Returns
+/// A warning listing each character's code point and character position,
+/// or `None` when the input is clean.
+fn control_character_warning(text: &str) -> Option<String> {
+    let found: Vec<String> = text
+        .chars()
+        .enumerate()
+        .filter(|(_, c)| {
+            (c.is_control() || (c.is_whitespace() && *c != ' ')) && *c != '\n' && *c != '\r'
+        })
+        .map(|(position, c)| format!("U+{:04X} at {}", c as u32, position))
+        .collect();
+
+    match found.is_empty() {
+        true => None,
+        false => Some(format!(
+            "Warning: input contains {} control or unusual whitespace character(s): {}",
+            found.len(),
+            found.join(", ")
+        )),
+    }
+}
+
+/// Describes the input characters that every cipher in the chain would
+/// pass through unchanged.
+///
+/// # Returns
+/// An error listing each such character and its character position,
+/// or `None` when every character is enciphered.
+fn unencrypted_characters(text: &str, ciphers: &[Box<dyn Cipher>]) -> Option<String> {
+    let found: Vec<String> = text
+        .chars()
+        .enumerate()
+        .filter(|(_, c)| !ciphers.iter().any(|cipher| cipher.transforms(*c)))
+        .map(|(position, c)| format!("{:?} at {}", c, position))
+        .collect();
+
+    match found.is_empty() {
+        true => None,
+        false => Some(format!(
+            "Input contains {} character(s) that would pass through unencrypted: {}",
+            found.len(),
+            found.join(", ")
+        )),
+    }
+}
+
+/// Normalizes the casing of the final output, independent of
+/// whatever casing the cipher itself produced.
+fn apply_output_case(text: &str, output_case: &OutputCase) -> String {
+    match output_case {
+        OutputCase::Upper => text.to_uppercase(),
+        OutputCase::Lower => text.to_lowercase(),
+        OutputCase::Preserve => text.to_string(),
+    }
+}
+
+/// Builds the byte-level cipher for input decoded from hex, base64 or
+/// base32, which only a lone XOR or byte Caesar cipher receives.
+fn build_byte_cipher(
+    algorithm: &Algorithm,
+    key: Option<&KeyType>,
+) -> Result<Box<dyn ByteCipher>, CipherError> {
+    match (algorithm, key) {
+        (Algorithm::Xor, Some(KeyType::Text(key))) => Ok(Box::new(XorCipher {
+            key: key.as_bytes().to_vec(),
+        })),
+        (Algorithm::ByteCaesar, Some(KeyType::Integer(key))) => {
+            Ok(Box::new(ByteCaesarCipher::new(*key)))
+        }
+        _ => Err(CipherError(format!(
+            "The {} can't take this key for byte input.",
+            algorithm.display_name()
+        ))),
+    }
+}
+
+/// Whether ciphertext from byte input can be streamed straight to stdout
+/// or the output file as it is encoded, because no option needs the whole
+/// output text first.
+fn streams_byte_ciphertext(args: &Args) -> bool {
+    matches!(args.output_case, OutputCase::Preserve)
+        && args.group.is_none()
+        && args.wrap.is_none()
+        && !args.phonetic
+        && !args.segment
+        && !args.print_decrypt_cmd
+        && !args.in_place
+        && args.expect_file.is_none()
+        && args.format == qr::OutputFormat::Text
+        && args.output_encoding == OutputEncoding::Utf8
+        && !args.bom
+}
+
+/// Writes the bytes that `write` produces to `sink` in the byte format,
+/// streaming them through a hex or base64 encoder, or encoding them as
+/// base32 at the end.
+fn encode_byte_stream(
+    write: impl FnOnce(&mut dyn Write) -> io::Result<()>,
+    format: ByteFormat,
+    pad: bool,
+    sink: &mut dyn Write,
+) -> io::Result<()> {
+    match format {
+        ByteFormat::Hex => {
+            let mut writer = encoding::HexWriter::new(sink);
+            write(&mut writer)?;
+            writer.finish();
+            Ok(())
+        }
+        ByteFormat::Base64 => {
+            let mut writer = encoding::Base64Writer::new(sink);
+            write(&mut writer)?;
+            writer.finish()?;
+            Ok(())
+        }
+        ByteFormat::Base32 => {
+            let mut raw = Vec::new();
+            write(&mut raw)?;
+            sink.write_all(encoding::encode_base32(&raw, pad).as_bytes())
+        }
+    }
+}
+
+/// Formats the bytes produced by a byte-level cipher from raw input.
+///
+/// Ciphertext is streamed through a hex or base64 encoder as it is
+/// produced, or encoded as base32 at the end; decrypted bytes are shown
+/// as text when they are valid UTF-8 and encoded otherwise.
+fn byte_output(
+    cipher: &dyn ByteCipher,
+    bytes: &[u8],
+    direction: &Direction,
+    format: ByteFormat,
+    pad: bool,
+) -> Result<String, Error> {
+    let decrypted = match direction {
+        Direction::Decrypt => match String::from_utf8(cipher.decrypt_bytes(bytes)) {
+            Ok(text) => return Ok(text),
+            Err(err) => Some(err.into_bytes()),
+        },
+        Direction::Encrypt => None,
+    };
+    let write = |sink: &mut dyn Write| match &decrypted {
+        Some(decrypted) => sink.write_all(decrypted),
+        None => cipher.encrypt_to(bytes, sink),
+    };
+
+    let mut encoded = Vec::new();
+    encode_byte_stream(write, format, pad, &mut encoded)
+        .map(|_| String::from_utf8(encoded).unwrap_or_default())
+        .map_err(|err| Error::io("Could not encode output", err))
+}
+
+/// Compares the input with the decryption of its ciphertext.
+///
+/// # Arguments
+/// * `original` - The text that was encrypted.
+/// * `decrypted` - The ciphertext decrypted again with the same keys.
+///
+/// # Returns
+/// A warning naming the first differing character position, or `None`
+/// when the round trip reproduced the input exactly.
+fn round_trip_warning(original: &str, decrypted: &str) -> Option<String> {
+    if original == decrypted {
+        return None;
+    }
+
+    let position = original
+        .chars()
+        .zip(decrypted.chars())
+        .position(|(a, b)| a != b)
+        .unwrap_or_else(|| original.chars().count().min(decrypted.chars().count()));
+    Some(format!(
+        "Warning: round trip failed, decrypting the output gives {:?} \
+         which differs from the input at position {}",
+        decrypted, position
+    ))
+}
+
+/// Checks that Playfair ciphertext, which always comes in whole digraphs,
+/// has an even number of letters.
+///
+/// # Returns
+/// A message explaining the padded last letter when the count is odd.
+fn odd_playfair_ciphertext(key: &str, options: &CipherOptions, ciphertext: &str) -> Option<String> {
+    let letters = PlayfairCipher::with_merge(
+        key.to_string(),
+        options.square_size,
+        options.fill,
+        options.merge,
+    )
+    .ciphertext_letters(ciphertext);
+    match letters % 2 {
+        0 => None,
+        _ => Some(format!(
+            "Playfair ciphertext has an odd number of letters ({}), but valid \
+             ciphertext is always even-length; the last letter was padded with \
+             'x' to decrypt it, so the end of the output is unreliable.",
+            letters
+        )),
+    }
+}
+
+/// Catches an encryption that left the text as it was, which almost always
+/// means a no-op key such as a Caesar shift of 0 or 26, or input with
+/// nothing the ciphers change.
+///
+/// # Returns
+/// A warning when the non-empty input and the output are identical.
+fn identity_warning(input: &str, output: &str) -> Option<String> {
+    match !input.is_empty() && input == output {
+        true => Some(
+            "Warning: the output is identical to the input; the key may be a no-op \
+             or the input may have no characters the cipher changes"
+                .to_string(),
+        ),
+        false => None,
+    }
+}
+
+/// Explains a failed round trip when a cipher in the chain changes the
+/// text's length, e.g. by padding or dropping characters.
+///
+/// # Returns
+/// A note naming the first such cipher, or `None` if every cipher preserves length.
+fn length_change_note(ciphers: &[Box<dyn Cipher>]) -> Option<String> {
+    ciphers
+        .iter()
+        .find(|cipher| !cipher.preserves_length())
+        .map(|cipher| {
+            format!(
+                "Note: the {} changes the length of the text, \
+                 so it isn't expected to round-trip exactly",
+                cipher
+            )
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_chain_round_trip() {
+        let ciphers = vec![
+            build_cipher(
+                &Algorithm::Caesar,
+                Some(&KeyType::Integer(3)),
+                &CipherOptions::default(),
+            )
+            .unwrap(),
+            build_cipher(
+                &Algorithm::Vigenère,
+                Some(&KeyType::Text("lemon".to_string())),
+                &CipherOptions::default(),
+            )
+            .unwrap(),
+        ];
+        let ciphertext = apply_chain(&ciphers, &Direction::Encrypt, "attackatdawn");
+        let plaintext = apply_chain(&ciphers, &Direction::Decrypt, &ciphertext);
+
+        assert_eq!(ciphertext, "oairsyhiuqku");
+        assert_eq!(plaintext, "attackatdawn");
+    }
+
+    #[test]
+    fn test_output_case() {
+        let cipher = build_cipher(
+            &Algorithm::Caesar,
+            Some(&KeyType::Integer(3)),
+            &CipherOptions::default(),
+        )
+        .unwrap();
+        let ciphertext = cipher.encrypt("Hello World");
+        let upper = apply_output_case(&ciphertext, &OutputCase::Upper);
+        let lower = apply_output_case(&ciphertext, &OutputCase::Lower);
+        let preserved = apply_output_case(&ciphertext, &OutputCase::Preserve);
+
+        assert_eq!(upper, "KHOOR ZRUOG");
+        assert_eq!(lower, "khoor zruog");
+        assert_eq!(preserved, "Khoor Zruog");
+    }
+
+    #[test]
+    fn test_read_input_files() {
+        let dir = std::env::temp_dir();
+        let first = dir.join("crypto_cli_tool_test_read_input_files_1.txt");
+        let second = dir.join("crypto_cli_tool_test_read_input_files_2.txt");
+        fs::write(&first, "attack").unwrap();
+        fs::write(&second, "atdawn").unwrap();
+
+        let cipher = build_cipher(
+            &Algorithm::Vigenère,
+            Some(&KeyType::Text("lemon".to_string())),
+            &CipherOptions::default(),
+        )
+        .unwrap();
+        let combined = read_input_files(&[first.clone(), second.clone()], "").unwrap();
+        let separated = read_input_files(&[first.clone(), second.clone()], "-").unwrap();
+        let missing = read_input_files(
+            &[first.clone(), dir.join("crypto_cli_tool_missing_input.txt")],
+            "",
+        );
+        fs::remove_file(first).unwrap();
+        fs::remove_file(second).unwrap();
+
+        assert_eq!(separated, "attack-atdawn");
+        assert_eq!(cipher.encrypt(&combined), cipher.encrypt("attackatdawn"));
+        assert!(missing.is_err());
+    }
+
+    #[test]
+    fn test_per_word_round_trip() {
+        let ciphers = vec![build_cipher(
+            &Algorithm::Vigenère,
+            Some(&KeyType::Text("lemon".to_string())),
+            &CipherOptions::default(),
+        )
+        .unwrap()];
+        let words = region::words();
+        let per_word = region::apply_to_regions("hello world", &words, |text| {
+            apply_chain(&ciphers, &Direction::Encrypt, text)
+        });
+        let continuous = apply_chain(&ciphers, &Direction::Encrypt, "hello world");
+        let plaintext = region::apply_to_regions(&per_word, &words, |text| {
+            apply_chain(&ciphers, &Direction::Decrypt, text)
+        });
+
+        assert_eq!(per_word, "sixzb hsdzq");
+        assert_ne!(per_word, continuous);
+        assert_eq!(plaintext, "hello world");
+    }
+
+    #[test]
+    fn test_control_character_warning() {
+        let warning = control_character_warning("attack\tat\0dawn\n");
+        let clean = control_character_warning("attack at dawn\n");
+
+        assert_eq!(
+            warning,
+            Some(
+                "Warning: input contains 2 control or unusual whitespace character(s): \
+                 U+0009 at 6, U+0000 at 9"
+                    .to_string()
+            )
+        );
+        assert_eq!(clean, None);
+    }
+
+    #[test]
+    fn test_describe_key_playfair() {
+        let lines = describe_key(
+            &Algorithm::Playfair,
+            Some(&"Key1!".parse().unwrap()),
+            &CipherOptions::default(),
+        );
+
+        assert_eq!(
+            lines,
+            vec![
+                "Playfair cipher key: parsed as the text \"Key1!\"",
+                "  uppercased, J read as I, non-letters and repeats stripped: \"KEY\"",
+                "  square:",
+                "    K E Y A B",
+                "    C D F G H",
+                "    I L M N O",
+                "    P Q R S T",
+                "    U V W X Z",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_every_algorithm_has_a_working_example() {
+        for algorithm in Algorithm::value_variants() {
+            let example = example_command(algorithm);
+            let args = Cli::try_parse_from(
+                std::iter::once(env!("CARGO_PKG_NAME").to_string()).chain(algorithm.example_args()),
+            )
+            .unwrap_or_else(|err| panic!("{:?} example doesn't parse: {}", algorithm, err))
+            .args;
+            let options = CipherOptions {
+                key2: args.key2.clone(),
+                ..CipherOptions::default()
+            };
+
+            assert!(example.contains(&format!("-a {}", algorithm.cli_name())));
+            assert_eq!(args.algorithm, vec![algorithm.clone()]);
+            assert!(
+                build_cipher(algorithm, args.key.first(), &options).is_ok(),
+                "{:?} example key doesn't build a cipher",
+                algorithm
+            );
+        }
+    }
+
+    #[test]
+    fn test_key_length_note() {
+        let options = CipherOptions::default();
+        let vigenere = VigenereCipher::new("k".to_string());
+        let playfair = PlayfairCipher::new("abcdefghijklmnopqrstuvwxyz".to_string());
+
+        assert_eq!(
+            key_length_note(&Algorithm::Vigenère, "k", &vigenere, &options),
+            Some(
+                "Note: the Vigenère cipher key \"k\" has 1 usable character(s); \
+                 with fewer than 2 the cipher degenerates into a simpler one"
+                    .to_string()
+            )
+        );
+        assert!(key_length_note(
+            &Algorithm::Playfair,
+            "abcdefghijklmnopqrstuvwxyz",
+            &playfair,
+            &options
+        )
+        .unwrap()
+        .contains("26 distinct characters, more than the 25"));
+        assert_eq!(
+            key_length_note(
+                &Algorithm::Vigenère,
+                "lemon",
+                &VigenereCipher::new("lemon".to_string()),
+                &options
+            ),
+            None
+        );
+        assert_eq!(
+            key_length_note(
+                &Algorithm::Playfair,
+                "",
+                &PlayfairCipher::new(String::new()),
+                &options
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn test_odd_playfair_ciphertext() {
+        let options = CipherOptions {
+            fill: SquareFill::Columns,
+            merge: "W=V".parse().unwrap(),
+            ..CipherOptions::default()
+        };
+
+        assert!(odd_playfair_ciphertext("key", &options, "ABC")
+            .unwrap()
+            .contains("odd number of letters (3)"));
+        assert_eq!(odd_playfair_ciphertext("key", &options, "AB CD"), None);
+    }
+
+    #[test]
+    fn test_format_seconds() {
+        assert_eq!(format_seconds(0.2), "under a second");
+        assert_eq!(format_seconds(42.0), "42 s");
+        assert_eq!(format_seconds(600.0), "10 min");
+        assert_eq!(format_seconds(5400.0), "1.5 h");
+    }
+
+    #[test]
+    fn test_shell_quote() {
+        assert_eq!(shell_quote("lemon"), "lemon");
+        assert_eq!(shell_quote("-3"), "-3");
+        assert_eq!(shell_quote("attack at dawn"), "'attack at dawn'");
+        assert_eq!(shell_quote("it's"), "'it'\\''s'");
+        assert_eq!(shell_quote(""), "''");
+    }
+
+    #[test]
+    fn test_percent_shift() {
+        let shift = percent_shift("50%").unwrap();
+        let caesar = build_cipher(
+            &Algorithm::Caesar,
+            Some(&KeyType::Integer(shift)),
+            &CipherOptions::default(),
+        )
+        .unwrap();
+        let ciphertext = caesar.encrypt("attack at dawn");
+
+        assert_eq!(shift, 13);
+        assert_eq!(ciphertext, "nggnpx ng qnja");
+        assert_eq!(caesar.decrypt(&ciphertext), "attack at dawn");
+        assert_eq!(percent_shift("150%"), Some(13));
+        assert_eq!(percent_shift("-25%"), Some(19));
+        assert_eq!(percent_shift("50"), None);
+        assert_eq!(percent_shift("half%"), None);
+    }
+
+    #[test]
+    fn test_round_trip_warning() {
+        let caesar = build_cipher(
+            &Algorithm::Caesar,
+            Some(&KeyType::Integer(3)),
+            &CipherOptions::default(),
+        )
+        .unwrap();
+        let playfair = build_cipher(
+            &Algorithm::Playfair,
+            Some(&KeyType::Text("keyword".to_string())),
+            &CipherOptions::default(),
+        )
+        .unwrap();
+        let caesar_round_trip = caesar.decrypt(&caesar.encrypt("attack at dawn"));
+        let playfair_round_trip = playfair.decrypt(&playfair.encrypt("HELLO"));
+
+        assert_eq!(
+            round_trip_warning("attack at dawn", &caesar_round_trip),
+            None
+        );
+        assert!(round_trip_warning("HELLO", &playfair_round_trip).is_some());
+        assert_eq!(length_change_note(&[caesar]), None);
+        assert_eq!(
+            length_change_note(&[playfair]).unwrap(),
+            "Note: the Playfair cipher changes the length of the text, \
+             so it isn't expected to round-trip exactly"
+        );
+    }
 }
@@ -0,0 +1,58 @@
+//! The `normalize` module applies Unicode normalization to the input before it is enciphered
+//!
+//! An accented letter like "é" can be one code point or a base letter plus a
+//! combining accent, and the ciphers treat those differently. Normalizing the
+//! input first makes the output consistent; decrypt with the same form.
+
+use unicode_normalization::UnicodeNormalization;
+
+/// Unicode normalization forms.
+#[derive(clap::ArgEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Normalization {
+    #[clap(name = "nfc")]
+    Nfc,
+    #[clap(name = "nfd")]
+    Nfd,
+    #[clap(name = "nfkc")]
+    Nfkc,
+    #[clap(name = "nfkd")]
+    Nfkd,
+}
+
+/// Normalizes the text to the given form.
+///
+/// # Arguments
+/// * `text` - The text to normalize.
+/// * `form` - Composed or decomposed, canonical or compatibility form.
+///
+/// # Returns
+/// The normalized text.
+///
+/// # Examples
+/// ```
+/// assert_eq!(normalize("e\u{301}", Normalization::Nfc), "\u{e9}");
+/// ```
+pub fn normalize(text: &str, form: Normalization) -> String {
+    match form {
+        Normalization::Nfc => text.nfc().collect(),
+        Normalization::Nfd => text.nfd().collect(),
+        Normalization::Nfkc => text.nfkc().collect(),
+        Normalization::Nfkd => text.nfkd().collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize() {
+        let composed = normalize("caf\u{65}\u{301}", Normalization::Nfc);
+        let decomposed = normalize("caf\u{e9}", Normalization::Nfd);
+        let compatibility = normalize("\u{fb01}", Normalization::Nfkc);
+
+        assert_eq!(composed, "caf\u{e9}");
+        assert_eq!(decomposed, "caf\u{65}\u{301}");
+        assert_eq!(compatibility, "fi");
+    }
+}
@@ -0,0 +1,249 @@
+//! The `output` module re-encodes the final report before it is written out
+
+use crate::exit::{self, ExitCode};
+use std::fmt;
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::path::Path;
+use std::process;
+
+/// Byte encodings the output can be written in.
+#[derive(clap::ArgEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OutputEncoding {
+    #[clap(name = "utf8")]
+    Utf8,
+    #[clap(name = "utf16le")]
+    Utf16Le,
+    #[clap(name = "windows1252")]
+    Windows1252,
+}
+
+/// Characters for the Windows-1252 bytes 0x80 to 0x9F; `None` marks unassigned bytes.
+/// Every other byte maps to the Unicode code point with the same value.
+const WINDOWS_1252_HIGH: [Option<char>; 32] = [
+    Some('€'),
+    None,
+    Some('‚'),
+    Some('ƒ'),
+    Some('„'),
+    Some('…'),
+    Some('†'),
+    Some('‡'),
+    Some('ˆ'),
+    Some('‰'),
+    Some('Š'),
+    Some('‹'),
+    Some('Œ'),
+    None,
+    Some('Ž'),
+    None,
+    None,
+    Some('‘'),
+    Some('’'),
+    Some('“'),
+    Some('”'),
+    Some('•'),
+    Some('–'),
+    Some('—'),
+    Some('˜'),
+    Some('™'),
+    Some('š'),
+    Some('›'),
+    Some('œ'),
+    None,
+    Some('ž'),
+    Some('Ÿ'),
+];
+
+/// Encodes a single character as its Windows-1252 byte, if it has one.
+fn windows_1252_byte(c: char) -> Option<u8> {
+    let code = c as u32;
+    if code < 0x80 || (0xA0..=0xFF).contains(&code) {
+        return Some(code as u8);
+    }
+
+    WINDOWS_1252_HIGH
+        .iter()
+        .position(|&mapped| mapped == Some(c))
+        .map(|index| 0x80 + index as u8)
+}
+
+/// Encodes the text in the requested encoding.
+///
+/// # Arguments
+/// * `text` - The text to encode.
+/// * `encoding` - Target byte encoding.
+/// * `bom` - Prefix the output with a byte order mark (UTF-8 and UTF-16LE only).
+///
+/// # Returns
+/// The encoded bytes, or an error naming the first character the encoding can't represent.
+pub fn encode(text: &str, encoding: OutputEncoding, bom: bool) -> Result<Vec<u8>, String> {
+    match encoding {
+        OutputEncoding::Utf8 => {
+            let mut bytes = Vec::with_capacity(text.len() + 3);
+            if bom {
+                bytes.extend_from_slice(&[0xEF, 0xBB, 0xBF]);
+            }
+            bytes.extend_from_slice(text.as_bytes());
+            Ok(bytes)
+        }
+        OutputEncoding::Utf16Le => {
+            let mut bytes = Vec::with_capacity(text.len() * 2 + 2);
+            if bom {
+                bytes.extend_from_slice(&[0xFF, 0xFE]);
+            }
+            for unit in text.encode_utf16() {
+                bytes.extend_from_slice(&unit.to_le_bytes());
+            }
+            Ok(bytes)
+        }
+        OutputEncoding::Windows1252 => {
+            if bom {
+                return Err("Windows-1252 has no byte order mark".to_string());
+            }
+            text.chars()
+                .map(|c| {
+                    windows_1252_byte(c).ok_or_else(|| {
+                        format!("character {:?} cannot be represented in Windows-1252", c)
+                    })
+                })
+                .collect()
+        }
+    }
+}
+
+/// Why the output couldn't be written.
+#[derive(Debug, PartialEq, Eq)]
+pub enum OutputError {
+    /// The text can't be represented in the requested encoding.
+    Encode(String),
+    /// Writing to stdout failed.
+    Io(String),
+}
+
+impl fmt::Display for OutputError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            OutputError::Encode(message) => write!(f, "{}", message),
+            OutputError::Io(message) => write!(f, "Could not write output: {}", message),
+        }
+    }
+}
+
+/// Converts an error writing to stdout, exiting successfully instead when the
+/// reader has closed the pipe early, e.g. when piped into `head`.
+fn stdout_error(err: io::Error) -> OutputError {
+    if err.kind() == io::ErrorKind::BrokenPipe {
+        exit::exit(ExitCode::Success);
+    }
+    OutputError::Io(err.to_string())
+}
+
+/// Encodes the text and writes it to stdout.
+pub fn write(text: &str, encoding: OutputEncoding, bom: bool) -> Result<(), OutputError> {
+    let bytes = encode(text, encoding, bom).map_err(OutputError::Encode)?;
+    let mut stdout = io::stdout().lock();
+    stdout
+        .write_all(&bytes)
+        .and_then(|_| stdout.flush())
+        .map_err(stdout_error)
+}
+
+/// Streams output to stdout through `write` as it is produced, instead of
+/// building the whole text first.
+pub fn stream(write: impl FnOnce(&mut dyn Write) -> io::Result<()>) -> Result<(), OutputError> {
+    let mut stdout = io::BufWriter::new(io::stdout().lock());
+    write(&mut stdout)
+        .and_then(|_| stdout.flush())
+        .map_err(stdout_error)
+}
+
+/// Writes a line to stdout, used by `outln!` in place of `println!`, which
+/// panics if the reader closes the pipe.
+pub fn write_line(line: &str) -> Result<(), OutputError> {
+    let mut stdout = io::stdout().lock();
+    writeln!(stdout, "{}", line).map_err(stdout_error)
+}
+
+/// Encodes the text and replaces the file at `path` with it.
+///
+/// The bytes go to a temporary file next to the original, which is then
+/// renamed over it, so a failure part way through leaves the original
+/// untouched and the temporary file removed.
+pub fn write_in_place(
+    path: &Path,
+    text: &str,
+    encoding: OutputEncoding,
+    bom: bool,
+) -> Result<(), OutputError> {
+    let bytes = encode(text, encoding, bom).map_err(OutputError::Encode)?;
+    write_bytes_in_place(path, &bytes)
+}
+
+/// Replaces the file at `path` with raw bytes, the same way as `write_in_place`.
+pub fn write_bytes_in_place(path: &Path, bytes: &[u8]) -> Result<(), OutputError> {
+    stream_in_place(path, |sink| sink.write_all(bytes))
+}
+
+/// Replaces the file at `path` with whatever `write` streams into it, the
+/// same way as `write_in_place`.
+pub fn stream_in_place(
+    path: &Path,
+    write: impl FnOnce(&mut dyn Write) -> io::Result<()>,
+) -> Result<(), OutputError> {
+    let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+    let temp_path = path.with_file_name(format!(".{}.{}.tmp", file_name, process::id()));
+
+    let result = File::create(&temp_path)
+        .and_then(|file| {
+            let mut sink = io::BufWriter::new(file);
+            write(&mut sink)?;
+            sink.into_inner()
+                .map_err(|err| err.into_error())?
+                .sync_all()
+        })
+        .and_then(|_| fs::rename(&temp_path, path));
+    if result.is_err() {
+        let _ = fs::remove_file(&temp_path);
+    }
+
+    result.map_err(|err| OutputError::Io(format!("{}: {}", path.display(), err)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode_utf16le_round_trip() {
+        let bytes = encode("Khoor, wörld €", OutputEncoding::Utf16Le, false).unwrap();
+        let units: Vec<u16> = bytes
+            .chunks(2)
+            .map(|pair| u16::from_le_bytes([pair[0], pair[1]]))
+            .collect();
+
+        assert_eq!(&bytes[..2], &[b'K', 0x00]);
+        assert_eq!(String::from_utf16(&units).unwrap(), "Khoor, wörld €");
+    }
+
+    #[test]
+    fn test_encode_utf16le_bom() {
+        let with_bom = encode("hi", OutputEncoding::Utf16Le, true).unwrap();
+        let without_bom = encode("hi", OutputEncoding::Utf16Le, false).unwrap();
+
+        assert_eq!(with_bom, vec![0xFF, 0xFE, b'h', 0x00, b'i', 0x00]);
+        assert_eq!(without_bom, vec![b'h', 0x00, b'i', 0x00]);
+    }
+
+    #[test]
+    fn test_encode_windows_1252() {
+        let bytes = encode("café €", OutputEncoding::Windows1252, false).unwrap();
+        let unrepresentable = encode("ā", OutputEncoding::Windows1252, false);
+
+        assert_eq!(bytes, vec![b'c', b'a', b'f', 0xE9, b' ', 0x80]);
+        assert_eq!(
+            unrepresentable,
+            Err("character 'ā' cannot be represented in Windows-1252".to_string())
+        );
+    }
+}
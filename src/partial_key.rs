@@ -0,0 +1,99 @@
+//! The `partial_key` module expands a key with unknown letters into every key it could stand for
+
+/// Marks a key position whose letter is unknown.
+pub const WILDCARD: char = '?';
+
+/// Most wildcards a key may hold; each one multiplies the keys to try by 26.
+pub const MAX_WILDCARDS: usize = 3;
+
+/// Whether the key has any unknown positions.
+pub fn has_wildcards(key: &str) -> bool {
+    key.contains(WILDCARD)
+}
+
+/// Number of keys the pattern stands for: 26 for every wildcard.
+pub fn candidate_count(pattern: &str) -> u64 {
+    26u64.saturating_pow(pattern.matches(WILDCARD).count() as u32)
+}
+
+/// Lists every key the pattern stands for, replacing each wildcard with
+/// every letter from `a` to `z`.
+///
+/// # Arguments
+/// * `pattern` - A key where `?` marks an unknown letter.
+///
+/// # Returns
+/// The keys in alphabetical order of their unknown letters, or an error
+/// when the pattern has more than `MAX_WILDCARDS` wildcards.
+///
+/// # Examples
+/// ```
+/// let keys = expand("k?y").unwrap();
+/// assert_eq!(keys.len(), 26);
+/// assert_eq!(keys[4], "key");
+/// ```
+pub fn expand(pattern: &str) -> Result<Vec<String>, String> {
+    let wildcards = pattern.matches(WILDCARD).count();
+    if wildcards > MAX_WILDCARDS {
+        return Err(format!(
+            "The key has {} wildcards; at most {} are allowed ({} keys).",
+            wildcards,
+            MAX_WILDCARDS,
+            26usize.pow(MAX_WILDCARDS as u32)
+        ));
+    }
+
+    let mut keys = vec![String::new()];
+    for c in pattern.chars() {
+        keys = match c {
+            WILDCARD => keys
+                .iter()
+                .flat_map(|key| ('a'..='z').map(move |letter| format!("{}{}", key, letter)))
+                .collect(),
+            c => keys.into_iter().map(|key| key + &c.to_string()).collect(),
+        };
+    }
+    Ok(keys)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ciphers::{Cipher, VigenereCipher};
+    use crate::wordlist;
+    use std::sync::atomic::AtomicBool;
+
+    #[test]
+    fn test_expand() {
+        let keys = expand("a?c?").unwrap();
+
+        assert_eq!(keys.len(), 26 * 26);
+        assert_eq!(candidate_count("a?c?"), 26 * 26);
+        assert_eq!(keys[0], "aaca");
+        assert_eq!(keys[27], "abcb");
+        assert_eq!(expand("lemon").unwrap(), vec!["lemon"]);
+        assert!(expand("????").is_err());
+    }
+
+    #[test]
+    fn test_one_wildcard_recovers_key() {
+        let cipher = VigenereCipher::new("lemon".to_string());
+        let ciphertext =
+            cipher.encrypt("meet me at the old bridge at noon and bring the documents");
+
+        let candidates = wordlist::rank_candidates(
+            expand("le?on").unwrap().into_iter().map(Ok),
+            &ciphertext,
+            |key| Some(Box::new(VigenereCipher::new(key.to_string())) as Box<dyn Cipher>),
+            3,
+            &AtomicBool::new(false),
+        )
+        .unwrap();
+
+        assert_eq!(candidates[0].key, "lemon");
+        assert_eq!(
+            candidates[0].plaintext,
+            "meet me at the old bridge at noon and bring the documents"
+        );
+    }
+}
@@ -0,0 +1,171 @@
+//! The `phonetic` module spells text out in the NATO phonetic alphabet and reads it back
+
+/// The NATO code word for every letter from A to Z.
+const LETTER_WORDS: [&str; 26] = [
+    "Alpha", "Bravo", "Charlie", "Delta", "Echo", "Foxtrot", "Golf", "Hotel", "India", "Juliet",
+    "Kilo", "Lima", "Mike", "November", "Oscar", "Papa", "Quebec", "Romeo", "Sierra", "Tango",
+    "Uniform", "Victor", "Whiskey", "Xray", "Yankee", "Zulu",
+];
+
+/// The spoken form of every digit from 0 to 9.
+const DIGIT_WORDS: [&str; 10] = [
+    "Zero", "One", "Two", "Three", "Four", "Five", "Six", "Seven", "Eight", "Nine",
+];
+
+/// Other spellings accepted when decoding, and the character each stands for.
+const ALTERNATE_WORDS: [(&str, char); 4] = [
+    ("alfa", 'A'),
+    ("juliett", 'J'),
+    ("x-ray", 'X'),
+    ("niner", '9'),
+];
+
+/// Token written for whitespace between words.
+const WORD_BREAK: &str = "/";
+
+/// Spells the text out one word per character.
+///
+/// Letters become their code word regardless of case and digits their
+/// spoken form. Whitespace becomes `/`, and any other character is
+/// written as itself.
+///
+/// # Arguments
+/// * `text` - The text to spell out.
+///
+/// # Returns
+/// The words separated by single spaces.
+///
+/// # Examples
+/// ```
+/// assert_eq!(encode("Hi 5!"), "Hotel India / Five !");
+/// ```
+pub fn encode(text: &str) -> String {
+    let words: Vec<String> = text
+        .chars()
+        .map(|c| match c {
+            'a'..='z' | 'A'..='Z' => {
+                LETTER_WORDS[(c.to_ascii_uppercase() as u8 - b'A') as usize].to_string()
+            }
+            '0'..='9' => DIGIT_WORDS[(c as u8 - b'0') as usize].to_string(),
+            c if c.is_whitespace() => WORD_BREAK.to_string(),
+            c => c.to_string(),
+        })
+        .collect();
+    words.join(" ")
+}
+
+/// Reads spelled-out text back into characters.
+///
+/// Code words are matched case-insensitively and give capital letters.
+/// `/` gives a space, and a token of a single character that isn't a
+/// letter or digit stands for itself.
+///
+/// # Arguments
+/// * `text` - Whitespace-separated code words.
+///
+/// # Returns
+/// The decoded text, or an error naming the first word that isn't a code word.
+///
+/// # Examples
+/// ```
+/// assert_eq!(decode("hotel india / five !"), Ok("HI 5!".to_string()));
+/// ```
+pub fn decode(text: &str) -> Result<String, String> {
+    text.split_whitespace()
+        .map(|word| {
+            let lowercase = word.to_lowercase();
+            let letter = LETTER_WORDS
+                .iter()
+                .position(|w| w.to_lowercase() == lowercase)
+                .map(|i| (b'A' + i as u8) as char);
+            let digit = DIGIT_WORDS
+                .iter()
+                .position(|w| w.to_lowercase() == lowercase)
+                .map(|i| (b'0' + i as u8) as char);
+            let alternate = ALTERNATE_WORDS
+                .iter()
+                .find(|(w, _)| *w == lowercase)
+                .map(|(_, c)| *c);
+            let mut chars = word.chars();
+            let symbol = match (chars.next(), chars.next()) {
+                (Some(c), None) if !c.is_alphanumeric() => Some(c),
+                _ => None,
+            };
+
+            match word {
+                WORD_BREAK => Ok(' '),
+                _ => letter
+                    .or(digit)
+                    .or(alternate)
+                    .or(symbol)
+                    .ok_or_else(|| format!("unknown phonetic word {:?}", word)),
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_encode_alphabet() {
+        let table = [
+            ('A', "Alpha"),
+            ('B', "Bravo"),
+            ('C', "Charlie"),
+            ('D', "Delta"),
+            ('E', "Echo"),
+            ('F', "Foxtrot"),
+            ('G', "Golf"),
+            ('H', "Hotel"),
+            ('I', "India"),
+            ('J', "Juliet"),
+            ('K', "Kilo"),
+            ('L', "Lima"),
+            ('M', "Mike"),
+            ('N', "November"),
+            ('O', "Oscar"),
+            ('P', "Papa"),
+            ('Q', "Quebec"),
+            ('R', "Romeo"),
+            ('S', "Sierra"),
+            ('T', "Tango"),
+            ('U', "Uniform"),
+            ('V', "Victor"),
+            ('W', "Whiskey"),
+            ('X', "Xray"),
+            ('Y', "Yankee"),
+            ('Z', "Zulu"),
+            ('0', "Zero"),
+            ('9', "Nine"),
+        ];
+        for (c, word) in table {
+            assert_eq!(encode(&c.to_string()), word);
+            assert_eq!(encode(&c.to_ascii_lowercase().to_string()), word);
+            assert_eq!(decode(word), Ok(c.to_string()));
+        }
+    }
+
+    #[test]
+    fn test_decode_round_trip() {
+        let text = "ATTACK AT 0600, HOLD!";
+        let spoken = encode(text);
+
+        assert_eq!(
+            spoken,
+            "Alpha Tango Tango Alpha Charlie Kilo / Alpha Tango / Zero Six Zero Zero , / \
+             Hotel Oscar Lima Delta !"
+        );
+        assert_eq!(decode(&spoken), Ok(text.to_string()));
+        assert_eq!(decode("alfa JULIETT x-ray niner"), Ok("AJX9".to_string()));
+    }
+
+    #[test]
+    fn test_decode_unknown_word() {
+        assert_eq!(
+            decode("Alpha Bravissimo"),
+            Err("unknown phonetic word \"Bravissimo\"".to_string())
+        );
+    }
+}
@@ -0,0 +1,69 @@
+//! The `qr` module renders the output as a QR code of Unicode block characters
+//!
+//! Rendering needs the optional `qrcode` dependency, enabled with the `qr`
+//! feature. Without it `--format qr` is rejected with a hint on how to build it.
+
+/// Ways to print the output text in the report.
+#[derive(clap::ArgEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum OutputFormat {
+    #[clap(name = "text")]
+    Text,
+    #[clap(name = "qr")]
+    Qr,
+}
+
+/// Whether this build can render QR codes.
+pub const AVAILABLE: bool = cfg!(feature = "qr");
+
+/// Renders the text as a QR code, two modules per character cell, light on
+/// dark so it scans from a dark terminal.
+///
+/// # Arguments
+/// * `text` - The text to encode; its UTF-8 bytes go into the code.
+///
+/// # Returns
+/// The QR code as lines of block characters, or an error if the text is
+/// too long for a QR code.
+#[cfg(feature = "qr")]
+pub fn render(text: &str) -> Result<String, String> {
+    use qrcode::render::unicode::Dense1x2;
+    use qrcode::QrCode;
+
+    let code = QrCode::new(text.as_bytes()).map_err(|err| err.to_string())?;
+    Ok(code
+        .render::<Dense1x2>()
+        .dark_color(Dense1x2::Light)
+        .light_color(Dense1x2::Dark)
+        .build())
+}
+
+/// Stands in for the renderer when the `qr` feature is disabled.
+#[cfg(not(feature = "qr"))]
+pub fn render(_text: &str) -> Result<String, String> {
+    Err(unavailable_message())
+}
+
+/// Explains how to get a build that renders QR codes.
+pub fn unavailable_message() -> String {
+    "--format qr needs the `qr` feature; rebuild with `cargo build --features qr`.".to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "qr")]
+    #[test]
+    fn test_render() {
+        let code = render("KHOOR").unwrap();
+
+        assert!(code.lines().count() > 10);
+        assert!(code.contains('█') || code.contains('▀') || code.contains('▄'));
+    }
+
+    #[cfg(not(feature = "qr"))]
+    #[test]
+    fn test_render_unavailable() {
+        assert_eq!(render("KHOOR"), Err(unavailable_message()));
+    }
+}
@@ -0,0 +1,93 @@
+//! The `region` module restricts a transformation to the parts of the input matched by a regex
+
+use regex::Regex;
+
+/// Pattern matching every whitespace-separated word, for enciphering words independently.
+pub fn words() -> Regex {
+    Regex::new(r"\S+").unwrap()
+}
+
+/// Pattern matching the contents of every line, without its `\n` or `\r\n`
+/// ending, so each line is enciphered independently and empty lines and
+/// line endings are left exactly as they were.
+pub fn lines() -> Regex {
+    Regex::new(r"[^\r\n]+").unwrap()
+}
+
+/// Applies `transform` to every region of `text` matched by `pattern`,
+/// leaving everything outside the matches untouched.
+///
+/// If the pattern has a capture group, only the first group of each match
+/// is transformed, so `<secret>(.*?)</secret>` keeps the tags in clear text.
+/// Otherwise the whole match is transformed. Each region is transformed
+/// independently, so keyed ciphers restart their key at every region.
+///
+/// On decrypt the same pattern must match the same regions of the ciphertext,
+/// which means the transformed text must not itself create or break matches.
+///
+/// # Arguments
+/// * `text` - The full input text.
+/// * `pattern` - Regex selecting the regions to transform.
+/// * `transform` - Function applied to each selected region.
+///
+/// # Returns
+/// A `String` with every selected region replaced by its transformed form.
+pub fn apply_to_regions<F>(text: &str, pattern: &Regex, transform: F) -> String
+where
+    F: Fn(&str) -> String,
+{
+    let mut output = String::with_capacity(text.len());
+    let mut last_end = 0;
+
+    for captures in pattern.captures_iter(text) {
+        let region = match captures.get(1) {
+            Some(group) => group,
+            None => captures.get(0).unwrap(),
+        };
+        output.push_str(&text[last_end..region.start()]);
+        output.push_str(&transform(region.as_str()));
+        last_end = region.end();
+    }
+    output.push_str(&text[last_end..]);
+
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ciphers::{CaesarCipher, Cipher, VigenereCipher};
+
+    #[test]
+    fn test_apply_to_whole_matches() {
+        let pattern = Regex::new("[aeiou]+").unwrap();
+        let output = apply_to_regions("beautiful day", &pattern, |s| s.to_uppercase());
+
+        assert_eq!(output, "bEAUtIfUl dAy");
+    }
+
+    #[test]
+    fn test_apply_to_capture_group() {
+        let pattern = Regex::new("<secret>(.*?)</secret>").unwrap();
+        let cipher = CaesarCipher { key: 3 };
+        let ciphertext =
+            apply_to_regions("meet <secret>at noon</secret> by the dock", &pattern, |s| {
+                cipher.encrypt(s)
+            });
+        let plaintext = apply_to_regions(&ciphertext, &pattern, |s| cipher.decrypt(s));
+
+        assert_eq!(ciphertext, "meet <secret>dw qrrq</secret> by the dock");
+        assert_eq!(plaintext, "meet <secret>at noon</secret> by the dock");
+    }
+
+    #[test]
+    fn test_lines_restart_key_and_keep_endings() {
+        let cipher = VigenereCipher::new("key".to_string());
+        let text = "hello\r\n\nhello\nworld\r\n";
+        let ciphertext = apply_to_regions(text, &lines(), |s| cipher.encrypt(s));
+        let plaintext = apply_to_regions(&ciphertext, &lines(), |s| cipher.decrypt(s));
+
+        assert_eq!(ciphertext, "rijvs\r\n\nrijvs\ngspvh\r\n");
+        assert_eq!(plaintext, text);
+    }
+}
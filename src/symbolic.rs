@@ -0,0 +1,138 @@
+//! The `symbolic` module resolves named Caesar keys like `today` into shifts
+//!
+//! The names are resolved against the current UTC date, so encrypting and
+//! decrypting with the same name on the same day round-trips.
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// A calendar date with its ISO weekday.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Date {
+    pub year: i64,
+    pub month: u32,
+    pub day: u32,
+    // 1 for Monday through 7 for Sunday
+    pub weekday: u32,
+}
+
+impl Date {
+    /// Converts a count of days since 1970-01-01 into a date.
+    ///
+    /// # Arguments
+    /// * `days` - Days since the Unix epoch; negative for earlier dates.
+    ///
+    /// # Returns
+    /// The proleptic Gregorian date.
+    ///
+    /// # Examples
+    /// ```
+    /// let date = Date::from_days(0);
+    /// assert_eq!((date.year, date.month, date.day, date.weekday), (1970, 1, 1, 4));
+    /// ```
+    pub fn from_days(days: i64) -> Date {
+        // Shift the epoch to 0000-03-01 so leap days fall at the end of each year
+        let days = days + 719_468;
+        let era = days.div_euclid(146_097);
+        let day_of_era = days.rem_euclid(146_097);
+        let year_of_era =
+            (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+        let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+        let month_index = (5 * day_of_year + 2) / 153;
+        let day = (day_of_year - (153 * month_index + 2) / 5 + 1) as u32;
+        let month = if month_index < 10 {
+            month_index + 3
+        } else {
+            month_index - 9
+        } as u32;
+        let year = year_of_era + era * 400 + i64::from(month <= 2);
+
+        Date {
+            year,
+            month,
+            day,
+            weekday: ((days - 719_468 + 3).rem_euclid(7) + 1) as u32,
+        }
+    }
+
+    /// The current date in UTC.
+    pub fn today() -> Date {
+        let seconds = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_secs() as i64)
+            .unwrap_or(0);
+        Date::from_days(seconds.div_euclid(86_400))
+    }
+}
+
+/// Resolves a symbolic key into a Caesar shift.
+///
+/// `today` is the day of the month mod 26 and `dow` is the ISO weekday,
+/// 1 for Monday through 7 for Sunday.
+///
+/// # Arguments
+/// * `name` - The key as given; matched case-insensitively.
+/// * `date` - The date to resolve against.
+///
+/// # Returns
+/// The shift, or `None` if the key isn't a symbolic name.
+///
+/// # Examples
+/// ```
+/// let date = Date::from_days(0);
+/// assert_eq!(resolve("today", &date), Some(1));
+/// assert_eq!(resolve("dow", &date), Some(4));
+/// ```
+pub fn resolve(name: &str, date: &Date) -> Option<i32> {
+    match name.to_ascii_lowercase().as_str() {
+        "today" => Some((date.day % 26) as i32),
+        "dow" => Some(date.weekday as i32),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_days() {
+        let epoch = Date::from_days(0);
+        let leap_day = Date::from_days(19_782);
+        let before_epoch = Date::from_days(-1);
+
+        assert_eq!(
+            epoch,
+            Date {
+                year: 1970,
+                month: 1,
+                day: 1,
+                weekday: 4
+            }
+        );
+        assert_eq!(
+            leap_day,
+            Date {
+                year: 2024,
+                month: 2,
+                day: 29,
+                weekday: 4
+            }
+        );
+        assert_eq!((before_epoch.year, before_epoch.weekday), (1969, 3));
+    }
+
+    #[test]
+    fn test_resolve_with_fixed_date() {
+        let date = Date {
+            year: 2024,
+            month: 3,
+            day: 28,
+            weekday: 4,
+        };
+
+        assert_eq!(resolve("today", &date), Some(2));
+        assert_eq!(resolve("TODAY", &date), Some(2));
+        assert_eq!(resolve("dow", &date), Some(4));
+        assert_eq!(resolve("lemon", &date), None);
+    }
+}
@@ -0,0 +1,175 @@
+//! The `transform` module provides keyless text transforms that chain with the ciphers
+//!
+//! A transform runs as the first layer of the chain: on the plaintext before
+//! the ciphers when encrypting, and on their output when decrypting. The
+//! digit shift is the exception, keyed separately and run as the last layer.
+
+use crate::ciphers::Cipher;
+
+/// Transforms selectable with `--transform`.
+#[derive(clap::ArgEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Transform {
+    #[clap(name = "swap-case")]
+    SwapCase,
+}
+
+impl Transform {
+    /// The transform as a layer of the cipher chain.
+    pub fn layer(self) -> Box<dyn Cipher> {
+        match self {
+            Transform::SwapCase => Box::new(SwapCase),
+        }
+    }
+}
+
+/// Swaps the case of a letter, or returns it as is when it has no single
+/// letter of the other case to swap back from, e.g. `ß`.
+fn swap_letter(c: char) -> char {
+    let swapped: Vec<char> = match (c.is_lowercase(), c.is_uppercase()) {
+        (true, _) => c.to_uppercase().collect(),
+        (_, true) => c.to_lowercase().collect(),
+        _ => return c,
+    };
+    let restored: Vec<char> = match (swapped.as_slice(), c.is_lowercase()) {
+        ([s], true) => s.to_lowercase().collect(),
+        ([s], false) => s.to_uppercase().collect(),
+        _ => return c,
+    };
+    match restored == [c] {
+        true => swapped[0],
+        false => c,
+    }
+}
+
+/// Inverts the case of every letter.
+///
+/// # Examples
+/// ```
+/// assert_eq!(swap_case("Hello, World"), "hELLO, wORLD");
+/// ```
+pub fn swap_case(text: &str) -> String {
+    text.chars().map(swap_letter).collect()
+}
+
+/// A `SwapCase` layer inverts the case of every letter; it is its own inverse
+pub struct SwapCase;
+
+impl Cipher for SwapCase {
+    fn name(&self) -> &'static str {
+        "Swap case"
+    }
+
+    fn encrypt(&self, plaintext: &str) -> String {
+        swap_case(plaintext)
+    }
+
+    fn decrypt(&self, ciphertext: &str) -> String {
+        swap_case(ciphertext)
+    }
+
+    /// Only letters with a counterpart in the other case change.
+    fn transforms(&self, c: char) -> bool {
+        swap_letter(c) != c
+    }
+}
+
+/// A `DigitShift` layer shifts each digit by its own key, modulo 10, so
+/// the digits of an alphanumeric text get enciphered apart from the letters
+pub struct DigitShift {
+    pub shift: u32,
+}
+
+impl DigitShift {
+    /// Builds a digit shift from a key of any sign, reduced modulo 10.
+    pub fn new(key: i32) -> Self {
+        DigitShift {
+            shift: key.rem_euclid(10) as u32,
+        }
+    }
+
+    fn shift_digits(&self, text: &str, shift: u32) -> String {
+        text.chars()
+            .map(|c| match c.to_digit(10) {
+                Some(digit) => char::from_digit((digit + shift) % 10, 10).unwrap(),
+                None => c,
+            })
+            .collect()
+    }
+}
+
+impl Cipher for DigitShift {
+    fn name(&self) -> &'static str {
+        "Digit shift"
+    }
+
+    fn encrypt(&self, plaintext: &str) -> String {
+        self.shift_digits(plaintext, self.shift)
+    }
+
+    fn decrypt(&self, ciphertext: &str) -> String {
+        self.shift_digits(ciphertext, (10 - self.shift) % 10)
+    }
+
+    /// Only ASCII digits change, and none do with a shift of 0.
+    fn transforms(&self, c: char) -> bool {
+        self.shift != 0 && c.is_ascii_digit()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ciphers::CaesarCipher;
+
+    #[test]
+    fn test_swap_case_is_its_own_inverse() {
+        let text = "Attack at Dawn, ÉCOLE straße 42";
+
+        assert_eq!(
+            swap_case(text),
+            "aTTACK AT dAWN, école STRASSE 42".replace("SS", "ß")
+        );
+        assert_eq!(swap_case(&swap_case(text)), text);
+    }
+
+    #[test]
+    fn test_swap_case_composes_with_caesar() {
+        let chain: Vec<Box<dyn Cipher>> = vec![
+            Transform::SwapCase.layer(),
+            Box::new(CaesarCipher { key: 3 }),
+        ];
+        let plaintext = "Attack at Dawn";
+
+        let ciphertext = chain
+            .iter()
+            .fold(plaintext.to_string(), |text, layer| layer.encrypt(&text));
+        let decrypted = chain
+            .iter()
+            .rev()
+            .fold(ciphertext.clone(), |text, layer| layer.decrypt(&text));
+
+        assert_eq!(ciphertext, "dWWDFN DW gDZQ");
+        assert_eq!(decrypted, plaintext);
+    }
+
+    #[test]
+    fn test_digit_shift_composes_with_caesar() {
+        let letters = CaesarCipher { key: 3 };
+        let digits = DigitShift::new(4);
+        let chain: [&dyn Cipher; 2] = [&letters, &digits];
+
+        let ciphertext = chain
+            .iter()
+            .fold("abc123".to_string(), |text, layer| layer.encrypt(&text));
+        let decrypted = chain
+            .iter()
+            .rev()
+            .fold(ciphertext.clone(), |text, layer| layer.decrypt(&text));
+
+        assert_eq!(ciphertext, "def567");
+        assert_eq!(decrypted, "abc123");
+        assert_eq!(letters.decrypt(&ciphertext), "abc567");
+        assert_eq!(digits.decrypt(&ciphertext), "def123");
+        assert_eq!(DigitShift::new(-3).encrypt("0, 9!"), "7, 6!");
+    }
+}
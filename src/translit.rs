@@ -0,0 +1,103 @@
+//! The `translit` module spells out letters the ASCII-only ciphers would
+//! otherwise skip, using a per-language transliteration table
+//!
+//! Transliteration is lossy: "ae" can't be told apart from an original "ä",
+//! so decrypting gives back the transliterated text, not the original.
+
+/// Languages with a transliteration table.
+#[derive(clap::ArgEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Language {
+    // German umlauts and sharp s
+    #[clap(name = "de")]
+    German,
+}
+
+/// German replacements, including the capital sharp s.
+const GERMAN: [(char, &str); 8] = [
+    ('ä', "ae"),
+    ('ö', "oe"),
+    ('ü', "ue"),
+    ('Ä', "Ae"),
+    ('Ö', "Oe"),
+    ('Ü', "Ue"),
+    ('ß', "ss"),
+    ('ẞ', "SS"),
+];
+
+/// Combining diaeresis, which follows the base letter of a decomposed umlaut.
+const COMBINING_DIAERESIS: char = '\u{308}';
+
+impl Language {
+    /// The replacement table for the language.
+    fn table(&self) -> &'static [(char, &'static str)] {
+        match self {
+            Language::German => &GERMAN,
+        }
+    }
+}
+
+/// Replaces every character in the language's table with its spelling.
+///
+/// Umlauts typed as a base letter plus a combining diaeresis are
+/// recognized too. Everything else is left as it is.
+///
+/// # Arguments
+/// * `text` - The text to transliterate.
+/// * `language` - Whose table to use.
+///
+/// # Returns
+/// The transliterated text.
+///
+/// # Examples
+/// ```
+/// assert_eq!(transliterate("Grüße", Language::German), "Gruesse");
+/// ```
+pub fn transliterate(text: &str, language: Language) -> String {
+    let table = language.table();
+    let lookup = |c: char| table.iter().find(|(from, _)| *from == c).map(|(_, to)| *to);
+
+    let mut output = String::with_capacity(text.len());
+    let mut chars = text.chars().peekable();
+    while let Some(c) = chars.next() {
+        if chars.peek() == Some(&COMBINING_DIAERESIS) {
+            let composed = match c {
+                'a' => Some('ä'),
+                'o' => Some('ö'),
+                'u' => Some('ü'),
+                'A' => Some('Ä'),
+                'O' => Some('Ö'),
+                'U' => Some('Ü'),
+                _ => None,
+            };
+            if let Some(replacement) = composed.and_then(lookup) {
+                chars.next();
+                output.push_str(replacement);
+                continue;
+            }
+        }
+
+        match lookup(c) {
+            Some(replacement) => output.push_str(replacement),
+            None => output.push(c),
+        }
+    }
+
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_transliterate_german() {
+        let phrase = transliterate("Über Größe läßt sich streiten", Language::German);
+        let decomposed = transliterate("U\u{308}bung", Language::German);
+        let capital = transliterate("STRAẞE", Language::German);
+
+        assert_eq!(phrase, "Ueber Groesse laesst sich streiten");
+        assert_eq!(decomposed, "Uebung");
+        assert_eq!(capital, "STRASSE");
+        assert_eq!(transliterate("café", Language::German), "café");
+    }
+}
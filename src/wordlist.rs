@@ -0,0 +1,289 @@
+//! The `wordlist` module brute forces text-keyed ciphers by trying every word of a wordlist as the key
+
+use crate::analysis;
+use crate::ciphers::Cipher;
+use std::io::{self, BufRead};
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// A key tried during the wordlist attack and how English-like its decryption scored.
+#[derive(Debug)]
+pub struct Candidate {
+    pub key: String,
+    pub score: f64,
+    pub plaintext: String,
+}
+
+/// Decrypts the ciphertext with every word of the wordlist as the key and
+/// keeps the best scoring candidates.
+///
+/// The wordlist is streamed line by line, so only the current top
+/// candidates (and, with the `parallel` feature, one batch of keys) are
+/// held in memory. Blank lines and words that aren't
+/// valid keys are skipped. The search stops early once `cancel` is set,
+/// returning the best candidates found so far.
+///
+/// # Arguments
+/// * `reader` - Source of the wordlist, one key per line.
+/// * `ciphertext` - The text to decrypt.
+/// * `build` - Builds the cipher for a candidate key, or `None` if it isn't a valid key.
+/// * `top` - Number of candidates to keep.
+/// * `cancel` - Checked before every key; set it to stop the search.
+///
+/// # Returns
+/// The best candidates, highest score first, or the error from reading the wordlist.
+pub fn rank_keys<R, F>(
+    reader: R,
+    ciphertext: &str,
+    build: F,
+    top: usize,
+    cancel: &AtomicBool,
+) -> io::Result<Vec<Candidate>>
+where
+    R: BufRead,
+    F: Fn(&str) -> Option<Box<dyn Cipher>> + Sync,
+{
+    rank_candidates(reader.lines(), ciphertext, build, top, cancel)
+}
+
+/// Decrypts the ciphertext with every key yielded by `keys` and keeps the
+/// best scoring candidates, like `rank_keys` does for a wordlist.
+///
+/// With the `parallel` feature the keys are read in batches and each batch
+/// is split into chunks ranked on their own threads. The chunk results are
+/// merged in the order the keys came in, so ties break the same way as in
+/// the single-threaded search and both return the same candidates.
+///
+/// # Arguments
+/// * `keys` - The keys to try, or the error that ends the search.
+/// * `ciphertext` - The text to decrypt.
+/// * `build` - Builds the cipher for a candidate key, or `None` if it isn't a valid key.
+/// * `top` - Number of candidates to keep.
+/// * `cancel` - Checked before every key; set it to stop the search.
+///
+/// # Returns
+/// The best candidates, highest score first, or the first error from `keys`.
+pub fn rank_candidates<I, F>(
+    keys: I,
+    ciphertext: &str,
+    build: F,
+    top: usize,
+    cancel: &AtomicBool,
+) -> io::Result<Vec<Candidate>>
+where
+    I: IntoIterator<Item = io::Result<String>>,
+    F: Fn(&str) -> Option<Box<dyn Cipher>> + Sync,
+{
+    #[cfg(feature = "parallel")]
+    return rank_parallel(keys, ciphertext, &build, top, cancel);
+    #[cfg(not(feature = "parallel"))]
+    return rank_serial(keys, ciphertext, &build, top, cancel);
+}
+
+/// Single-threaded body of `rank_candidates`.
+fn rank_serial<I, F>(
+    keys: I,
+    ciphertext: &str,
+    build: &F,
+    top: usize,
+    cancel: &AtomicBool,
+) -> io::Result<Vec<Candidate>>
+where
+    I: IntoIterator<Item = io::Result<String>>,
+    F: Fn(&str) -> Option<Box<dyn Cipher>>,
+{
+    let mut candidates: Vec<Candidate> = Vec::with_capacity(top + 1);
+
+    for line in keys {
+        if cancel.load(Ordering::Relaxed) {
+            break;
+        }
+        if let Some(candidate) = try_key(line?.trim(), ciphertext, build) {
+            insert_candidate(&mut candidates, candidate, top);
+        }
+    }
+
+    Ok(candidates)
+}
+
+/// Keys read from the source before they are handed out to the threads.
+#[cfg(feature = "parallel")]
+const PARALLEL_BATCH: usize = 16 * 1024;
+
+/// Keys ranked by one thread at a time.
+#[cfg(feature = "parallel")]
+const PARALLEL_CHUNK: usize = 256;
+
+/// Multi-threaded body of `rank_candidates`.
+#[cfg(feature = "parallel")]
+fn rank_parallel<I, F>(
+    keys: I,
+    ciphertext: &str,
+    build: &F,
+    top: usize,
+    cancel: &AtomicBool,
+) -> io::Result<Vec<Candidate>>
+where
+    I: IntoIterator<Item = io::Result<String>>,
+    F: Fn(&str) -> Option<Box<dyn Cipher>> + Sync,
+{
+    use rayon::prelude::*;
+
+    let mut candidates: Vec<Candidate> = Vec::with_capacity(top + 1);
+    let mut keys = keys.into_iter();
+
+    while !cancel.load(Ordering::Relaxed) {
+        let batch = keys
+            .by_ref()
+            .take(PARALLEL_BATCH)
+            .collect::<io::Result<Vec<String>>>()?;
+        if batch.is_empty() {
+            break;
+        }
+
+        let ranked = batch
+            .par_chunks(PARALLEL_CHUNK)
+            .map(|chunk| {
+                let chunk = chunk.iter().map(|key| Ok(key.clone()));
+                rank_serial(chunk, ciphertext, build, top, cancel)
+            })
+            .collect::<io::Result<Vec<Vec<Candidate>>>>()?;
+        for candidate in ranked.into_iter().flatten() {
+            insert_candidate(&mut candidates, candidate, top);
+        }
+    }
+
+    Ok(candidates)
+}
+
+/// Decrypts the ciphertext with one key and scores the result, or returns
+/// `None` for a blank or invalid key.
+fn try_key<F>(key: &str, ciphertext: &str, build: &F) -> Option<Candidate>
+where
+    F: Fn(&str) -> Option<Box<dyn Cipher>>,
+{
+    if key.is_empty() {
+        return None;
+    }
+    let plaintext = build(key)?.decrypt(ciphertext);
+    Some(Candidate {
+        key: key.to_string(),
+        score: analysis::english_score(&plaintext),
+        plaintext,
+    })
+}
+
+/// Inserts the candidate after every candidate scoring at least as well,
+/// keeping at most `top` of them.
+fn insert_candidate(candidates: &mut Vec<Candidate>, candidate: Candidate, top: usize) {
+    let position = candidates
+        .iter()
+        .position(|other| other.score < candidate.score)
+        .unwrap_or(candidates.len());
+    if position < top {
+        candidates.insert(position, candidate);
+        candidates.truncate(top);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ciphers::VigenereCipher;
+
+    #[test]
+    fn test_rank_keys() {
+        let cipher = VigenereCipher::new("lemon".to_string());
+        let ciphertext =
+            cipher.encrypt("meet me at the old bridge at noon and bring the documents");
+        let wordlist = "apple\nsecret\n\n123\nlemon\norange\nkey\n";
+
+        let candidates = rank_keys(
+            wordlist.as_bytes(),
+            &ciphertext,
+            |key| {
+                let cipher = VigenereCipher::new(key.to_string());
+                match cipher.key.is_empty() {
+                    true => None,
+                    false => Some(Box::new(cipher) as Box<dyn Cipher>),
+                }
+            },
+            3,
+            &AtomicBool::new(false),
+        )
+        .unwrap();
+
+        assert_eq!(candidates.len(), 3);
+        assert_eq!(candidates[0].key, "lemon");
+        assert_eq!(
+            candidates[0].plaintext,
+            "meet me at the old bridge at noon and bring the documents"
+        );
+    }
+
+    #[test]
+    fn test_rank_keys_keeps_best_so_far_on_cancel() {
+        let cipher = VigenereCipher::new("lemon".to_string());
+        let ciphertext = cipher.encrypt("meet me at the old bridge at noon");
+        let wordlist = "apple\nlemon\norange\nkey\n";
+        let cancel = AtomicBool::new(false);
+        let tried = std::sync::Mutex::new(Vec::new());
+
+        let candidates = rank_keys(
+            wordlist.as_bytes(),
+            &ciphertext,
+            |key| {
+                tried.lock().unwrap().push(key.to_string());
+                // Simulates Ctrl-C arriving while "lemon" is being tried
+                if key == "lemon" {
+                    cancel.store(true, Ordering::Relaxed);
+                }
+                Some(Box::new(VigenereCipher::new(key.to_string())) as Box<dyn Cipher>)
+            },
+            3,
+            &cancel,
+        )
+        .unwrap();
+
+        assert_eq!(tried.into_inner().unwrap(), vec!["apple", "lemon"]);
+        assert_eq!(candidates.len(), 2);
+        assert_eq!(candidates[0].key, "lemon");
+        assert_eq!(candidates[0].plaintext, "meet me at the old bridge at noon");
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn test_parallel_search_matches_single_threaded() {
+        let cipher = VigenereCipher::new("quartz".to_string());
+        let ciphertext = cipher.encrypt("the quick brown fox jumps over the lazy dog at dawn");
+        // Enough keys for several chunks, with repeats so that ties must
+        // break the same way in both searches
+        let keys: Vec<String> = (0..5000)
+            .map(|i| format!("k{}ey", ["a", "b", "c", "d", "e", "f", "g"][i % 7]))
+            .chain(["quartz".to_string(), "quarts".to_string()])
+            .collect();
+        let build =
+            |key: &str| Some(Box::new(VigenereCipher::new(key.to_string())) as Box<dyn Cipher>);
+        let single = rank_serial(
+            keys.iter().cloned().map(Ok),
+            &ciphertext,
+            &build,
+            5,
+            &AtomicBool::new(false),
+        )
+        .unwrap();
+        let parallel = rank_parallel(
+            keys.iter().cloned().map(Ok),
+            &ciphertext,
+            &build,
+            5,
+            &AtomicBool::new(false),
+        )
+        .unwrap();
+
+        assert_eq!(parallel[0].key, "quartz");
+        assert_eq!(
+            parallel.iter().map(|c| &c.key).collect::<Vec<_>>(),
+            single.iter().map(|c| &c.key).collect::<Vec<_>>()
+        );
+    }
+}
@@ -0,0 +1,1844 @@
+use std::process::Command;
+
+fn run(args: &[&str]) -> String {
+    let output = Command::new(env!("CARGO_BIN_EXE_crypto_cli_tool"))
+        .args(args)
+        .output()
+        .expect("failed to run binary");
+
+    assert!(output.status.success(), "{:?} failed", args);
+    String::from_utf8(output.stdout).unwrap()
+}
+
+/// Runs the binary expecting it to fail, returning the exit code and stderr.
+fn run_failure(args: &[&str]) -> (Option<i32>, String) {
+    let output = Command::new(env!("CARGO_BIN_EXE_crypto_cli_tool"))
+        .args(args)
+        .output()
+        .expect("failed to run binary");
+
+    assert!(!output.status.success(), "{:?} succeeded", args);
+    (
+        output.status.code(),
+        String::from_utf8(output.stderr).unwrap(),
+    )
+}
+
+/// The values `--help` lists for `--algorithm`.
+fn algorithm_names() -> Vec<String> {
+    let help = run(&["--help"]);
+    let section = help.split("--algorithm <ALGORITHM>").nth(1).unwrap();
+    let values = section
+        .split("[possible values: ")
+        .nth(1)
+        .unwrap()
+        .split(']')
+        .next()
+        .unwrap();
+    values
+        .split(',')
+        .map(|name| name.trim().to_string())
+        .collect()
+}
+
+#[test]
+fn test_each_algorithm_round_trip() {
+    // Ciphers that drop spaces or case give the plaintext back normalized
+    let cases = [
+        (
+            &["-a", "caesar", "-k", "3"][..],
+            "dwwdfn dw gdzq",
+            "attack at dawn",
+        ),
+        (
+            &["-a", "vigenere", "-k", "lemon"][..],
+            "lxfopv mh oeib",
+            "attack at dawn",
+        ),
+        (
+            &["-a", "playfair", "-k", "keyword"][..],
+            "rvvrrorvabeq",
+            "attackatdawn",
+        ),
+        (
+            &["-a", "columnar", "-k", "tomato"][..],
+            "a tttankaa wcd",
+            "attack at dawn",
+        ),
+        (
+            &["-a", "railfence", "-k", "3"][..],
+            "actwtaka ant d",
+            "attack at dawn",
+        ),
+        (
+            &["-a", "bifid", "-k", "key"][..],
+            "apgzpk qk tgwn",
+            "attack at dawn",
+        ),
+        (
+            &["-a", "trifid", "-k", "abcdefghijklmnopqrstuvwxyz."][..],
+            "iaabll ab djwn",
+            "attack at dawn",
+        ),
+        (
+            &["-a", "quagmire1", "-k", "key", "--key2", "flower"][..],
+            "fcfwgo fc rwyc",
+            "attack at dawn",
+        ),
+        (
+            &[
+                "-a",
+                "vic",
+                "-k",
+                "77651",
+                "--key2",
+                "idreamofjeaniewiththelightbrownhair",
+            ][..],
+            "74218900697094746954",
+            "ATTACK AT DAWN",
+        ),
+        (
+            &["-a", "gromark", "-k", "23452", "--key2", "enigma"][..],
+            "rclbeo sr fstq",
+            "attack at dawn",
+        ),
+        (
+            &["-a", "alberti", "-k", "key", "--key2", "k"][..],
+            "KphhprLz og roMie",
+            "ATTACK AT DAWN",
+        ),
+        (
+            &["-a", "shuffle", "-k", "key"][..],
+            " atdntwta kaca",
+            "attack at dawn",
+        ),
+        (
+            &["-a", "bazeries", "-k", "3752"][..],
+            "KKTTBKTPVTID",
+            "ATTACKATDAWN",
+        ),
+        (
+            &["-a", "digrafid", "-k", "key", "--key2", "vocabulary"][..],
+            "AWUCDFHGPGWN",
+            "ATTACKATDAWN",
+        ),
+        (
+            &["-a", "xor", "-k", "key"][..],
+            "0a110d0a06124b040d4b01181c0b",
+            "attack at dawn",
+        ),
+        (
+            &["-a", "byte-caesar", "-k", "3"][..],
+            "64777764666e2364772367647a71",
+            "attack at dawn",
+        ),
+        (&["-a", "rot18"][..], "nggnpx ng qnja", "attack at dawn"),
+        (&["-a", "rot47"][..], "2EE24< 2E 52H?", "attack at dawn"),
+        (
+            &["-a", "unicode-rot", "-k", "3"][..],
+            "dwwdfn#dw#gdzq",
+            "attack at dawn",
+        ),
+    ];
+
+    let covered: Vec<&str> = cases.iter().map(|(algorithm, _, _)| algorithm[1]).collect();
+    for name in algorithm_names() {
+        assert!(covered.contains(&name.as_str()), "{} isn't covered", name);
+    }
+
+    for (algorithm, ciphertext, plaintext) in cases {
+        let encrypted = run(&[algorithm, &["-d", "encrypt", "attack at dawn"]].concat());
+        let decrypted = run(&[algorithm, &["-d", "decrypt", ciphertext]].concat());
+
+        assert!(
+            encrypted.contains(&format!("Output: {}\n", ciphertext)),
+            "{:?} encrypted to {}",
+            algorithm,
+            encrypted
+        );
+        assert!(
+            decrypted.contains(&format!("Output: {}\n", plaintext)),
+            "{:?} decrypted to {}",
+            algorithm,
+            decrypted
+        );
+    }
+}
+
+#[test]
+fn test_chain_round_trip() {
+    let chain = ["-a", "caesar,vigenere", "-k", "3", "-k", "lemon"];
+    let encrypted = run(&[&chain[..], &["-d", "encrypt", "attack at dawn"]].concat());
+    let ciphertext = encrypted
+        .lines()
+        .find_map(|line| line.strip_prefix("Output: "))
+        .unwrap();
+    let decrypted = run(&[&chain[..], &["-d", "decrypt", ciphertext]].concat());
+
+    assert_ne!(ciphertext, "attack at dawn");
+    assert!(
+        decrypted.contains("Output: attack at dawn\n"),
+        "{}",
+        decrypted
+    );
+}
+
+#[test]
+fn test_playfair_happy_path() {
+    let encrypted = run(&[
+        "-a",
+        "playfair",
+        "-d",
+        "encrypt",
+        "-k",
+        "keyword",
+        "ATTACKATDAWN",
+    ]);
+    let decrypted = run(&[
+        "-a",
+        "playfair",
+        "-d",
+        "decrypt",
+        "-k",
+        "keyword",
+        "RVVRRORVABEQ",
+    ]);
+
+    assert_eq!(
+        encrypted,
+        "Algorithm: Playfair cipher\nDirection: Encrypt\nOutput: RVVRRORVABEQ\n\n"
+    );
+    assert!(decrypted.contains("Output: ATTACKATDAWN\n"));
+}
+
+#[test]
+fn test_brute_force_with_encrypt_is_rejected() {
+    let (code, stderr) = run_failure(&["-a", "caesar", "-d", "encrypt", "-b", "hello"]);
+
+    assert_eq!(code, Some(1));
+    assert!(stderr.contains("Brute force mode cannot be used with encryption."));
+}
+
+#[test]
+fn test_mismatched_key_type_is_rejected() {
+    let (text_for_caesar, caesar_stderr) =
+        run_failure(&["-a", "caesar", "-d", "encrypt", "-k", "abc", "hello"]);
+    let (integer_for_vigenere, vigenere_stderr) =
+        run_failure(&["-a", "vigenere", "-d", "encrypt", "-k", "3", "hello"]);
+
+    assert_eq!(text_for_caesar, Some(2));
+    assert!(caesar_stderr.contains("Caesar cipher requires an integer key."));
+    assert_eq!(integer_for_vigenere, Some(2));
+    assert!(vigenere_stderr.contains("Vigenère cipher requires a text key."));
+}
+
+#[test]
+fn test_negative_caesar_key() {
+    let short = run(&["-a", "caesar", "-d", "decrypt", "-k", "-3", "abc"]);
+    let long = run(&["-a", "caesar", "-d", "decrypt", "--key=-3", "abc"]);
+    let min = run(&["-a", "caesar", "-d", "decrypt", "--key=-2147483648", "hi"]);
+
+    assert!(short.contains("Output: def\n"));
+    assert!(long.contains("Output: def\n"));
+    assert!(min.contains("Output: fg\n"), "{}", min);
+}
+
+#[test]
+fn test_caesar_brute_force() {
+    let output = run(&["-a", "caesar", "-d", "decrypt", "-b", "khoor"]);
+
+    assert!(output.contains("Key 3: hello\n"));
+    assert!(output.contains("Key 25: lipps\n"));
+}
+
+#[test]
+fn test_brute_force_refused_for_unbounded_key_space() {
+    let output = Command::new(env!("CARGO_BIN_EXE_crypto_cli_tool"))
+        .args(["-a", "vigenere", "-d", "decrypt", "-b", "khoor"])
+        .output()
+        .expect("failed to run binary");
+    let stderr = String::from_utf8(output.stderr).unwrap();
+
+    assert!(!output.status.success());
+    assert!(stderr.contains("key space is too large"));
+}
+
+#[test]
+fn test_time_flag() {
+    let output = Command::new(env!("CARGO_BIN_EXE_crypto_cli_tool"))
+        .args(["-a", "caesar", "-d", "encrypt", "-k", "3", "--time", "abc"])
+        .output()
+        .expect("failed to run binary");
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    let elapsed: f64 = stderr
+        .trim()
+        .strip_prefix("Elapsed: ")
+        .and_then(|line| line.strip_suffix(" ms"))
+        .expect("missing elapsed line")
+        .parse()
+        .unwrap();
+
+    assert!(output.status.success());
+    assert!(stdout.contains("Output: def\n"));
+    assert!(!stdout.contains("Elapsed"));
+    assert!(elapsed >= 0.0);
+}
+
+#[test]
+fn test_verbosity_levels() {
+    let quiet = Command::new(env!("CARGO_BIN_EXE_crypto_cli_tool"))
+        .args(["-a", "vigenere", "-d", "encrypt", "-k", "k3y!", "secret"])
+        .output()
+        .expect("failed to run binary");
+    let verbose = Command::new(env!("CARGO_BIN_EXE_crypto_cli_tool"))
+        .args([
+            "-a", "vigenere", "-d", "encrypt", "-k", "k3y!", "-vv", "secret",
+        ])
+        .output()
+        .expect("failed to run binary");
+    let quiet_stderr = String::from_utf8(quiet.stderr).unwrap();
+    let verbose_stderr = String::from_utf8(verbose.stderr).unwrap();
+
+    assert!(!quiet_stderr.contains("stripped non-letter characters"));
+    assert!(verbose_stderr.contains("stripped non-letter characters"));
+    assert!(verbose_stderr.contains("info: Vigenère"));
+}
+
+#[test]
+fn test_verbose_key_names_cipher() {
+    let output = Command::new(env!("CARGO_BIN_EXE_crypto_cli_tool"))
+        .args([
+            "-a",
+            "railfence",
+            "-d",
+            "encrypt",
+            "-k",
+            "3",
+            "-vv",
+            "hello",
+        ])
+        .output()
+        .expect("failed to run binary");
+    let stderr = String::from_utf8(output.stderr).unwrap();
+
+    assert!(
+        stderr.contains("info: Rail fence cipher: key 3"),
+        "{}",
+        stderr
+    );
+}
+
+#[test]
+fn test_all_algorithms_with_integer_key() {
+    let output = run(&["--all-algorithms", "-d", "encrypt", "-k", "3", "hello"]);
+
+    assert!(output.contains("Caesar cipher: khoor\n"));
+    assert!(output.contains("Rail fence cipher: hoell\n"));
+    assert!(output.contains("Vigenère cipher: skipped (incompatible key)\n"));
+    assert!(output.contains("Playfair cipher: skipped (incompatible key)\n"));
+    assert!(output.contains("ROT18: skipped (incompatible key)\n"));
+}
+
+#[test]
+fn test_group_and_ungroup_round_trip() {
+    let encrypted = run(&[
+        "-a",
+        "playfair",
+        "-d",
+        "encrypt",
+        "-k",
+        "keyword",
+        "--group",
+        "5",
+        "MEETMEATNOON",
+    ]);
+    let ciphertext = encrypted
+        .lines()
+        .find_map(|line| line.strip_prefix("Output: "))
+        .unwrap();
+    let decrypted = run(&[
+        "-a",
+        "playfair",
+        "-d",
+        "decrypt",
+        "-k",
+        "keyword",
+        "--ungroup",
+        ciphertext,
+    ]);
+
+    assert_eq!(ciphertext.split(' ').next().unwrap().len(), 5);
+    assert!(decrypted.contains("Output: MEETMEATNOON\n"));
+}
+
+#[test]
+fn test_zero_group_rejected() {
+    let (code, stderr) = run_failure(&[
+        "-a", "caesar", "-d", "encrypt", "-k", "3", "--group", "0", "hi",
+    ]);
+
+    assert_eq!(code, Some(1));
+    assert!(stderr.contains("--group must be positive."), "{}", stderr);
+}
+
+#[test]
+fn test_zero_wrap_rejected() {
+    let (code, stderr) = run_failure(&[
+        "-a", "caesar", "-d", "encrypt", "-k", "3", "--wrap", "0", "hello",
+    ]);
+
+    assert_eq!(code, Some(1));
+    assert!(stderr.contains("--wrap must be positive."), "{}", stderr);
+}
+
+#[test]
+fn test_exit_code_for_invalid_key() {
+    let output = Command::new(env!("CARGO_BIN_EXE_crypto_cli_tool"))
+        .args(["-a", "caesar", "-d", "encrypt", "-k", "abc", "hello"])
+        .output()
+        .expect("failed to run binary");
+
+    assert_eq!(output.status.code(), Some(2));
+}
+
+#[test]
+fn test_exit_code_for_missing_input_file() {
+    let output = Command::new(env!("CARGO_BIN_EXE_crypto_cli_tool"))
+        .args(["-a", "caesar", "-d", "encrypt", "-k", "3"])
+        .args(["-i", "/nonexistent/crypto_cli_tool_input.txt"])
+        .output()
+        .expect("failed to run binary");
+
+    assert_eq!(output.status.code(), Some(3));
+}
+
+#[test]
+fn test_exit_code_for_usage_error() {
+    let output = Command::new(env!("CARGO_BIN_EXE_crypto_cli_tool"))
+        .args(["-a", "caesar", "hello"])
+        .output()
+        .expect("failed to run binary");
+
+    assert_eq!(output.status.code(), Some(1));
+}
+
+#[test]
+fn test_verify_flag() {
+    let caesar = Command::new(env!("CARGO_BIN_EXE_crypto_cli_tool"))
+        .args([
+            "-a", "caesar", "-d", "encrypt", "-k", "3", "--verify", "hello",
+        ])
+        .output()
+        .expect("failed to run binary");
+    let playfair = Command::new(env!("CARGO_BIN_EXE_crypto_cli_tool"))
+        .args(["-a", "playfair", "-d", "encrypt", "-k", "keyword"])
+        .args(["--verify", "HELLO"])
+        .output()
+        .expect("failed to run binary");
+
+    assert!(caesar.status.success());
+    assert!(caesar.stderr.is_empty());
+    assert!(String::from_utf8(playfair.stderr)
+        .unwrap()
+        .contains("round trip failed"));
+}
+
+#[test]
+fn test_strict_roundtrip() {
+    let caesar = run(&[
+        "-a",
+        "caesar",
+        "-d",
+        "encrypt",
+        "-k",
+        "3",
+        "--strict-roundtrip",
+        "hello",
+    ]);
+    let playfair = Command::new(env!("CARGO_BIN_EXE_crypto_cli_tool"))
+        .args(["-a", "playfair", "-d", "encrypt", "-k", "keyword"])
+        .args(["--strict-roundtrip", "BALLOON"])
+        .output()
+        .expect("failed to run binary");
+
+    assert!(caesar.contains("Output: khoor"));
+    assert_eq!(playfair.status.code(), Some(5));
+    assert!(playfair.stdout.is_empty());
+    assert!(String::from_utf8(playfair.stderr)
+        .unwrap()
+        .contains("--strict-roundtrip"));
+}
+
+#[test]
+fn test_xor_hex_input() {
+    let encrypted = run(&[
+        "-a",
+        "xor",
+        "-d",
+        "encrypt",
+        "-k",
+        "key",
+        "--input-format",
+        "hex",
+        "00ff10",
+    ]);
+    let decrypted = run(&[
+        "-a",
+        "xor",
+        "-d",
+        "decrypt",
+        "-k",
+        "key",
+        "--input-format",
+        "hex",
+        "030015070a",
+    ]);
+
+    assert!(encrypted.contains("Output: 6b9a69"));
+    assert!(decrypted.contains("Output: hello"));
+}
+
+#[test]
+fn test_xor_base64_byte_format() {
+    let output = run(&[
+        "-a",
+        "xor",
+        "-d",
+        "encrypt",
+        "-k",
+        "key",
+        "--input-format",
+        "hex",
+        "--byte-format",
+        "base64",
+        "00ff10",
+    ]);
+
+    assert!(output.contains("Output: a5pp"));
+}
+
+#[test]
+fn test_xor_streams_to_output_file() {
+    let dir = std::env::temp_dir().join("crypto_cli_tool_test_xor_output_file");
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("cipher.txt");
+    let output = Command::new(env!("CARGO_BIN_EXE_crypto_cli_tool"))
+        .args([
+            "-a",
+            "xor",
+            "-d",
+            "encrypt",
+            "-k",
+            "key",
+            "--input-format",
+            "hex",
+            "--byte-format",
+            "base64",
+            "--output-file",
+        ])
+        .arg(&path)
+        .arg("00ff10")
+        .output()
+        .expect("failed to run binary");
+    let contents = std::fs::read_to_string(&path).unwrap();
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert!(output.status.success());
+    assert!(output.stdout.is_empty());
+    assert_eq!(contents, "a5pp");
+}
+
+#[test]
+fn test_xor_decrypt_rejects_invalid_hex() {
+    let (code, stderr) = run_failure(&["-a", "xor", "-d", "decrypt", "-k", "key", "AwAVBwo="]);
+
+    assert_eq!(code, Some(4));
+    assert!(
+        stderr.contains("Could not decode hex ciphertext"),
+        "{}",
+        stderr
+    );
+}
+
+#[test]
+fn test_base32() {
+    let decoded = run(&[
+        "-a",
+        "caesar",
+        "-d",
+        "encrypt",
+        "-k",
+        "3",
+        "--input-format",
+        "base32",
+        "nbswy3dp",
+    ]);
+    let encoded = run(&[
+        "-a",
+        "xor",
+        "-d",
+        "encrypt",
+        "-k",
+        "key",
+        "--input-format",
+        "hex",
+        "--byte-format",
+        "base32",
+        "--no-padding",
+        "00ff10",
+    ]);
+
+    assert!(decoded.contains("Output: khoor"));
+    assert!(encoded.contains("Output: NONGS\n"));
+}
+
+#[test]
+fn test_base64_input_decodes_to_text_for_letter_ciphers() {
+    let output = run(&[
+        "-a",
+        "caesar",
+        "-d",
+        "encrypt",
+        "-k",
+        "3",
+        "--input-format",
+        "base64",
+        "aGVsbG8=",
+    ]);
+
+    assert!(output.contains("Output: khoor"));
+}
+
+#[test]
+fn test_playfair_punctuation() {
+    let dropped = run(&[
+        "-a",
+        "playfair",
+        "-d",
+        "encrypt",
+        "-k",
+        "keyword",
+        "HELLO, WORLD!",
+    ]);
+    let kept = run(&[
+        "-a",
+        "playfair",
+        "-d",
+        "encrypt",
+        "-k",
+        "keyword",
+        "--keep-punctuation",
+        "HELLO, WORLD!",
+    ]);
+
+    assert!(dropped.contains("Output: GYIZKOKCGC"));
+    assert!(kept.contains("Output: GYIZK, OKCGC!"));
+}
+
+#[test]
+fn test_dictionary_confidence() {
+    let path = std::env::temp_dir().join("crypto_cli_tool_test_dictionary.txt");
+    std::fs::write(&path, "hello\nworld\n").unwrap();
+    let output = Command::new(env!("CARGO_BIN_EXE_crypto_cli_tool"))
+        .args(["-a", "caesar", "-d", "decrypt", "-k", "3", "--dictionary"])
+        .arg(&path)
+        .arg("Khoor Zruog")
+        .output()
+        .expect("failed to run binary");
+    std::fs::remove_file(&path).unwrap();
+    let stderr = String::from_utf8(output.stderr).unwrap();
+
+    assert!(stderr.contains("Dictionary confidence: 100.0% (2/2 words)"));
+}
+
+#[test]
+fn test_unicode_rot_flag() {
+    let encrypted = run(&["--unicode-rot", "1", "-d", "encrypt", "\u{D7FF}a"]);
+    let decrypted = run(&["--unicode-rot", "1", "-d", "decrypt", "\u{E000}b"]);
+
+    assert!(encrypted.contains("Output: \u{E000}b"));
+    assert!(decrypted.contains("Output: \u{D7FF}a"));
+}
+
+#[test]
+fn test_max_memory_refuses_large_input() {
+    let path = std::env::temp_dir().join("crypto_cli_tool_test_max_memory.txt");
+    std::fs::write(&path, "wearediscoveredfleeatonce").unwrap();
+    let transposition = Command::new(env!("CARGO_BIN_EXE_crypto_cli_tool"))
+        .args(["-a", "columnar", "-d", "encrypt", "-k", "tomato"])
+        .args(["--max-memory", "10", "-i"])
+        .arg(&path)
+        .output()
+        .expect("failed to run binary");
+    let substitution = Command::new(env!("CARGO_BIN_EXE_crypto_cli_tool"))
+        .args(["-a", "caesar", "-d", "encrypt", "-k", "3"])
+        .args(["--max-memory", "10", "-i"])
+        .arg(&path)
+        .output()
+        .expect("failed to run binary");
+    std::fs::remove_file(&path).unwrap();
+    let stderr = String::from_utf8(transposition.stderr).unwrap();
+
+    assert_eq!(transposition.status.code(), Some(1));
+    assert!(stderr.contains("over the --max-memory limit"));
+    assert!(substitution.status.success());
+}
+
+#[test]
+fn test_brute_force_first_match() {
+    let path = std::env::temp_dir().join("crypto_cli_tool_test_first_match.txt");
+    std::fs::write(&path, "attack\nat\ndawn\n").unwrap();
+    let output = Command::new(env!("CARGO_BIN_EXE_crypto_cli_tool"))
+        .args([
+            "-a",
+            "caesar",
+            "-d",
+            "decrypt",
+            "-b",
+            "--first-match",
+            "--dictionary",
+        ])
+        .arg(&path)
+        .arg("dwwdfn dw gdzq")
+        .output()
+        .expect("failed to run binary");
+    std::fs::remove_file(&path).unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    assert!(stdout.contains("Key 3: attack at dawn"));
+    assert!(!stdout.contains("Key 1:"));
+    assert!(!stdout.contains("Key 4:"));
+}
+
+#[test]
+fn test_in_place() {
+    let dir = std::env::temp_dir().join("crypto_cli_tool_test_in_place");
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("message.txt");
+    std::fs::write(&path, "attack at dawn").unwrap();
+    let output = Command::new(env!("CARGO_BIN_EXE_crypto_cli_tool"))
+        .args([
+            "-a",
+            "caesar",
+            "-d",
+            "encrypt",
+            "-k",
+            "3",
+            "--in-place",
+            "-i",
+        ])
+        .arg(&path)
+        .output()
+        .expect("failed to run binary");
+    let contents = std::fs::read_to_string(&path).unwrap();
+    let leftover = std::fs::read_dir(&dir).unwrap().count();
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert!(output.status.success());
+    assert_eq!(contents, "dwwdfn dw gdzq");
+    assert_eq!(leftover, 1);
+}
+
+#[test]
+fn test_in_place_binary_file() {
+    let dir = std::env::temp_dir().join("crypto_cli_tool_test_in_place_binary");
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("bin.dat");
+    std::fs::write(&path, [0x00, 0xff, 0xfe, 0x10]).unwrap();
+    let in_place = |direction: &str| {
+        Command::new(env!("CARGO_BIN_EXE_crypto_cli_tool"))
+            .args([
+                "-a",
+                "xor",
+                "-d",
+                direction,
+                "-k",
+                "key",
+                "--in-place",
+                "-i",
+            ])
+            .arg(&path)
+            .output()
+            .expect("failed to run binary")
+    };
+    let encrypted = in_place("encrypt");
+    let encrypted_contents = std::fs::read(&path).unwrap();
+    let decrypted = in_place("decrypt");
+    let decrypted_contents = std::fs::read(&path).unwrap();
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert!(encrypted.status.success(), "{:?}", encrypted);
+    assert!(decrypted.status.success(), "{:?}", decrypted);
+    assert_eq!(encrypted_contents, [0x6b, 0x9a, 0x87, 0x7b]);
+    assert_eq!(decrypted_contents, [0x00, 0xff, 0xfe, 0x10]);
+}
+
+#[test]
+fn test_in_place_requires_input_file() {
+    let output = Command::new(env!("CARGO_BIN_EXE_crypto_cli_tool"))
+        .args([
+            "-a",
+            "caesar",
+            "-d",
+            "encrypt",
+            "-k",
+            "3",
+            "--in-place",
+            "hello",
+        ])
+        .output()
+        .expect("failed to run binary");
+
+    assert_eq!(output.status.code(), Some(1));
+}
+
+#[test]
+fn test_normalize_nfc() {
+    let decomposed = run(&[
+        "-a",
+        "caesar",
+        "-d",
+        "encrypt",
+        "-k",
+        "3",
+        "--normalize",
+        "nfc",
+        "caf\u{65}\u{301}",
+    ]);
+    let composed = run(&[
+        "-a",
+        "caesar",
+        "-d",
+        "encrypt",
+        "-k",
+        "3",
+        "--normalize",
+        "nfc",
+        "caf\u{e9}",
+    ]);
+
+    assert_eq!(decomposed, composed);
+    assert!(composed.contains("Output: fdi\u{e9}"));
+}
+
+#[test]
+fn test_period() {
+    let five = run(&[
+        "-a",
+        "bifid",
+        "-d",
+        "encrypt",
+        "-k",
+        "keyword",
+        "--period",
+        "5",
+        "meet me by the old oak",
+    ]);
+    let seven = run(&[
+        "-a",
+        "bifid",
+        "-d",
+        "encrypt",
+        "-k",
+        "keyword",
+        "--period",
+        "7",
+        "meet me by the old oak",
+    ]);
+    let (unused, stderr) = run_failure(&[
+        "-a", "caesar", "-d", "encrypt", "-k", "3", "--period", "5", "hello",
+    ]);
+
+    assert_ne!(five, seven);
+    assert_eq!(unused, Some(1));
+    assert!(stderr.contains("--period is unused"));
+}
+
+#[test]
+fn test_show_schedule() {
+    let standard = run(&[
+        "-a",
+        "columnar",
+        "-d",
+        "encrypt",
+        "-k",
+        "zebra",
+        "--show-schedule",
+        "hello",
+    ]);
+    let myszkowski = run(&[
+        "-a",
+        "columnar",
+        "--variant",
+        "myszkowski",
+        "-d",
+        "encrypt",
+        "-k",
+        "tomato",
+        "--show-schedule",
+        "hello",
+    ]);
+
+    assert!(standard.contains("Column order for ZEBRA: 5 3 2 4 1\n"));
+    assert!(myszkowski.contains("Column order for TOMATO: 4 3 2 1 4 3\n"));
+}
+
+#[test]
+fn test_broken_pipe_exits_cleanly() {
+    use std::io::Read;
+    use std::process::Stdio;
+
+    let path = std::env::temp_dir().join("crypto_cli_tool_test_broken_pipe.txt");
+    std::fs::write(&path, "attack at dawn ".repeat(200_000)).unwrap();
+    let mut child = Command::new(env!("CARGO_BIN_EXE_crypto_cli_tool"))
+        .args(["-a", "caesar", "-d", "encrypt", "-k", "3", "-i"])
+        .arg(&path)
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .expect("failed to run binary");
+    let mut head = [0; 16];
+    child.stdout.take().unwrap().read_exact(&mut head).unwrap();
+    let output = child.wait_with_output().unwrap();
+    std::fs::remove_file(&path).unwrap();
+    let stderr = String::from_utf8(output.stderr).unwrap();
+
+    assert_eq!(&head, b"Algorithm: Caesa");
+    assert!(output.status.success(), "{}", stderr);
+    assert!(!stderr.contains("panicked"));
+}
+
+#[test]
+fn test_selftest_passes_every_cipher() {
+    let stdout = run(&["selftest"]);
+    let (results, summary) = stdout.trim_end().rsplit_once('\n').unwrap();
+    let count = results.lines().count();
+
+    assert!(results.lines().all(|line| line.contains(": pass (")));
+    assert!(results.contains("VIC cipher: pass"));
+    assert_eq!(summary, format!("{}/{} ciphers passed", count, count));
+}
+
+#[test]
+fn test_key_from_env() {
+    let output = Command::new(env!("CARGO_BIN_EXE_crypto_cli_tool"))
+        .args(["-a", "vigenere", "-d", "encrypt", "--key-from-env"])
+        .args(["CRYPTO_CLI_TOOL_TEST_KEY", "attack at dawn"])
+        .env("CRYPTO_CLI_TOOL_TEST_KEY", "lemon")
+        .output()
+        .expect("failed to run binary");
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    assert!(output.status.success());
+    assert!(stdout.contains("Algorithm: Vigenère cipher"));
+    assert!(stdout.contains("Output: lxfopv mh oeib"));
+}
+
+#[test]
+fn test_key_from_env_missing_or_empty() {
+    let missing = run_failure(&[
+        "-a",
+        "caesar",
+        "-d",
+        "encrypt",
+        "--key-from-env",
+        "CRYPTO_CLI_TOOL_TEST_UNSET_KEY",
+        "hello",
+    ]);
+    let empty = Command::new(env!("CARGO_BIN_EXE_crypto_cli_tool"))
+        .args(["-a", "caesar", "-d", "encrypt", "--key-from-env"])
+        .args(["CRYPTO_CLI_TOOL_TEST_EMPTY_KEY", "hello"])
+        .env("CRYPTO_CLI_TOOL_TEST_EMPTY_KEY", "")
+        .output()
+        .expect("failed to run binary");
+    let conflict = run_failure(&[
+        "-a",
+        "caesar",
+        "-d",
+        "encrypt",
+        "-k",
+        "3",
+        "--key-from-env",
+        "HOME",
+        "hello",
+    ]);
+
+    assert_eq!(missing.0, Some(1));
+    assert!(missing
+        .1
+        .contains("Environment variable CRYPTO_CLI_TOOL_TEST_UNSET_KEY is not set."));
+    assert_eq!(empty.status.code(), Some(1));
+    assert!(String::from_utf8(empty.stderr)
+        .unwrap()
+        .contains("Environment variable CRYPTO_CLI_TOOL_TEST_EMPTY_KEY is empty."));
+    assert_eq!(conflict.0, Some(1));
+}
+
+#[test]
+fn test_expect_file() {
+    let matching = std::env::temp_dir().join("crypto_cli_tool_test_expect_match.txt");
+    let mismatching = std::env::temp_dir().join("crypto_cli_tool_test_expect_mismatch.txt");
+    std::fs::write(&matching, "khoor zruog\n").unwrap();
+    std::fs::write(&mismatching, "khoor zrxog\n").unwrap();
+    let run_expect = |path: &std::path::Path| {
+        Command::new(env!("CARGO_BIN_EXE_crypto_cli_tool"))
+            .args(["-a", "caesar", "-d", "encrypt", "-k", "3", "--expect-file"])
+            .arg(path)
+            .arg("hello world")
+            .output()
+            .expect("failed to run binary")
+    };
+    let matched = run_expect(&matching);
+    let mismatched = run_expect(&mismatching);
+    std::fs::remove_file(&matching).unwrap();
+    std::fs::remove_file(&mismatching).unwrap();
+    let stderr = String::from_utf8(mismatched.stderr).unwrap();
+
+    assert!(matched.status.success());
+    assert!(matched.stdout.is_empty());
+    assert_eq!(mismatched.status.code(), Some(5));
+    assert!(mismatched.stdout.is_empty());
+    assert!(stderr.contains("first difference at line 1, column 9"));
+}
+
+#[test]
+fn test_no_color_output_is_plain() {
+    let analyze = run(&["analyze", "--no-color", "attack at dawn"]);
+    let output = Command::new(env!("CARGO_BIN_EXE_crypto_cli_tool"))
+        .args(["-a", "vigenere", "-d", "encrypt", "-k", "lemon", "-vv"])
+        .args(["--no-color", "attack at dawn"])
+        .output()
+        .expect("failed to run binary");
+    let stderr = String::from_utf8(output.stderr).unwrap();
+
+    assert!(analyze.contains("a: 4 (33.33%)"));
+    assert!(!analyze.contains('\x1b'));
+    assert!(stderr.contains("info: Vigenère: shifting letters by the repeating key"));
+    assert!(!stderr.contains('\x1b'));
+}
+
+#[test]
+fn test_by_line_preserves_line_structure() {
+    let dir = std::env::temp_dir().join("crypto_cli_tool_test_by_line");
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("names.txt");
+    std::fs::write(&path, "alice\r\n\nbob\n\r\ncarol\r\n").unwrap();
+    let output = Command::new(env!("CARGO_BIN_EXE_crypto_cli_tool"))
+        .args(["-a", "vigenere", "-d", "encrypt", "-k", "key", "--by-line"])
+        .args(["--in-place", "-i"])
+        .arg(&path)
+        .output()
+        .expect("failed to run binary");
+    let contents = std::fs::read(&path).unwrap();
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    assert!(output.status.success());
+    assert_eq!(contents, b"kpgmi\r\n\nlsz\n\r\nmepyp\r\n");
+}
+
+#[test]
+fn test_key_stretch_to() {
+    let output_of = |stdout: String| {
+        stdout
+            .lines()
+            .find_map(|line| line.strip_prefix("Output: "))
+            .unwrap()
+            .to_string()
+    };
+    let stretch = ["-a", "vigenere", "-k", "lemon", "--key-stretch-to", "16"];
+    let first = output_of(run(
+        &[&stretch[..], &["-d", "encrypt", "attack at dawn"]].concat()
+    ));
+    let second = output_of(run(
+        &[&stretch[..], &["-d", "encrypt", "attack at dawn"]].concat()
+    ));
+    let repeated = output_of(run(&[
+        "-a",
+        "vigenere",
+        "-k",
+        "lemonlemonlemonl",
+        "-d",
+        "encrypt",
+        "attack at dawn",
+    ]));
+    let decrypted = output_of(run(&[&stretch[..], &["-d", "decrypt", &first]].concat()));
+
+    assert_eq!(first, second);
+    assert_ne!(first, repeated);
+    assert_eq!(decrypted, "attack at dawn");
+}
+
+#[test]
+fn test_job_file_matches_cli() {
+    let dir = std::env::temp_dir().join("crypto_cli_tool_test_job");
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("plain.txt"), "attack at dawn").unwrap();
+    std::fs::write(
+        dir.join("job.toml"),
+        "algorithm = \"caesar,vigenere\"\n\
+         direction = \"encrypt\"\n\
+         key = [3, \"lemon\"]\n\
+         input_file = \"plain.txt\"\n\
+         output_file = \"cipher.txt\"\n",
+    )
+    .unwrap();
+    let job = Command::new(env!("CARGO_BIN_EXE_crypto_cli_tool"))
+        .arg("--job")
+        .arg(dir.join("job.toml"))
+        .output()
+        .expect("failed to run binary");
+    let job_output = std::fs::read_to_string(dir.join("cipher.txt")).unwrap();
+    let overridden = Command::new(env!("CARGO_BIN_EXE_crypto_cli_tool"))
+        .arg("--job")
+        .arg(dir.join("job.toml"))
+        .args(["-k", "4", "-k", "lemon", "--output-file"])
+        .arg(dir.join("overridden.txt"))
+        .output()
+        .expect("failed to run binary");
+    let overridden_output = std::fs::read_to_string(dir.join("overridden.txt")).unwrap();
+    std::fs::remove_dir_all(&dir).unwrap();
+    let cli = run(&[
+        "-a",
+        "caesar,vigenere",
+        "-d",
+        "encrypt",
+        "-k",
+        "3",
+        "-k",
+        "lemon",
+        "attack at dawn",
+    ]);
+    let cli_overridden = run(&[
+        "-a",
+        "caesar,vigenere",
+        "-d",
+        "encrypt",
+        "-k",
+        "4",
+        "-k",
+        "lemon",
+        "attack at dawn",
+    ]);
+
+    assert!(job.status.success());
+    assert!(overridden.status.success());
+    assert!(cli.contains(&format!("Output: {}\n", job_output)));
+    assert!(cli_overridden.contains(&format!("Output: {}\n", overridden_output)));
+    assert_ne!(job_output, overridden_output);
+}
+
+#[test]
+fn test_invalid_job_file() {
+    let path = std::env::temp_dir().join("crypto_cli_tool_test_invalid_job.toml");
+    std::fs::write(&path, "algorithm = \"enigma\"\ndirection = \"encrypt\"\n").unwrap();
+    let output = Command::new(env!("CARGO_BIN_EXE_crypto_cli_tool"))
+        .arg("--job")
+        .arg(&path)
+        .arg("hello")
+        .output()
+        .expect("failed to run binary");
+    std::fs::remove_file(&path).unwrap();
+    let stderr = String::from_utf8(output.stderr).unwrap();
+
+    assert_eq!(output.status.code(), Some(1));
+    assert!(stderr.contains("Invalid job file"));
+}
+
+#[cfg(feature = "qr")]
+#[test]
+fn test_format_qr() {
+    let stdout = run(&[
+        "-a", "caesar", "-d", "encrypt", "-k", "3", "--format", "qr", "hello",
+    ]);
+    let code: Vec<&str> = stdout
+        .lines()
+        .skip_while(|line| *line != "Output:")
+        .skip(1)
+        .take_while(|line| !line.is_empty())
+        .collect();
+
+    assert!(code.len() > 10);
+    assert!(code
+        .iter()
+        .all(|line| line.chars().all(|c| " █▀▄".contains(c))));
+}
+
+#[cfg(not(feature = "qr"))]
+#[test]
+fn test_format_qr_without_feature() {
+    let (code, stderr) = run_failure(&[
+        "-a", "caesar", "-d", "encrypt", "-k", "3", "--format", "qr", "hello",
+    ]);
+
+    assert_eq!(code, Some(1));
+    assert!(stderr.contains("--format qr needs the `qr` feature"));
+}
+
+/// Encrypts with `--print-decrypt-cmd`, then runs the printed command
+/// through a shell and returns the command and its stdout.
+fn run_decrypt_cmd(args: &[&str]) -> (String, String) {
+    let encrypted = Command::new(env!("CARGO_BIN_EXE_crypto_cli_tool"))
+        .args(args)
+        .arg("--print-decrypt-cmd")
+        .output()
+        .expect("failed to run binary");
+    assert!(encrypted.status.success(), "{:?} failed", args);
+    let stderr = String::from_utf8(encrypted.stderr).unwrap();
+    let command = stderr
+        .lines()
+        .find_map(|line| line.strip_prefix("Decrypt with: crypto_cli_tool "))
+        .unwrap()
+        .to_string();
+    let decrypted = Command::new("sh")
+        .arg("-c")
+        .arg(format!(
+            "\"{}\" {}",
+            env!("CARGO_BIN_EXE_crypto_cli_tool"),
+            command
+        ))
+        .output()
+        .expect("failed to run shell");
+
+    assert!(decrypted.status.success(), "{} failed", command);
+    (command, String::from_utf8(decrypted.stdout).unwrap())
+}
+
+#[test]
+fn test_print_decrypt_cmd_recovers_plaintext() {
+    let plaintext = "it's time to attack at dawn";
+    let (command, stdout) = run_decrypt_cmd(&[
+        "-a",
+        "caesar,columnar",
+        "-d",
+        "encrypt",
+        "-k",
+        "50%",
+        "-k",
+        "zebra",
+        plaintext,
+    ]);
+    let (phonetic_command, phonetic) = run_decrypt_cmd(&[
+        "-a",
+        "caesar",
+        "-k",
+        "3",
+        "--phonetic",
+        "-d",
+        "encrypt",
+        "abc",
+    ]);
+    let (xor_command, xor) = run_decrypt_cmd(&[
+        "-a",
+        "xor",
+        "-k",
+        "key",
+        "--input-format",
+        "base64",
+        "--byte-format",
+        "base64",
+        "-d",
+        "encrypt",
+        "aGVsbG8=",
+    ]);
+
+    assert!(command.contains("-k 13 -k zebra"));
+    assert!(stdout.contains(&format!("Output: {}\n", plaintext)));
+    assert!(phonetic_command.contains("--phonetic"));
+    assert!(phonetic.contains("Output: ABC\n"), "{}", phonetic);
+    assert!(xor_command.contains("--input-format base64"));
+    assert!(xor.contains("Output: hello\n"), "{}", xor);
+}
+
+#[test]
+fn test_explain_key_needs_no_input() {
+    let output = run(&[
+        "-a",
+        "playfair",
+        "-d",
+        "encrypt",
+        "-k",
+        "Key1!",
+        "--explain-key",
+    ]);
+
+    assert!(output.contains("parsed as the text \"Key1!\""));
+    assert!(output.contains("K E Y A B"));
+    assert!(!output.contains("Output:"));
+}
+
+#[test]
+fn test_vigenere_wildcard_key() {
+    let encrypted = run(&[
+        "-a",
+        "vigenere",
+        "-d",
+        "encrypt",
+        "-k",
+        "lemon",
+        "meet me at the old bridge at noon and bring the documents",
+    ]);
+    let ciphertext = encrypted
+        .lines()
+        .find_map(|line| line.strip_prefix("Output: "))
+        .unwrap();
+    let output = run(&["-a", "vigenere", "-d", "decrypt", "-k", "le?on", ciphertext]);
+    let (code, _) = run_failure(&["-a", "vigenere", "-d", "decrypt", "-k", "????n", ciphertext]);
+
+    assert!(output.contains("1. Key lemon"));
+    assert_eq!(code, Some(2));
+}
+
+#[test]
+fn test_segment_brute_force_output() {
+    let path = std::env::temp_dir().join("crypto_cli_tool_segment_words.txt");
+    std::fs::write(&path, "hello\nworld\nhell\nlow\n").unwrap();
+
+    let output = run(&[
+        "-a",
+        "caesar",
+        "-d",
+        "decrypt",
+        "-b",
+        "--dictionary",
+        path.to_str().unwrap(),
+        "--segment",
+        "khoorzruog",
+    ]);
+    let decrypted = run(&[
+        "-a",
+        "caesar",
+        "-d",
+        "decrypt",
+        "-k",
+        "3",
+        "--dictionary",
+        path.to_str().unwrap(),
+        "--segment",
+        "khoorzruog",
+    ]);
+    std::fs::remove_file(&path).unwrap();
+
+    assert!(output.contains("Key 3: hello world\n"));
+    assert!(decrypted.contains("Output: hello world"));
+}
+
+#[test]
+fn test_reverse_alphabet() {
+    let encrypted = run(&[
+        "-a",
+        "caesar",
+        "-d",
+        "encrypt",
+        "-k",
+        "3",
+        "--reverse-alphabet",
+        "Attack at dawn",
+    ]);
+    let decrypted = run(&[
+        "-a",
+        "caesar",
+        "-d",
+        "decrypt",
+        "-k",
+        "3",
+        "--reverse-alphabet",
+        "Cjjcas cj zcgp",
+    ]);
+    let atbash = run(&[
+        "-a",
+        "caesar",
+        "-d",
+        "encrypt",
+        "-k",
+        "0",
+        "--reverse-alphabet",
+        "wizard",
+    ]);
+
+    assert!(encrypted.contains("Output: Cjjcas cj zcgp"));
+    assert!(decrypted.contains("Output: Attack at dawn"));
+    assert!(atbash.contains("Output: draziw"));
+}
+
+#[test]
+fn test_examples_subcommand() {
+    let output = run(&["examples"]);
+
+    assert!(output
+        .contains("# Caesar cipher\ncrypto_cli_tool -a caesar -d encrypt -k 3 ATTACKATDAWN\n"));
+    assert!(output.contains("# ROT18\ncrypto_cli_tool -a rot18 -d encrypt ATTACKATDAWN\n"));
+    assert!(output.contains(
+        "# Gromark cipher\ncrypto_cli_tool -a gromark -d encrypt -k 23452 --key2 enigma"
+    ));
+    assert!(!output.contains("selftest"));
+}
+
+#[test]
+fn test_fail_on_unencrypted() {
+    let letters = run(&[
+        "-a",
+        "caesar",
+        "-d",
+        "encrypt",
+        "-k",
+        "3",
+        "--fail-on-unencrypted",
+        "helloworld",
+    ]);
+    let (code, stderr) = run_failure(&[
+        "-a",
+        "caesar",
+        "-d",
+        "encrypt",
+        "-k",
+        "3",
+        "--fail-on-unencrypted",
+        "hello, world",
+    ]);
+
+    assert!(letters.contains("Output: khoorzruog"));
+    assert_eq!(code, Some(4));
+    assert!(stderr.contains("',' at 5, ' ' at 6"));
+}
+
+#[test]
+fn test_fail_on_unencrypted_fractionating() {
+    let cases = [
+        &["-a", "bifid", "-k", "key"][..],
+        &["-a", "trifid", "-k", "abcdefghijklmnopqrstuvwxyz."][..],
+    ];
+
+    for algorithm in cases {
+        let (code, stderr) = run_failure(
+            &[
+                algorithm,
+                &["-d", "encrypt", "--fail-on-unencrypted", "hello, world"],
+            ]
+            .concat(),
+        );
+
+        assert_eq!(code, Some(4), "{:?}", algorithm);
+        assert!(stderr.contains("',' at 5, ' ' at 6"), "{}", stderr);
+    }
+}
+
+#[test]
+fn test_large_search_needs_confirmation() {
+    let ciphertext = "xicx xi mx xli sph fvmhki";
+    let estimate = run(&[
+        "-a",
+        "vigenere",
+        "-d",
+        "decrypt",
+        "-k",
+        "l???n",
+        "--benchmark-keyspace",
+        ciphertext,
+    ]);
+    let (code, stderr) =
+        run_failure(&["-a", "vigenere", "-d", "decrypt", "-k", "l???n", ciphertext]);
+    let confirmed = run(&[
+        "-a", "vigenere", "-d", "decrypt", "-k", "l???n", "--yes", ciphertext,
+    ]);
+    let small = run(&["-a", "caesar", "-d", "decrypt", "-b", ciphertext]);
+
+    assert!(estimate.contains("Estimated work: 17576 candidate keys"));
+    assert!(!estimate.contains("1. Key"));
+    assert_eq!(code, Some(1));
+    assert!(stderr.contains("pass --yes"));
+    assert!(confirmed.contains("1. Key"));
+    assert!(small.contains("Key 25:"));
+}
+
+#[test]
+fn test_outer_algorithm_round_trip() {
+    let plaintext = "attack at dawn";
+    let layered = run(&[
+        "-a",
+        "vigenere",
+        "-d",
+        "encrypt",
+        "-k",
+        "lemon",
+        "--outer-algorithm",
+        "columnar",
+        "--outer-key",
+        "zebra",
+        plaintext,
+    ]);
+    let inner = run(&["-a", "vigenere", "-d", "encrypt", "-k", "lemon", plaintext]);
+    let inner = inner
+        .lines()
+        .find_map(|line| line.strip_prefix("Output: "))
+        .unwrap();
+    let chained = run(&["-a", "columnar", "-d", "encrypt", "-k", "zebra", inner]);
+    let ciphertext = layered
+        .lines()
+        .find_map(|line| line.strip_prefix("Output: "))
+        .unwrap();
+    let decrypted = run(&[
+        "-a",
+        "vigenere",
+        "-d",
+        "decrypt",
+        "-k",
+        "lemon",
+        "--outer-algorithm",
+        "columnar",
+        "--outer-key",
+        "zebra",
+        ciphertext,
+    ]);
+
+    assert!(chained.contains(&format!("Output: {}\n", ciphertext)));
+    assert!(decrypted.contains(&format!("Output: {}\n", plaintext)));
+}
+
+#[test]
+fn test_phonetic() {
+    let encrypted = run(&[
+        "-a",
+        "caesar",
+        "-d",
+        "encrypt",
+        "-k",
+        "3",
+        "--phonetic",
+        "hi 5",
+    ]);
+    let decrypted = run(&[
+        "-a",
+        "caesar",
+        "-d",
+        "decrypt",
+        "-k",
+        "3",
+        "--phonetic",
+        "Kilo Lima / Five",
+    ]);
+    let (code, stderr) = run_failure(&[
+        "-a",
+        "caesar",
+        "-d",
+        "decrypt",
+        "-k",
+        "3",
+        "--phonetic",
+        "Kilo Llama",
+    ]);
+
+    assert!(encrypted.contains("Output: Kilo Lima / Five"));
+    assert!(decrypted.contains("Output: HI 5"));
+    assert_eq!(code, Some(4));
+    assert!(stderr.contains("unknown phonetic word \"Llama\""));
+}
+
+#[test]
+fn test_degenerate_key_note() {
+    let output = Command::new(env!("CARGO_BIN_EXE_crypto_cli_tool"))
+        .args(["-a", "vigenere", "-d", "encrypt", "-k", "k", "hello"])
+        .output()
+        .expect("failed to run binary");
+
+    assert!(output.status.success());
+    assert!(String::from_utf8(output.stderr)
+        .unwrap()
+        .contains("Note: the Vigenère cipher key \"k\" has 1 usable character(s)"));
+}
+
+#[test]
+fn test_preview_matches_start_of_full_encryption() {
+    let plaintext = "the quick brown fox jumps over the lazy dog";
+    let full = run(&["-a", "caesar", "-k", "7", "-d", "encrypt", plaintext]);
+    let full = full
+        .lines()
+        .find_map(|line| line.strip_prefix("Output: "))
+        .unwrap()
+        .to_string();
+
+    let preview = run(&[
+        "-a",
+        "caesar",
+        "-k",
+        "7",
+        "-d",
+        "encrypt",
+        "--preview",
+        "12",
+        plaintext,
+    ]);
+
+    assert_eq!(
+        preview,
+        format!("Preview of the first 12 characters:\n{}\n", &full[..12])
+    );
+    assert!(!preview.contains("Output:"));
+}
+
+#[test]
+fn test_byte_caesar_round_trip_through_hex() {
+    let encrypted = run(&[
+        "-a",
+        "byte-caesar",
+        "-d",
+        "encrypt",
+        "-k",
+        "200",
+        "--input-format",
+        "hex",
+        "0038ff7f",
+    ]);
+    let decrypted = run(&[
+        "-a",
+        "byte-caesar",
+        "-d",
+        "decrypt",
+        "-k",
+        "200",
+        "--input-format",
+        "hex",
+        "c800c747",
+    ]);
+
+    assert!(encrypted.contains("Output: c800c747\n"));
+    // The decrypted bytes aren't valid UTF-8, so they are shown as hex
+    assert!(decrypted.contains("Output: 0038ff7f\n"));
+}
+
+#[test]
+fn test_identity_key_warning() {
+    let stderr = |key: &str| {
+        let output = Command::new(env!("CARGO_BIN_EXE_crypto_cli_tool"))
+            .args(["-a", "caesar", "-d", "encrypt", "-k", key, "attack at dawn"])
+            .output()
+            .expect("failed to run binary");
+        assert!(output.status.success());
+        String::from_utf8(output.stderr).unwrap()
+    };
+
+    assert!(stderr("26").contains("Warning: the output is identical to the input"));
+    assert!(!stderr("3").contains("identical"));
+}
+
+#[test]
+fn test_brute_force_key_only() {
+    let output = run(&[
+        "-a",
+        "caesar",
+        "-d",
+        "decrypt",
+        "-b",
+        "--key-only",
+        "wkh vhfuhw phhwlqj lv dw wkh roq eulgjh dw qrrq wrpruurz",
+    ]);
+
+    assert_eq!(output, "3\n");
+}
+
+#[test]
+fn test_odd_length_playfair_ciphertext() {
+    let output = Command::new(env!("CARGO_BIN_EXE_crypto_cli_tool"))
+        .args(["-a", "playfair", "-d", "decrypt", "-k", "keyword", "GYIZS"])
+        .output()
+        .expect("failed to run binary");
+
+    assert!(output.status.success());
+    assert!(String::from_utf8(output.stderr)
+        .unwrap()
+        .contains("Warning: Playfair ciphertext has an odd number of letters (5)"));
+
+    let (code, stderr) = run_failure(&[
+        "-a", "playfair", "-d", "decrypt", "-k", "keyword", "--strict", "GYIZS",
+    ]);
+    assert_eq!(code, Some(4));
+    assert!(stderr.contains("odd number of letters"));
+}
+
+#[test]
+fn test_direction_subcommands_match_flag() {
+    for (direction, input) in [("encrypt", "attack at dawn"), ("decrypt", "dwwdfn dw gdzq")] {
+        let flag = run(&["-a", "caesar", "-k", "3", "-d", direction, input]);
+        let subcommand = run(&[direction, "-a", "caesar", "-k", "3", input]);
+
+        assert_eq!(subcommand, flag);
+    }
+
+    let (code, stderr) =
+        run_failure(&["encrypt", "-d", "decrypt", "-a", "caesar", "-k", "3", "hi"]);
+    assert_eq!(code, Some(1));
+    assert!(stderr.contains("leave out --direction"));
+}
+
+#[test]
+fn test_swap_case_transform_with_caesar() {
+    let encrypted = run(&[
+        "encrypt",
+        "-a",
+        "caesar",
+        "-k",
+        "3",
+        "--transform",
+        "swap-case",
+        "Attack at Dawn",
+    ]);
+    let decrypted = run(&[
+        "decrypt",
+        "-a",
+        "caesar",
+        "-k",
+        "3",
+        "--transform",
+        "swap-case",
+        "dWWDFN DW gDZQ",
+    ]);
+
+    assert!(encrypted.contains("Output: dWWDFN DW gDZQ\n"));
+    assert!(decrypted.contains("Output: Attack at Dawn\n"));
+}
+
+#[test]
+fn test_playfair_wordlist_brute_force() {
+    let path = std::env::temp_dir().join("crypto_cli_tool_test_playfair_wordlist.txt");
+    std::fs::write(
+        &path,
+        "apple\nkeyword\nsecret\n\nmonarchy\ncipher\nplayfair\nzebra\n",
+    )
+    .unwrap();
+    // "we are discovered save yourself and flee at once" under the key "monarchy"
+    let output = Command::new(env!("CARGO_BIN_EXE_crypto_cli_tool"))
+        .args(["-a", "playfair", "-d", "decrypt", "-b", "--wordlist"])
+        .arg(&path)
+        .arg("ugrmkcsxhmufmkbtoxgcmvatluioryepiursnael")
+        .output()
+        .expect("failed to run binary");
+    std::fs::remove_file(&path).unwrap();
+    let stdout = String::from_utf8(output.stdout).unwrap();
+
+    assert!(output.status.success());
+    assert!(stdout.contains("1. Key monarchy (score"));
+    assert!(stdout.contains("wearediscoveredsaveyourselfandflexatonce"));
+    assert!(stdout.contains("5. Key "));
+}
+
+#[test]
+fn test_playfair_merge_pair() {
+    let encrypted = run(&[
+        "-a",
+        "playfair",
+        "-d",
+        "encrypt",
+        "-k",
+        "keyword",
+        "--merge",
+        "W=V",
+        "jumpatdawn",
+    ]);
+    let ciphertext = encrypted
+        .lines()
+        .find_map(|line| line.strip_prefix("Output: "))
+        .unwrap();
+    let decrypted = run(&[
+        "-a", "playfair", "-d", "decrypt", "-k", "keyword", "--merge", "W=V", ciphertext,
+    ]);
+    let (_, stderr) = run_failure(&[
+        "-a", "playfair", "-d", "encrypt", "-k", "keyword", "--merge", "V=V", "hello",
+    ]);
+
+    assert!(decrypted.contains("Output: jumpatdavn\n"));
+    assert!(stderr.contains("cannot merge V into itself"));
+}
+
+#[test]
+fn test_digit_key_shifts_digits_apart_from_letters() {
+    let encrypted = run(&[
+        "encrypt",
+        "-a",
+        "caesar",
+        "-k",
+        "3",
+        "--digit-key",
+        "4",
+        "abc123",
+    ]);
+    let decrypted = run(&[
+        "decrypt",
+        "-a",
+        "caesar",
+        "-k",
+        "3",
+        "--digit-key",
+        "4",
+        "def567",
+    ]);
+    let letters_only = run(&["decrypt", "-a", "caesar", "-k", "3", "def567"]);
+
+    assert!(encrypted.contains("Output: def567\n"));
+    assert!(decrypted.contains("Output: abc123\n"));
+    assert!(letters_only.contains("Output: abc567\n"));
+}
+
+#[test]
+fn test_show_grid_rail_fence() {
+    let stdout = run(&[
+        "-a",
+        "railfence",
+        "-d",
+        "encrypt",
+        "-k",
+        "3",
+        "--show-grid",
+        "WEAREDISCOVERED",
+    ]);
+    let (code, stderr) = run_failure(&[
+        "-a",
+        "caesar",
+        "-d",
+        "encrypt",
+        "-k",
+        "3",
+        "--show-grid",
+        "hi",
+    ]);
+
+    assert!(stdout.contains(
+        "Rails:\n  W...E...C...R..\n  .E.R.D.S.O.E.E.\n  ..A...I...V...D\nOutput: WECRERDSOEEAIVD\n"
+    ));
+    assert_eq!(code, Some(1));
+    assert!(stderr.contains("--show-grid only applies"));
+}
+
+#[test]
+fn test_cyrillic_alphabet_round_trip() {
+    let encrypted = run(&[
+        "encrypt",
+        "-a",
+        "caesar",
+        "-k",
+        "3",
+        "--alphabet",
+        "cyrillic",
+        "Ёлка в Москве",
+    ]);
+    let decrypted = run(&[
+        "decrypt",
+        "-a",
+        "caesar",
+        "-k",
+        "3",
+        "--alphabet",
+        "cyrillic",
+        "Ионг е Псфнез",
+    ]);
+
+    assert!(
+        encrypted.contains("Output: Ионг е Псфнез\n"),
+        "{}",
+        encrypted
+    );
+    assert!(
+        decrypted.contains("Output: Ёлка в Москве\n"),
+        "{}",
+        decrypted
+    );
+}